@@ -1,14 +1,21 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::types::ReviewPr;
+use crate::types::{PrList, ReviewPr};
 
 /// Cached PR lists
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct PrCache {
     pub review_prs: Vec<ReviewPr>,
     pub my_prs: Vec<ReviewPr>,
+    /// Results of the saved search tabs, keyed by query
+    #[serde(default)]
+    pub searches: HashMap<String, Vec<ReviewPr>>,
+    /// Notifications about PRs
+    #[serde(default)]
+    pub inbox: Vec<ReviewPr>,
     /// Unix timestamp when cache was saved
     #[serde(default)]
     pub cached_at: u64,
@@ -44,8 +51,13 @@ fn cache_file_path() -> Option<PathBuf> {
     dirs::config_dir().map(|p| p.join("kensa").join("cache.json"))
 }
 
-/// Save PR lists to cache
-pub fn save_cache(review_prs: &[ReviewPr], my_prs: &[ReviewPr]) {
+/// Save PR lists (saved search results keyed by query, and the inbox) to cache
+pub fn save_cache(
+    review_prs: &[ReviewPr],
+    my_prs: &[ReviewPr],
+    searches: HashMap<String, Vec<ReviewPr>>,
+    inbox: &[ReviewPr],
+) {
     let Some(file_path) = cache_file_path() else {
         return;
     };
@@ -63,6 +75,8 @@ pub fn save_cache(review_prs: &[ReviewPr], my_prs: &[ReviewPr]) {
     let cache = PrCache {
         review_prs: review_prs.to_vec(),
         my_prs: my_prs.to_vec(),
+        searches,
+        inbox: inbox.to_vec(),
         cached_at,
     };
 
@@ -71,6 +85,15 @@ pub fn save_cache(review_prs: &[ReviewPr], my_prs: &[ReviewPr]) {
     }
 }
 
+/// Successful saved search results keyed by query, as stored by `save_cache`
+pub fn search_results<E>(queries: &[String], results: &[Result<PrList, E>]) -> HashMap<String, Vec<ReviewPr>> {
+    queries
+        .iter()
+        .zip(results)
+        .filter_map(|(query, result)| Some((query.clone(), result.as_ref().ok()?.prs.clone())))
+        .collect()
+}
+
 /// Load PR lists from cache (returns None if no cache or error)
 pub fn load_cache() -> Option<PrCache> {
     let file_path = cache_file_path()?;
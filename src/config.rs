@@ -4,6 +4,8 @@ use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
+use crate::types::{DEFAULT_HOST, GITLAB_HOST};
+
 /// RGB color representation for config
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct RgbColor {
@@ -121,6 +123,118 @@ impl Default for NavigationSettings {
     }
 }
 
+/// Optional column of the PR list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrColumn {
+    /// "draft" marker
+    Draft,
+    /// Review decision: approved, changes requested or review required
+    Review,
+    /// Combined CI state of the head commit
+    Ci,
+    /// Lines added/removed and files changed
+    Size,
+    /// Time since the last update
+    Updated,
+}
+
+/// PR list settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrListSettings {
+    /// Maximum number of PRs fetched per list query and host
+    /// Lists cut short by this cap are marked in the tab header
+    pub max_prs: usize,
+
+    /// Columns shown between the title and the author, in this order
+    pub columns: Vec<PrColumn>,
+}
+
+impl Default for PrListSettings {
+    fn default() -> Self {
+        Self {
+            max_prs: DEFAULT_MAX_PRS,
+            columns: vec![PrColumn::Draft, PrColumn::Review, PrColumn::Ci, PrColumn::Size, PrColumn::Updated],
+        }
+    }
+}
+
+/// Default cap on PRs fetched per list query
+pub const DEFAULT_MAX_PRS: usize = 300;
+
+/// GitHub API settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GithubSettings {
+    /// Backend used to talk to GitHub: "auto", "api" or "gh"
+    /// "auto" uses the native API when a token is available, otherwise the gh CLI
+    pub backend: String,
+
+    /// Personal access token for the native API
+    /// GH_TOKEN/GITHUB_TOKEN take precedence; `gh auth token` is used as a last resort
+    pub token: Option<String>,
+
+    /// Push new inline drafts to a pending review on GitHub as they are written
+    /// Drafts already on a pending review are kept in sync either way
+    pub sync_pending_review: bool,
+}
+
+impl Default for GithubSettings {
+    fn default() -> Self {
+        Self {
+            backend: "auto".to_string(),
+            token: None,
+            sync_pending_review: false,
+        }
+    }
+}
+
+/// Kind of forge software running on a host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    #[default]
+    Github,
+    Gitlab,
+    /// Gitea and its fork Forgejo share the same API
+    #[serde(alias = "forgejo")]
+    Gitea,
+}
+
+/// An additional forge host (e.g. a GitHub Enterprise Server or GitLab instance)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostConfig {
+    /// Hostname as it appears in PR URLs, e.g. "github.example.com"
+    pub host: String,
+
+    /// Forge software on this host: "github" (default), "gitlab" or "gitea" (also "forgejo")
+    #[serde(default)]
+    pub kind: ForgeKind,
+
+    /// Token for this host
+    /// GitHub falls back to GH_ENTERPRISE_TOKEN, then `gh auth token --hostname`
+    /// GitLab falls back to GITLAB_TOKEN, Gitea/Forgejo to GITEA_TOKEN
+    #[serde(default)]
+    pub token: Option<String>,
+
+    /// API base URL override
+    /// Defaults to https://<host>/api/v3 (GitHub), /api/v4 (GitLab) or /api/v1 (Gitea/Forgejo)
+    #[serde(default)]
+    pub api_url: Option<String>,
+}
+
+/// A saved search shown as an extra PR list tab
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabConfig {
+    /// Tab title
+    pub name: String,
+
+    /// Search query in the host's search syntax, e.g. "repo:org/api label:security"
+    /// Only open PRs are listed
+    pub query: String,
+}
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -144,6 +258,22 @@ pub struct Config {
     /// Navigation settings
     #[serde(default)]
     pub navigation: NavigationSettings,
+
+    /// PR list settings
+    #[serde(default)]
+    pub pr_list: PrListSettings,
+
+    /// GitHub API settings
+    #[serde(default)]
+    pub github: GithubSettings,
+
+    /// Additional hosts besides github.com
+    #[serde(default)]
+    pub hosts: Vec<HostConfig>,
+
+    /// Saved searches shown as extra PR list tabs
+    #[serde(default)]
+    pub tabs: Vec<TabConfig>,
 }
 
 /// Language-specific configuration
@@ -162,6 +292,10 @@ impl Default for Config {
             display: DisplaySettings::default(),
             colors: DiffColors::default(),
             navigation: NavigationSettings::default(),
+            pr_list: PrListSettings::default(),
+            github: GithubSettings::default(),
+            hosts: Vec::new(),
+            tabs: Vec::new(),
         }
     }
 }
@@ -319,6 +453,76 @@ collapse_folders_by_default = false
 
 # Show confirmation dialog before quitting (q/Esc)
 confirm_quit = true
+
+# =============================================================================
+# PR LIST SETTINGS
+# =============================================================================
+
+[pr_list]
+# Maximum number of PRs fetched per list (and host); all pages are followed up to this cap
+# Lists cut short by the cap show a "+" after their count
+max_prs = 300
+
+# Columns shown between the title and the author, in this order (remove any to hide them):
+#   "draft", "review" (approved/changes/review), "ci", "size" (+added -removed, files), "updated"
+columns = ["draft", "review", "ci", "size", "updated"]
+
+# =============================================================================
+# GITHUB SETTINGS
+# =============================================================================
+
+[github]
+# Backend used to talk to GitHub:
+#   - "auto": native API when a token is available, otherwise the gh CLI (default)
+#   - "api":  always use the native API (requires a token)
+#   - "gh":   always shell out to the gh CLI
+backend = "auto"
+
+# Personal access token for the native API
+# Resolution order: GH_TOKEN, GITHUB_TOKEN, this setting, then `gh auth token`
+# token = "ghp_..."
+
+# Push new inline drafts to your pending review on GitHub as you write them,
+# so they show up (as pending) in the browser and survive switching machines.
+# An existing pending review is always loaded, and drafts on it stay in sync.
+sync_pending_review = false
+
+# =============================================================================
+# ADDITIONAL HOSTS
+# =============================================================================
+
+# GitHub Enterprise Server and GitLab instances, in addition to github.com
+# PR lists from all hosts are merged into the PR list screen
+# [[hosts]]
+# host = "github.example.com"
+# token = "ghp_..."                              # or GH_ENTERPRISE_TOKEN / gh auth login --hostname
+# api_url = "https://github.example.com/api/v3"  # default
+#
+# [[hosts]]
+# host = "gitlab.com"                            # merge request URLs from gitlab.com open without this
+# kind = "gitlab"
+# token = "glpat-..."                            # or GITLAB_TOKEN
+# api_url = "https://gitlab.com/api/v4"          # default
+#
+# [[hosts]]
+# host = "git.example.com"
+# kind = "forgejo"                               # or "gitea"
+# token = "..."                                  # or GITEA_TOKEN
+# api_url = "https://git.example.com/api/v1"     # default
+
+# =============================================================================
+# SAVED SEARCHES
+# =============================================================================
+
+# Extra PR list tabs, after "For Review", "My PRs" and "Inbox" (keys 4-9, or Tab to cycle)
+# The query uses GitHub search syntax and is run on every GitHub host; only open PRs are listed
+# [[tabs]]
+# name = "Security"
+# query = "org:example label:security"
+#
+# [[tabs]]
+# name = "API drafts"
+# query = "repo:example/api draft:true"
 "#.to_string()
     }
 
@@ -432,6 +636,61 @@ confirm_quit = true
     pub fn is_split_view_default(&self) -> bool {
         self.display.default_view_mode.to_lowercase() == "split"
     }
+
+    /// All hosts to fetch PR lists from: github.com followed by configured hosts
+    pub fn forge_hosts(&self) -> Vec<String> {
+        let mut hosts = vec![DEFAULT_HOST.to_string()];
+        for entry in &self.hosts {
+            let host = entry.host.trim().to_lowercase();
+            if !host.is_empty() && !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        hosts
+    }
+
+    /// Hosts accepted in PR URLs with their forge kind
+    /// gitlab.com is always accepted; it only joins the PR lists when configured
+    pub fn url_hosts(&self) -> Vec<(String, ForgeKind)> {
+        let mut hosts: Vec<(String, ForgeKind)> = self
+            .forge_hosts()
+            .into_iter()
+            .map(|host| {
+                let kind = self.host_kind(&host);
+                (host, kind)
+            })
+            .collect();
+        if !hosts.iter().any(|(h, _)| h == GITLAB_HOST) {
+            hosts.push((GITLAB_HOST.to_string(), ForgeKind::Gitlab));
+        }
+        hosts
+    }
+
+    /// Forge kind of a host: the configured kind, otherwise inferred from well-known hosts
+    pub fn host_kind(&self, host: &str) -> ForgeKind {
+        match self.host_config(host) {
+            Some(entry) => entry.kind,
+            None if host.eq_ignore_ascii_case(GITLAB_HOST) => ForgeKind::Gitlab,
+            None => ForgeKind::Github,
+        }
+    }
+
+    /// Get the configuration for a non-default host
+    pub fn host_config(&self, host: &str) -> Option<&HostConfig> {
+        self.hosts
+            .iter()
+            .find(|h| h.host.trim().eq_ignore_ascii_case(host))
+    }
+
+    /// Check if the gh CLI backend is forced
+    pub fn is_gh_backend_forced(&self) -> bool {
+        self.github.backend.to_lowercase() == "gh"
+    }
+
+    /// Check if the native API backend is forced
+    pub fn is_api_backend_forced(&self) -> bool {
+        self.github.backend.to_lowercase() == "api"
+    }
 }
 
 #[cfg(test)]
@@ -663,6 +922,134 @@ confirm_quit = false
         assert!(config.is_split_view_default());
     }
 
+    #[test]
+    fn test_pr_list_settings() {
+        assert_eq!(Config::default().pr_list.max_prs, DEFAULT_MAX_PRS);
+
+        let config: Config = toml::from_str("[pr_list]\nmax_prs = 50\n").unwrap();
+        assert_eq!(config.pr_list.max_prs, 50);
+        assert_eq!(config.pr_list.columns.len(), 5);
+
+        let config: Config = toml::from_str("[pr_list]\ncolumns = [\"ci\", \"review\"]\n").unwrap();
+        assert_eq!(config.pr_list.columns, vec![PrColumn::Ci, PrColumn::Review]);
+    }
+
+    #[test]
+    fn test_parse_saved_search_tabs() {
+        assert!(Config::default().tabs.is_empty());
+
+        let toml_str = r#"
+[[tabs]]
+name = "Security"
+query = "org:example label:security"
+
+[[tabs]]
+name = "Drafts"
+query = "repo:example/api draft:true"
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.tabs.len(), 2);
+        assert_eq!(config.tabs[0].name, "Security");
+        assert_eq!(config.tabs[0].query, "org:example label:security");
+        assert_eq!(config.tabs[1].name, "Drafts");
+    }
+
+    #[test]
+    fn test_github_settings_defaults() {
+        let config = Config::default();
+        assert_eq!(config.github.backend, "auto");
+        assert!(config.github.token.is_none());
+        assert!(!config.github.sync_pending_review);
+        assert!(!config.is_gh_backend_forced());
+        assert!(!config.is_api_backend_forced());
+    }
+
+    #[test]
+    fn test_parse_toml_with_github() {
+        let toml_str = r#"
+[github]
+backend = "API"
+token = "ghp_test"
+sync_pending_review = true
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.is_api_backend_forced());
+        assert!(!config.is_gh_backend_forced());
+        assert_eq!(config.github.token.as_deref(), Some("ghp_test"));
+        assert!(config.github.sync_pending_review);
+    }
+
+    #[test]
+    fn test_forge_hosts_default() {
+        let config = Config::default();
+        assert_eq!(config.forge_hosts(), vec!["github.com".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_toml_with_hosts() {
+        let toml_str = r#"
+[[hosts]]
+host = "GitHub.Example.com"
+token = "ghe_token"
+
+[[hosts]]
+host = "github.com"
+
+[[hosts]]
+host = "ghe.internal"
+api_url = "https://ghe.internal/custom/api"
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.forge_hosts(),
+            vec![
+                "github.com".to_string(),
+                "github.example.com".to_string(),
+                "ghe.internal".to_string()
+            ]
+        );
+        let ghe = config.host_config("github.example.com").unwrap();
+        assert_eq!(ghe.token.as_deref(), Some("ghe_token"));
+        assert_eq!(
+            config.host_config("ghe.internal").unwrap().api_url.as_deref(),
+            Some("https://ghe.internal/custom/api")
+        );
+        assert!(config.host_config("unknown.example.com").is_none());
+    }
+
+    #[test]
+    fn test_host_kind() {
+        let toml_str = r#"
+[[hosts]]
+host = "git.example.com"
+kind = "gitlab"
+
+[[hosts]]
+host = "code.example.com"
+kind = "forgejo"
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.host_kind("git.example.com"), ForgeKind::Gitlab);
+        assert_eq!(config.host_kind("code.example.com"), ForgeKind::Gitea);
+        assert_eq!(config.host_kind("gitlab.com"), ForgeKind::Gitlab);
+        assert_eq!(config.host_kind("github.com"), ForgeKind::Github);
+        assert_eq!(config.host_kind("ghe.example.com"), ForgeKind::Github);
+
+        // gitlab.com is accepted in URLs without joining the PR lists
+        assert!(config.url_hosts().contains(&("gitlab.com".to_string(), ForgeKind::Gitlab)));
+        assert!(!config.forge_hosts().contains(&"gitlab.com".to_string()));
+    }
+
+    #[test]
+    fn test_default_config_content_parses() {
+        let config: Config = toml::from_str(&Config::default_config_content()).unwrap();
+        assert_eq!(config.github.backend, "auto");
+    }
+
     #[test]
     fn test_rgb_color_new() {
         let color = RgbColor::new(255, 128, 64);
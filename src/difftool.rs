@@ -0,0 +1,586 @@
+//! Git integration: kensa as an external diff tool (`GIT_EXTERNAL_DIFF`, `git difftool`)
+//! and as a pager (`core.pager`).
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Instant;
+use tokio::process::Command;
+
+use crate::github::perf_log;
+
+/// Unified diff for one external diff or difftool invocation, with git headers
+/// naming the repository path instead of git's temporary files
+///
+/// Accepted argument forms:
+/// - `path old-file old-hex old-mode new-file new-hex new-mode [new-path rename-info]`
+///   (`GIT_EXTERNAL_DIFF`)
+/// - `path` alone for an unmerged path (no diff)
+/// - `LOCAL REMOTE [MERGED]` (`git difftool`), files or directories (`--dir-diff`)
+pub async fn external_diff(args: &[String]) -> Result<String> {
+    match args.len() {
+        1 => Ok(String::new()),
+        2 | 3 => {
+            let (local, remote) = (Path::new(&args[0]), Path::new(&args[1]));
+            if local.is_dir() && remote.is_dir() {
+                let diff = diff_no_index(local, remote).await?;
+                Ok(relabel(
+                    &diff,
+                    &[
+                        (dir_prefix(local), String::new()),
+                        (dir_prefix(remote), String::new()),
+                    ],
+                ))
+            } else {
+                let path = match args.get(2) {
+                    Some(merged) => merged.clone(),
+                    None => remote_label(
+                        &args[1],
+                        std::env::var("BASE").ok(),
+                        &std::env::current_dir().unwrap_or_default(),
+                    ),
+                };
+                pair_diff(local, remote, &path, &path).await
+            }
+        }
+        7 | 9 => {
+            let new_path = if args.len() == 9 { &args[7] } else { &args[0] };
+            pair_diff(Path::new(&args[1]), Path::new(&args[4]), &args[0], new_path).await
+        }
+        n => bail!(
+            "Unexpected number of difftool arguments: {} (expected 1, 2, 3, 7 or 9)",
+            n
+        ),
+    }
+}
+
+/// Repository path for `LOCAL REMOTE` without `$MERGED` (`git difftool --extcmd`)
+/// git exports the path as `$BASE`; otherwise REMOTE names it when it is the work tree file
+/// (difftool runs from the top of the work tree). Temporary files keep their full path so
+/// that files with the same name in different directories stay apart.
+fn remote_label(remote: &str, base: Option<String>, toplevel: &Path) -> String {
+    if let Some(base) = base.filter(|b| !b.is_empty()) {
+        return base;
+    }
+
+    let path = Path::new(remote);
+    match path.strip_prefix(toplevel) {
+        Ok(relative) if path.is_absolute() => relative.to_string_lossy().into_owned(),
+        // Diff headers have no room for a leading slash
+        _ => remote.trim_start_matches('/').to_string(),
+    }
+}
+
+/// Diff two files and label them with their repository paths
+async fn pair_diff(old: &Path, new: &Path, old_label: &str, new_label: &str) -> Result<String> {
+    let diff = diff_no_index(old, new).await?;
+    let (old_raw, new_raw) = (raw_path(old), raw_path(new));
+
+    // Added and deleted files name the existing side twice
+    Ok(relabel(
+        &diff,
+        &[
+            (old_raw, old_label.to_string()),
+            (new_raw, new_label.to_string()),
+        ],
+    ))
+}
+
+/// Run `git diff --no-index`; exit status 1 just means the inputs differ
+async fn diff_no_index(old: &Path, new: &Path) -> Result<String> {
+    let start = Instant::now();
+    let output = Command::new("git")
+        .args(["diff", "--no-index", "--no-color", "--no-ext-diff", "--"])
+        .arg(absolute(old))
+        .arg(absolute(new))
+        // Never recurse into ourselves
+        .env_remove("GIT_EXTERNAL_DIFF")
+        .output()
+        .await
+        .context("Failed to run 'git'. Is it installed?")?;
+
+    perf_log("git diff --no-index", start.elapsed().as_millis());
+
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        _ => Err(anyhow!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Absolute form of a path (git prints it in headers without the leading slash)
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// How git prints a path in `a/...`/`b/...` headers
+fn raw_path(path: &Path) -> String {
+    absolute(path)
+        .to_string_lossy()
+        .trim_start_matches('/')
+        .to_string()
+}
+
+/// Directory prefix to strip from `--dir-diff` paths
+fn dir_prefix(dir: &Path) -> String {
+    format!("{}/", raw_path(dir).trim_end_matches('/'))
+}
+
+/// Rewrite `a/<from>`/`b/<from>` to `a/<to>`/`b/<to>` in the header lines of a diff
+fn relabel(diff: &str, replacements: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(diff.len());
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") || line.starts_with("--- ") || line.starts_with("+++ ") {
+            let mut line = line.to_string();
+            for (from, to) in replacements {
+                line = line
+                    .replace(&format!("a/{}", from), &format!("a/{}", to))
+                    .replace(&format!("b/{}", from), &format!("b/{}", to));
+            }
+            out.push_str(&line);
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Directory of the difftool session files, private to the user
+/// (`$XDG_RUNTIME_DIR/kensa`, or kensa's cache directory)
+fn spool_dir() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .map(|dir| dir.join("kensa"))
+}
+
+/// File that collects per-file diffs of one session until git has handed over the last one
+fn spool_path(dir: &Path, session: u32) -> PathBuf {
+    dir.join(format!("difftool-{}.diff", session))
+}
+
+/// Add one file's diff to the session
+/// Returns the whole session once this is the last file git will send
+/// (`GIT_DIFF_PATH_COUNTER` == `GIT_DIFF_PATH_TOTAL`; both absent means a single file)
+pub fn collect(file_diff: &str) -> Result<Option<String>> {
+    let env_number = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+    };
+    match (
+        env_number("GIT_DIFF_PATH_COUNTER"),
+        env_number("GIT_DIFF_PATH_TOTAL"),
+    ) {
+        (Some(counter), Some(total)) => {
+            let dir = spool_dir().ok_or_else(|| {
+                anyhow!("Could not determine a directory for the difftool session")
+            })?;
+            create_private_dir(&dir)?;
+            collect_into(
+                &spool_path(&dir, git_session_id()),
+                file_diff,
+                counter,
+                total,
+            )
+        }
+        _ => Ok(Some(file_diff.to_string())),
+    }
+}
+
+fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(dir)
+        .context("Failed to create difftool session directory")
+}
+
+/// Id shared by every file of one `git diff` or `git difftool` run: the pid of the git process
+/// running the external diff, the nearest ancestor named git (kensa may be started through
+/// `sh -c` or git-difftool--helper)
+#[cfg(unix)]
+fn git_session_id() -> u32 {
+    let parent = std::os::unix::process::parent_id();
+    let mut pid = parent;
+    for _ in 0..8 {
+        match process_info(pid) {
+            Some((name, _)) if name == "git" => return pid,
+            Some((_, ppid)) if ppid > 1 => pid = ppid,
+            _ => break,
+        }
+    }
+    parent
+}
+
+/// Without parent pids, concurrent sessions share the spool file
+#[cfg(not(unix))]
+fn git_session_id() -> u32 {
+    0
+}
+
+/// Name and parent pid of a process, from /proc or `ps`
+#[cfg(unix)]
+fn process_info(pid: u32) -> Option<(String, u32)> {
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        return parse_proc_stat(&stat);
+    }
+
+    let output = std::process::Command::new("ps")
+        .args(["-o", "ppid=,comm=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let (ppid, command) = line.split_once(char::is_whitespace)?;
+    let name = Path::new(command.trim())
+        .file_name()?
+        .to_string_lossy()
+        .into_owned();
+    Some((name, ppid.parse().ok()?))
+}
+
+/// Name and parent pid from `/proc/<pid>/stat` ("pid (name) state ppid ..."; the name may contain spaces)
+#[cfg(unix)]
+fn parse_proc_stat(stat: &str) -> Option<(String, u32)> {
+    let name_start = stat.find('(')? + 1;
+    let name_end = stat.rfind(')')?;
+    let ppid = stat
+        .get(name_end + 1..)?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()?;
+    Some((stat.get(name_start..name_end)?.to_string(), ppid))
+}
+
+fn collect_into(
+    path: &Path,
+    file_diff: &str,
+    counter: usize,
+    total: usize,
+) -> Result<Option<String>> {
+    let mut options = std::fs::OpenOptions::new();
+    if counter <= 1 {
+        // Left behind by an aborted session whose git pid was reused
+        let _ = std::fs::remove_file(path);
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+    } else {
+        options.append(true);
+    }
+    let mut spool = options
+        .open(path)
+        .context("Failed to open difftool session file")?;
+    spool
+        .write_all(file_diff.as_bytes())
+        .context("Failed to write difftool session file")?;
+
+    if counter < total {
+        return Ok(None);
+    }
+
+    let session = std::fs::read_to_string(path).context("Failed to read difftool session file")?;
+    let _ = std::fs::remove_file(path);
+    Ok(Some(session))
+}
+
+/// Remove ANSI escape sequences (colors from `git diff --color`, `git log -p`)
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, then a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC (e.g. hyperlinks): ends with BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// How much pager input is read to tell a diff from other text
+pub const PAGER_SNIFF_BYTES: u64 = 64 * 1024;
+
+/// Read the start of the input, up to `limit` bytes or the end of input
+/// The rest stays unread, so long output can still be streamed
+pub fn read_prefix(input: &mut impl Read, limit: u64) -> std::io::Result<Vec<u8>> {
+    let mut prefix = Vec::new();
+    input.by_ref().take(limit).read_to_end(&mut prefix)?;
+    Ok(prefix)
+}
+
+/// Show text that is not a diff (`git log`, `git branch`) the way a plain pager would
+/// The already read prefix goes first, then the rest is streamed as it arrives
+pub fn page(prefix: &[u8], rest: &mut impl Read) -> Result<()> {
+    use std::io::IsTerminal;
+
+    if std::io::stdout().is_terminal()
+        && let Ok(mut less) = std::process::Command::new("less")
+            .arg("-FRX")
+            .stdin(Stdio::piped())
+            .spawn()
+    {
+        if let Some(mut stdin) = less.stdin.take() {
+            // less closing early (q) is not an error
+            let _ = stdin
+                .write_all(prefix)
+                .and_then(|_| std::io::copy(rest, &mut stdin));
+        }
+        less.wait().context("Failed to wait for less")?;
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    let written = stdout
+        .write_all(prefix)
+        .and_then(|_| std::io::copy(rest, &mut stdout))
+        .and_then(|_| stdout.flush());
+    match written {
+        // The reader closing early (e.g. head) is not an error either
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        let colored =
+            "\x1b[1mdiff --git a/x b/x\x1b[m\n\x1b[32m+added\x1b[m\n\x1b[31m-removed\x1b[0m\n";
+        assert_eq!(
+            strip_ansi(colored),
+            "diff --git a/x b/x\n+added\n-removed\n"
+        );
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://x\x07link\x1b]8;;\x1b\\ done"),
+            "link done"
+        );
+        assert_eq!(strip_ansi("plain ✓"), "plain ✓");
+    }
+
+    #[test]
+    fn test_read_prefix_leaves_the_rest() {
+        let mut input = std::io::Cursor::new(b"commit abc\nAuthor: me\n".to_vec());
+        assert_eq!(read_prefix(&mut input, 11).unwrap(), b"commit abc\n");
+
+        let mut rest = Vec::new();
+        input.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"Author: me\n");
+
+        let mut short = std::io::Cursor::new(b"short".to_vec());
+        assert_eq!(read_prefix(&mut short, 64).unwrap(), b"short");
+    }
+
+    #[test]
+    fn test_relabel_pair() {
+        let diff = "\
+diff --git a/tmp/git-blob-a1/lib.rs b/tmp/git-blob-b2/lib.rs
+index 1..2 100644
+--- a/tmp/git-blob-a1/lib.rs
++++ b/tmp/git-blob-b2/lib.rs
+@@ -1 +1 @@
+-a/tmp/git-blob-a1/lib.rs stays in content
++new
+";
+        let out = relabel(
+            diff,
+            &[
+                (
+                    "tmp/git-blob-a1/lib.rs".to_string(),
+                    "src/lib.rs".to_string(),
+                ),
+                (
+                    "tmp/git-blob-b2/lib.rs".to_string(),
+                    "src/lib.rs".to_string(),
+                ),
+            ],
+        );
+        assert!(out.starts_with("diff --git a/src/lib.rs b/src/lib.rs\n"));
+        assert!(out.contains("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(out.contains("-a/tmp/git-blob-a1/lib.rs stays in content\n"));
+    }
+
+    #[test]
+    fn test_remote_label() {
+        let top = Path::new("/work/repo");
+        assert_eq!(
+            remote_label("/tmp/x/mod.rs", Some("src/mod.rs".to_string()), top),
+            "src/mod.rs"
+        );
+        assert_eq!(remote_label("tests/mod.rs", None, top), "tests/mod.rs");
+        assert_eq!(
+            remote_label("/work/repo/src/mod.rs", None, top),
+            "src/mod.rs"
+        );
+        assert_eq!(
+            remote_label("/tmp/git-blob-b2/mod.rs", Some(String::new()), top),
+            "tmp/git-blob-b2/mod.rs"
+        );
+    }
+
+    #[test]
+    fn test_relabel_dir_diff() {
+        let diff = "\
+diff --git a/tmp/d/right/new.rs b/tmp/d/right/new.rs
+new file mode 100644
+--- /dev/null
++++ b/tmp/d/right/new.rs
+diff --git a/tmp/d/left/src/x.rs b/tmp/d/right/src/x.rs
+--- a/tmp/d/left/src/x.rs
++++ b/tmp/d/right/src/x.rs
+";
+        let out = relabel(
+            diff,
+            &[
+                ("tmp/d/left/".to_string(), String::new()),
+                ("tmp/d/right/".to_string(), String::new()),
+            ],
+        );
+        let files = crate::parser::parse_diff(&out);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "new.rs");
+        assert_eq!(files[1].path, "src/x.rs");
+        assert!(out.contains("--- /dev/null\n"));
+    }
+
+    #[test]
+    fn test_collect_into_accumulates_until_last_file() {
+        let path =
+            std::env::temp_dir().join(format!("kensa-spool-test-{}.diff", std::process::id()));
+        std::fs::write(&path, "stale session\n").unwrap();
+
+        assert_eq!(collect_into(&path, "one\n", 1, 3).unwrap(), None);
+        assert_eq!(collect_into(&path, "two\n", 2, 3).unwrap(), None);
+        assert_eq!(
+            collect_into(&path, "three\n", 3, 3).unwrap().as_deref(),
+            Some("one\ntwo\nthree\n")
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_collect_into_interleaved_sessions() {
+        let dir = std::env::temp_dir().join(format!("kensa-spool-sessions-{}", std::process::id()));
+        create_private_dir(&dir).unwrap();
+        let (first, second) = (spool_path(&dir, 100), spool_path(&dir, 200));
+
+        assert_eq!(collect_into(&first, "a1\n", 1, 2).unwrap(), None);
+        assert_eq!(collect_into(&second, "b1\n", 1, 3).unwrap(), None);
+        assert_eq!(collect_into(&second, "b2\n", 2, 3).unwrap(), None);
+        assert_eq!(
+            collect_into(&first, "a2\n", 2, 2).unwrap().as_deref(),
+            Some("a1\na2\n")
+        );
+        assert_eq!(
+            collect_into(&second, "b3\n", 3, 3).unwrap().as_deref(),
+            Some("b1\nb2\nb3\n")
+        );
+
+        // A later file without the session's first one doesn't start a new file
+        assert!(collect_into(&first, "stray\n", 2, 2).is_err());
+        assert!(!first.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spool_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("kensa-spool-private-{}", std::process::id()));
+        create_private_dir(&dir).unwrap();
+        let path = spool_path(&dir, 1);
+        collect_into(&path, "one\n", 1, 2).unwrap();
+
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&path), 0o600);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_proc_stat() {
+        assert_eq!(
+            parse_proc_stat("4242 (git) S 4200 4242 4200 0 -1"),
+            Some(("git".to_string(), 4200))
+        );
+        assert_eq!(
+            parse_proc_stat("7 (my (odd) name) R 1 7 7"),
+            Some(("my (odd) name".to_string(), 1))
+        );
+        assert_eq!(parse_proc_stat("garbage"), None);
+    }
+
+    #[tokio::test]
+    async fn test_external_diff_git_arguments() {
+        let dir = std::env::temp_dir().join(format!("kensa-difftool-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let old = dir.join("old_blob");
+        let new = dir.join("new_blob");
+        std::fs::write(&old, "one\ntwo\n").unwrap();
+        std::fs::write(&new, "one\nthree\n").unwrap();
+
+        let s = |p: &Path| p.to_string_lossy().into_owned();
+        let args = vec![
+            "src/lib.rs".to_string(),
+            s(&old),
+            "abc".to_string(),
+            "100644".to_string(),
+            s(&new),
+            ".".to_string(),
+            "100644".to_string(),
+        ];
+        let diff = external_diff(&args).await.unwrap();
+        let files = crate::parser::parse_diff(&diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 1);
+
+        // Unmerged paths come with no files to diff
+        assert_eq!(external_diff(&args[..1]).await.unwrap(), "");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,8 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::types::{PendingComment, PrInfo};
+use crate::types::{PendingComment, PrInfo, DEFAULT_HOST};
 
 /// Get the drafts directory path (~/.config/kensa/drafts/)
 fn drafts_dir() -> Option<PathBuf> {
@@ -9,14 +10,157 @@ fn drafts_dir() -> Option<PathBuf> {
 }
 
 /// Get the draft file path for a specific PR
+/// github.com drafts keep the original host-less name so existing drafts still load
 fn draft_file_path(pr: &PrInfo) -> Option<PathBuf> {
-    drafts_dir().map(|dir| dir.join(format!("{}_{}_{}.json", pr.owner, pr.repo, pr.number)))
+    // GitLab namespaces can be nested (group/subgroup), keep the name a single path component
+    let owner = pr.owner.replace('/', "_");
+    let name = if pr.host == DEFAULT_HOST {
+        format!("{}_{}_{}.json", owner, pr.repo, pr.number)
+    } else {
+        format!("{}_{}_{}_{}.json", pr.host, owner, pr.repo, pr.number)
+    };
+    drafts_dir().map(|dir| dir.join(name))
+}
+
+/// Get the notes file path for a local review (see `crate::local`)
+fn local_notes_file_path(key: &str) -> Option<PathBuf> {
+    drafts_dir().map(|dir| dir.join(format!("local_{}.json", key)))
+}
+
+/// Get the file recording the head commit of each PR's last review submitted from kensa
+fn reviewed_heads_path() -> Option<PathBuf> {
+    drafts_dir().map(|dir| dir.join("reviewed.json"))
+}
+
+/// Key of a PR in the reviewed heads file
+fn reviewed_head_key(pr: &PrInfo) -> String {
+    format!("{}/{}/{}#{}", pr.host, pr.owner, pr.repo, pr.number)
+}
+
+/// Record the head commit a review of the PR was submitted on
+pub fn save_reviewed_head(pr: &PrInfo, head_sha: &str) -> Result<(), String> {
+    let dir = drafts_dir().ok_or("Could not determine config directory")?;
+    let file_path = reviewed_heads_path().ok_or("Could not determine reviewed commits file path")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create drafts directory: {}", e))?;
+
+    let mut heads = load_reviewed_heads(&file_path);
+    heads.insert(reviewed_head_key(pr), head_sha.to_string());
+    let json = serde_json::to_string_pretty(&heads)
+        .map_err(|e| format!("Failed to serialize reviewed commits: {}", e))?;
+    fs::write(&file_path, json).map_err(|e| format!("Failed to write reviewed commits file: {}", e))
+}
+
+/// Head commit of the last review of the PR submitted from kensa
+pub fn load_reviewed_head(pr: &PrInfo) -> Option<String> {
+    let file_path = reviewed_heads_path()?;
+    load_reviewed_heads(&file_path).remove(&reviewed_head_key(pr))
+}
+
+fn load_reviewed_heads(file_path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(file_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
 }
 
 /// Save drafts for a PR to disk
 pub fn save_drafts(pr: &PrInfo, comments: &[PendingComment]) -> Result<(), String> {
-    let dir = drafts_dir().ok_or("Could not determine config directory")?;
     let file_path = draft_file_path(pr).ok_or("Could not determine draft file path")?;
+    save_to_file(&file_path, comments)
+}
+
+/// Load drafts for a PR from disk
+pub fn load_drafts(pr: &PrInfo) -> Vec<PendingComment> {
+    draft_file_path(pr)
+        .map(|p| load_from_file(&p))
+        .unwrap_or_default()
+}
+
+/// Save notes for a local review to disk
+pub fn save_local_notes(key: &str, comments: &[PendingComment]) -> Result<(), String> {
+    let file_path = local_notes_file_path(key).ok_or("Could not determine notes file path")?;
+    save_to_file(&file_path, comments)
+}
+
+/// Load notes for a local review from disk
+pub fn load_local_notes(key: &str) -> Vec<PendingComment> {
+    local_notes_file_path(key)
+        .map(|p| load_from_file(&p))
+        .unwrap_or_default()
+}
+
+/// Edits that bring the host's pending review in line with the local drafts
+#[derive(Debug, Default)]
+pub struct PendingReviewSync {
+    /// Inline drafts not on the pending review yet
+    pub add: Vec<PendingComment>,
+    /// Drafts whose text changed since the last sync: (remote id, new body)
+    pub update: Vec<(String, String)>,
+    /// Remote ids of drafts that were deleted locally
+    pub delete: Vec<String>,
+}
+
+impl PendingReviewSync {
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.update.is_empty() && self.delete.is_empty()
+    }
+}
+
+/// Merge the comments of a pending review loaded from the host into the drafts
+/// Synced drafts take the remote text unless they were edited locally since the last sync;
+/// drafts whose remote copy is gone were submitted or deleted elsewhere and are dropped
+pub fn merge_pending_review(drafts: &mut Vec<PendingComment>, remote: &[PendingComment]) {
+    drafts.retain(|d| d.remote_id.is_none() || remote.iter().any(|r| r.remote_id == d.remote_id));
+
+    for comment in remote {
+        match drafts.iter_mut().find(|d| d.remote_id == comment.remote_id) {
+            Some(draft) => {
+                if draft.synced_body.as_ref().is_none_or(|synced| *synced == draft.body) {
+                    draft.body = comment.body.clone();
+                }
+                draft.synced_body = Some(comment.body.clone());
+            }
+            None => {
+                let mut draft = comment.clone();
+                draft.synced_body = Some(comment.body.clone());
+                drafts.push(draft);
+            }
+        }
+    }
+}
+
+/// Work out what to send to the pending review
+/// `synced` maps remote ids to the text last known to be on the host
+/// New inline and file-level drafts are only pushed with `push_new`
+pub fn pending_review_changes(
+    drafts: &[PendingComment],
+    synced: &HashMap<String, String>,
+    push_new: bool,
+) -> PendingReviewSync {
+    let mut changes = PendingReviewSync::default();
+
+    for draft in drafts {
+        match &draft.remote_id {
+            Some(id) if synced.get(id).is_some_and(|body| *body != draft.body) => {
+                changes.update.push((id.clone(), draft.body.clone()));
+            }
+            None if push_new && draft.is_review_comment() => changes.add.push(draft.clone()),
+            _ => {}
+        }
+    }
+
+    changes.delete = synced
+        .keys()
+        .filter(|id| !drafts.iter().any(|d| d.remote_id.as_ref() == Some(*id)))
+        .cloned()
+        .collect();
+    changes.delete.sort();
+
+    changes
+}
+
+fn save_to_file(file_path: &Path, comments: &[PendingComment]) -> Result<(), String> {
+    let dir = drafts_dir().ok_or("Could not determine config directory")?;
 
     // Create directory if it doesn't exist
     fs::create_dir_all(&dir).map_err(|e| format!("Failed to create drafts directory: {}", e))?;
@@ -24,30 +168,24 @@ pub fn save_drafts(pr: &PrInfo, comments: &[PendingComment]) -> Result<(), Strin
     if comments.is_empty() {
         // Remove the file if no drafts
         if file_path.exists() {
-            fs::remove_file(&file_path).map_err(|e| format!("Failed to remove draft file: {}", e))?;
+            fs::remove_file(file_path).map_err(|e| format!("Failed to remove draft file: {}", e))?;
         }
     } else {
         // Save drafts to file
         let json = serde_json::to_string_pretty(comments)
             .map_err(|e| format!("Failed to serialize drafts: {}", e))?;
-        fs::write(&file_path, json).map_err(|e| format!("Failed to write draft file: {}", e))?;
+        fs::write(file_path, json).map_err(|e| format!("Failed to write draft file: {}", e))?;
     }
 
     Ok(())
 }
 
-/// Load drafts for a PR from disk
-pub fn load_drafts(pr: &PrInfo) -> Vec<PendingComment> {
-    let file_path = match draft_file_path(pr) {
-        Some(p) => p,
-        None => return Vec::new(),
-    };
-
+fn load_from_file(file_path: &Path) -> Vec<PendingComment> {
     if !file_path.exists() {
         return Vec::new();
     }
 
-    let content = match fs::read_to_string(&file_path) {
+    let content = match fs::read_to_string(file_path) {
         Ok(c) => c,
         Err(_) => return Vec::new(),
     };
@@ -77,6 +215,7 @@ mod tests {
     #[test]
     fn test_draft_file_path_format() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "testowner".to_string(),
             repo: "testrepo".to_string(),
             number: 123,
@@ -92,6 +231,7 @@ mod tests {
     #[test]
     fn test_draft_file_path_with_hyphenated_names() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "my-org".to_string(),
             repo: "my-cool-repo".to_string(),
             number: 42,
@@ -104,9 +244,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_draft_file_path_enterprise_host() {
+        let pr = PrInfo {
+            host: "github.example.com".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 7,
+        };
+
+        let path = draft_file_path(&pr);
+        if let Some(p) = path {
+            let filename = p.file_name().unwrap().to_string_lossy();
+            assert_eq!(filename, "github.example.com_owner_repo_7.json");
+        }
+    }
+
+    #[test]
+    fn test_reviewed_head_key() {
+        let pr = PrInfo {
+            host: "gitlab.com".to_string(),
+            owner: "group/sub".to_string(),
+            repo: "repo".to_string(),
+            number: 7,
+        };
+
+        assert_eq!(reviewed_head_key(&pr), "gitlab.com/group/sub/repo#7");
+    }
+
+    #[test]
+    fn test_draft_file_path_nested_namespace() {
+        let pr = PrInfo {
+            host: "gitlab.com".to_string(),
+            owner: "group/subgroup".to_string(),
+            repo: "project".to_string(),
+            number: 5,
+        };
+
+        let path = draft_file_path(&pr);
+        if let Some(p) = path {
+            let filename = p.file_name().unwrap().to_string_lossy();
+            assert_eq!(filename, "gitlab.com_group_subgroup_project_5.json");
+        }
+    }
+
     #[test]
     fn test_draft_file_path_with_large_pr_number() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "owner".to_string(),
             repo: "repo".to_string(),
             number: 999999,
@@ -127,6 +312,7 @@ mod tests {
     fn test_load_drafts_nonexistent_pr() {
         // Loading drafts for a PR that has never had any should return empty vec
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "nonexistent_owner_12345".to_string(),
             repo: "nonexistent_repo_67890".to_string(),
             number: 99999999,
@@ -140,6 +326,7 @@ mod tests {
     fn test_save_and_load_drafts_roundtrip() {
         // Use a unique PR to avoid conflicts with other tests
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "test_roundtrip_owner".to_string(),
             repo: "test_roundtrip_repo".to_string(),
             number: 11111,
@@ -189,6 +376,7 @@ mod tests {
     #[test]
     fn test_save_empty_removes_file() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "test_empty_owner".to_string(),
             repo: "test_empty_repo".to_string(),
             number: 22222,
@@ -214,6 +402,7 @@ mod tests {
     #[test]
     fn test_save_overwrites_existing() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "test_overwrite_owner".to_string(),
             repo: "test_overwrite_repo".to_string(),
             number: 33333,
@@ -246,6 +435,7 @@ mod tests {
         // but we can verify the function handles invalid JSON gracefully
 
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "corrupted_test".to_string(),
             repo: "corrupted_repo".to_string(),
             number: 44444,
@@ -260,12 +450,14 @@ mod tests {
     fn test_multiple_prs_independent() {
         // Verify that different PRs have independent draft storage
         let pr1 = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "owner1".to_string(),
             repo: "repo1".to_string(),
             number: 55555,
         };
 
         let pr2 = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "owner2".to_string(),
             repo: "repo2".to_string(),
             number: 66666,
@@ -299,6 +491,7 @@ mod tests {
     #[test]
     fn test_draft_with_special_characters_in_body() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "special_char_owner".to_string(),
             repo: "special_char_repo".to_string(),
             number: 77777,
@@ -324,6 +517,7 @@ mod tests {
     #[test]
     fn test_draft_with_unicode_in_path() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "unicode_owner".to_string(),
             repo: "unicode_repo".to_string(),
             number: 88888,
@@ -350,6 +544,7 @@ mod tests {
     #[test]
     fn test_draft_with_empty_body() {
         let pr = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "empty_body_owner".to_string(),
             repo: "empty_body_repo".to_string(),
             number: 99999,
@@ -368,6 +563,27 @@ mod tests {
         let _ = save_drafts(&pr, &[]);
     }
 
+    #[test]
+    fn test_save_and_load_local_notes_roundtrip() {
+        let key = "test_local_notes_0000";
+        let comments = vec![PendingComment::new_inline(
+            "Local note".to_string(),
+            "src/lib.rs".to_string(),
+            7,
+        )];
+
+        let save_result = save_local_notes(key, &comments);
+        assert!(save_result.is_ok(), "Failed to save notes: {:?}", save_result);
+
+        let loaded = load_local_notes(key);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].body, "Local note");
+
+        // Cleanup
+        let _ = save_local_notes(key, &[]);
+        assert!(load_local_notes(key).is_empty());
+    }
+
     #[test]
     fn test_draft_serialization_json_format() {
         // Verify that the JSON format is correct (for debugging purposes)
@@ -387,4 +603,98 @@ mod tests {
         assert!(json.contains("src/test.rs"));
         assert!(json.contains("42"));
     }
+
+    // ========================================================================
+    // Pending review sync
+    // ========================================================================
+
+    fn remote(body: &str, id: &str) -> PendingComment {
+        let mut comment = PendingComment::new_inline(body.to_string(), "src/a.rs".to_string(), 3);
+        comment.remote_id = Some(id.to_string());
+        comment
+    }
+
+    /// A draft whose remote copy had `synced_body` when last synced
+    fn synced(body: &str, id: &str, synced_body: &str) -> PendingComment {
+        let mut comment = remote(body, id);
+        comment.synced_body = Some(synced_body.to_string());
+        comment
+    }
+
+    #[test]
+    fn test_merge_pending_review() {
+        let mut drafts = vec![
+            PendingComment::new_general("Local".to_string()),
+            synced("Edited locally", "C_1", "Original"),
+            remote("Submitted elsewhere", "C_9"),
+        ];
+
+        merge_pending_review(&mut drafts, &[remote("Original", "C_1"), remote("From browser", "C_2")]);
+
+        let bodies: Vec<&str> = drafts.iter().map(|d| d.body.as_str()).collect();
+        assert_eq!(bodies, vec!["Local", "Edited locally", "From browser"]);
+        assert_eq!(drafts[1].remote_id.as_deref(), Some("C_1"));
+        assert_eq!(drafts[1].synced_body.as_deref(), Some("Original"));
+        assert_eq!(drafts[2].remote_id.as_deref(), Some("C_2"));
+        assert_eq!(drafts[2].synced_body.as_deref(), Some("From browser"));
+
+        // Merging again changes nothing
+        merge_pending_review(&mut drafts, &[remote("Original", "C_1"), remote("From browser", "C_2")]);
+        assert_eq!(drafts.len(), 3);
+
+        // No pending review left: only local drafts remain
+        merge_pending_review(&mut drafts, &[]);
+        assert_eq!(drafts.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_pending_review_takes_remote_edits() {
+        let mut drafts = vec![synced("Original", "C_1", "Original"), remote("Before upgrade", "C_2")];
+
+        merge_pending_review(
+            &mut drafts,
+            &[remote("Edited in browser", "C_1"), remote("Also edited", "C_2")],
+        );
+
+        let bodies: Vec<&str> = drafts.iter().map(|d| d.body.as_str()).collect();
+        assert_eq!(bodies, vec!["Edited in browser", "Also edited"]);
+        assert_eq!(drafts[0].synced_body.as_deref(), Some("Edited in browser"));
+
+        // Nothing is sent back once the host's text is recorded as synced
+        let synced: HashMap<String, String> = drafts
+            .iter()
+            .filter_map(|d| Some((d.remote_id.clone()?, d.synced_body.clone()?)))
+            .collect();
+        assert!(pending_review_changes(&drafts, &synced, true).is_empty());
+    }
+
+    #[test]
+    fn test_pending_review_changes() {
+        let drafts = vec![
+            PendingComment::new_general("General".to_string()),
+            PendingComment::new_inline("New".to_string(), "src/a.rs".to_string(), 1),
+            remote("Changed", "C_1"),
+            remote("Same", "C_2"),
+        ];
+        let synced: HashMap<String, String> = [
+            ("C_1".to_string(), "Before".to_string()),
+            ("C_2".to_string(), "Same".to_string()),
+            ("C_3".to_string(), "Deleted".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        let changes = pending_review_changes(&drafts, &synced, true);
+        assert_eq!(changes.add.len(), 1);
+        assert_eq!(changes.add[0].body, "New");
+        assert_eq!(changes.update, vec![("C_1".to_string(), "Changed".to_string())]);
+        assert_eq!(changes.delete, vec!["C_3".to_string()]);
+
+        // Without pushing, only drafts already on the pending review are kept in sync
+        let changes = pending_review_changes(&drafts, &synced, false);
+        assert!(changes.add.is_empty());
+        assert_eq!(changes.update.len(), 1);
+
+        assert!(pending_review_changes(&drafts[..1], &HashMap::new(), true).is_empty());
+    }
 }
@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+
+use super::github_graphql::{self, GraphqlTransport};
+use super::{ForgeBackend, ForgeError};
+use crate::config::DEFAULT_MAX_PRS;
+use crate::github;
+use crate::types::{
+    CheckRun, CommentThread, MergeInfo, MergeRequest, PendingComment, PrInfo, PrList, PrMetadata,
+    ReactionKind, ReviewPr, ReviewState,
+};
+
+/// Backend that shells out to the GitHub CLI (`gh`)
+pub struct GhCli {
+    host: String,
+    max_prs: usize,
+}
+
+impl GhCli {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            max_prs: DEFAULT_MAX_PRS,
+        }
+    }
+
+    /// Cap the number of PRs fetched per list query
+    pub fn with_max_prs(mut self, max_prs: usize) -> Self {
+        self.max_prs = max_prs;
+        self
+    }
+
+    /// Submit the user's pending review if there is one, posting general comments alongside
+    /// Returns None when there is no pending review
+    async fn submit_pending_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: Option<&str>,
+        comments: Option<&[PendingComment]>,
+    ) -> Result<Option<usize>, ForgeError> {
+        let (inline_comments, general_comments): (Vec<_>, Vec<_>) = comments
+            .map(|c| c.iter().partition(|c| c.is_review_comment()))
+            .unwrap_or_default();
+
+        let Some(mut submitted) =
+            github_graphql::submit_pending_review(self, pr, event, body, &inline_comments).await?
+        else {
+            return Ok(None);
+        };
+
+        for comment in general_comments {
+            github::submit_pr_comment(pr, comment, None).await?;
+            submitted += 1;
+        }
+        Ok(Some(submitted))
+    }
+}
+
+#[async_trait]
+impl GraphqlTransport for GhCli {
+    async fn graphql(&self, body: &serde_json::Value) -> Result<serde_json::Value, ForgeError> {
+        Ok(github::graphql(&self.host, body).await?)
+    }
+}
+
+#[async_trait]
+impl ForgeBackend for GhCli {
+    fn name(&self) -> &'static str {
+        "gh"
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        Ok(github::check_gh_cli(&self.host).await?)
+    }
+
+    async fn current_user(&self) -> Result<String, ForgeError> {
+        Ok(github::get_current_user(&self.host).await?)
+    }
+
+    async fn fetch_review_prs(&self) -> Result<PrList, ForgeError> {
+        let mut prs = github::fetch_review_prs(&self.host, self.max_prs).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_my_prs(&self) -> Result<PrList, ForgeError> {
+        let mut prs = github::fetch_my_prs(&self.host, self.max_prs).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_prs_by_author(&self, username: &str) -> Result<PrList, ForgeError> {
+        let mut prs = github::fetch_prs_by_author(&self.host, username, self.max_prs).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_search_prs(&self, query: &str) -> Result<PrList, ForgeError> {
+        let mut prs = github::search_prs(&self.host, query, self.max_prs).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_notifications(&self) -> Result<PrList, ForgeError> {
+        let mut prs = github::fetch_notifications(&self.host, self.max_prs).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn mark_notification(&self, thread_id: &str, done: bool) -> Result<(), ForgeError> {
+        Ok(github::mark_notification(&self.host, thread_id, done).await?)
+    }
+
+    async fn fetch_pr_diff(&self, pr: &PrInfo) -> Result<String, ForgeError> {
+        Ok(github::fetch_pr_diff(pr).await?)
+    }
+
+    async fn fetch_compare_diff(
+        &self,
+        pr: &PrInfo,
+        base: &str,
+        head: &str,
+    ) -> Result<String, ForgeError> {
+        Ok(github::fetch_compare_diff(pr, base, head).await?)
+    }
+
+    async fn fetch_pr_details(&self, pr: &PrInfo) -> Result<ReviewPr, ForgeError> {
+        Ok(github::fetch_pr_details(pr).await?)
+    }
+
+    async fn fetch_comment_threads(&self, pr: &PrInfo) -> Result<Vec<CommentThread>, ForgeError> {
+        Ok(github::fetch_all_comment_threads(pr).await?)
+    }
+
+    async fn fetch_review_state(&self, pr: &PrInfo) -> Result<ReviewState, ForgeError> {
+        github_graphql::fetch_review_state(self, pr).await
+    }
+
+    async fn submit_comments(
+        &self,
+        pr: &PrInfo,
+        comments: &[PendingComment],
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        if let Some(submitted) = self
+            .submit_pending_review(pr, "COMMENT", None, Some(comments))
+            .await?
+        {
+            return Ok(submitted);
+        }
+        Ok(github::submit_pr_comments(pr, comments, head_sha).await?)
+    }
+
+    async fn submit_thread_reply(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        Ok(github::submit_thread_reply(pr, thread, body).await?)
+    }
+
+    async fn set_thread_resolved(
+        &self,
+        _pr: &PrInfo,
+        thread: &CommentThread,
+        resolved: bool,
+    ) -> Result<(), ForgeError> {
+        github_graphql::set_thread_resolved(self, thread, resolved).await
+    }
+
+    async fn set_reaction(
+        &self,
+        subject_id: &str,
+        kind: ReactionKind,
+        add: bool,
+    ) -> Result<(), ForgeError> {
+        github_graphql::set_reaction(self, subject_id, kind, add).await
+    }
+
+    async fn fetch_checks(&self, pr: &PrInfo, head_sha: &str) -> Result<Vec<CheckRun>, ForgeError> {
+        github_graphql::fetch_checks(self, pr, head_sha).await
+    }
+
+    async fn fetch_merge_info(&self, pr: &PrInfo) -> Result<MergeInfo, ForgeError> {
+        github_graphql::fetch_merge_info(self, pr).await
+    }
+
+    async fn merge_pr(&self, pr: &PrInfo, request: &MergeRequest) -> Result<(), ForgeError> {
+        github_graphql::merge_pr(self, pr, request).await
+    }
+
+    async fn fetch_metadata(&self, pr: &PrInfo) -> Result<PrMetadata, ForgeError> {
+        github_graphql::fetch_metadata(self, pr).await
+    }
+
+    async fn update_metadata(
+        &self,
+        pr: &PrInfo,
+        original: &PrMetadata,
+        updated: &PrMetadata,
+    ) -> Result<(), ForgeError> {
+        github_graphql::update_metadata(self, pr, original, updated).await
+    }
+
+    async fn edit_comment(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        Ok(github::edit_comment(pr, thread, comment_id, body).await?)
+    }
+
+    async fn delete_comment(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        comment_id: u64,
+    ) -> Result<(), ForgeError> {
+        Ok(github::delete_comment(pr, thread, comment_id).await?)
+    }
+
+    async fn add_pending_comment(
+        &self,
+        pr: &PrInfo,
+        comment: &PendingComment,
+    ) -> Result<String, ForgeError> {
+        github_graphql::add_pending_comment(self, pr, comment).await
+    }
+
+    async fn update_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        remote_id: &str,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        github_graphql::update_pending_comment(self, remote_id, body).await
+    }
+
+    async fn delete_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        remote_id: &str,
+    ) -> Result<(), ForgeError> {
+        github_graphql::delete_pending_comment(self, remote_id).await
+    }
+
+    async fn submit_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: Option<&str>,
+        pending_comments: Option<&[PendingComment]>,
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        if let Some(submitted) = self
+            .submit_pending_review(pr, event, body, pending_comments)
+            .await?
+        {
+            return Ok(submitted);
+        }
+        Ok(github::submit_pr_review(pr, event, body, pending_comments, head_sha).await?)
+    }
+}
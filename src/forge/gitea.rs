@@ -0,0 +1,853 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::http::HttpClient;
+use super::{ForgeBackend, ForgeError};
+use crate::config::DEFAULT_MAX_PRS;
+use crate::github::{build_comment_threads, merge_review_prs, perf_log};
+use crate::types::{
+    CommentThread, CommentUser, DiffSide, IssueComment, PendingComment, PrInfo, PrList,
+    ReviewComment, ReviewPr, ReviewReason,
+};
+
+/// Page size for list endpoints (Gitea's default maximum)
+const PER_PAGE: &str = "50";
+
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// Native Gitea/Forgejo REST API (v1) client
+pub struct GiteaApi {
+    http: HttpClient,
+    host: String,
+    token: String,
+    max_prs: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPullRequest {
+    number: u32,
+    title: String,
+    user: ApiUser,
+    created_at: String,
+    head: ApiRef,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRepository {
+    name: String,
+    owner: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiIssue {
+    number: u32,
+    title: String,
+    user: ApiUser,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    repository: ApiRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReview {
+    id: u64,
+    #[serde(default)]
+    comments_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReviewComment {
+    id: u64,
+    body: String,
+    user: ApiUser,
+    path: String,
+    /// Line in the new version of the file (0 when the comment is on a removed line)
+    #[serde(default)]
+    position: u32,
+    /// Line in the old version of the file
+    #[serde(default)]
+    original_position: u32,
+    created_at: String,
+    #[serde(default)]
+    commit_id: Option<String>,
+    #[serde(default)]
+    original_commit_id: Option<String>,
+}
+
+impl GiteaApi {
+    /// Create a client for a host and its API base URL (e.g. https://codeberg.org/api/v1)
+    pub fn new(host: &str, api_url: &str, token: &str) -> Self {
+        let headers = vec![("Authorization", format!("token {}", token))];
+
+        Self {
+            http: HttpClient::new(api_url, headers, JSON_MEDIA_TYPE),
+            host: host.to_string(),
+            token: token.to_string(),
+            max_prs: DEFAULT_MAX_PRS,
+        }
+    }
+
+    /// Cap the number of PRs fetched per list query
+    pub fn with_max_prs(mut self, max_prs: usize) -> Self {
+        self.max_prs = max_prs;
+        self
+    }
+
+    fn pr_path(pr: &PrInfo) -> String {
+        format!("repos/{}/{}/pulls/{}", pr.owner, pr.repo, pr.number)
+    }
+
+    /// Search open PRs across all repositories visible to the user, up to the PR cap
+    async fn search_prs(&self, filters: &[(&str, &str)]) -> Result<PrList, ForgeError> {
+        self.search_prs_matching(filters, |_| true).await
+    }
+
+    /// Search open PRs, keeping those that pass `keep`; the PR cap counts kept PRs only
+    async fn search_prs_matching(
+        &self,
+        filters: &[(&str, &str)],
+        keep: impl Fn(&ApiIssue) -> bool,
+    ) -> Result<PrList, ForgeError> {
+        let mut query = vec![("type", "pulls"), ("state", "open"), ("limit", PER_PAGE)];
+        query.extend_from_slice(filters);
+
+        let (issues, truncated) = self
+            .http
+            .get_pages_matching("repos/issues/search", &query, Some(self.max_prs), keep)
+            .await?;
+        let prs = issues
+            .into_iter()
+            .map(|issue| ReviewPr {
+                host: self.host.clone(),
+                number: issue.number,
+                title: issue.title,
+                repo_owner: issue.repository.owner,
+                repo_name: issue.repository.name,
+                author: issue.user.login,
+                created_at: issue.created_at,
+                head_sha: None,
+                body: None, // Not included in search results
+                is_draft: false,
+                review_decision: None,
+                ci_state: None,
+                size: None,
+                updated_at: issue.updated_at,
+                review_reasons: Vec::new(),
+                notification: None,
+            })
+            .collect();
+
+        Ok(PrList {
+            prs,
+            truncated,
+            ..Default::default()
+        })
+    }
+
+    async fn post_issue_comment(&self, pr: &PrInfo, body: &str) -> Result<(), ForgeError> {
+        self.http
+            .post_json(
+                &format!(
+                    "repos/{}/{}/issues/{}/comments",
+                    pr.owner, pr.repo, pr.number
+                ),
+                &serde_json::json!({ "body": body }),
+            )
+            .await
+    }
+
+    /// Use the given head SHA, or fetch it when unknown
+    async fn resolve_head_sha(
+        &self,
+        pr: &PrInfo,
+        head_sha: Option<&str>,
+    ) -> Result<String, ForgeError> {
+        match head_sha {
+            Some(sha) => Ok(sha.to_string()),
+            None => self
+                .fetch_pr_details(pr)
+                .await?
+                .head_sha
+                .ok_or_else(|| ForgeError::Other("PR has no head SHA".to_string())),
+        }
+    }
+
+    /// Create a review with the given event and inline comments
+    async fn post_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: &str,
+        inline_comments: &[&PendingComment],
+        commit_id: &str,
+    ) -> Result<(), ForgeError> {
+        let comments: Vec<serde_json::Value> = inline_comments
+            .iter()
+            .map(|c| {
+                review_comment_json(
+                    c.file_path.as_deref().unwrap_or_default(),
+                    c.line_number,
+                    c.side,
+                    &c.body,
+                )
+            })
+            .collect();
+
+        let mut request_body = serde_json::json!({
+            "event": event,
+            "body": body,
+            "comments": comments,
+        });
+        if !commit_id.is_empty() {
+            request_body["commit_id"] = serde_json::json!(commit_id);
+        }
+
+        self.http
+            .post_json(&format!("{}/reviews", Self::pr_path(pr)), &request_body)
+            .await
+    }
+}
+
+/// A review comment on a line of the old or new version of a file
+/// Gitea has no multi-line comments, so these are anchored at their last line
+fn review_comment_json(
+    path: &str,
+    line: Option<u32>,
+    side: DiffSide,
+    body: &str,
+) -> serde_json::Value {
+    let position_key = match side {
+        DiffSide::Left => "old_position",
+        DiffSide::Right => "new_position",
+    };
+    let mut comment = serde_json::json!({ "path": path, "body": body });
+    comment[position_key] = serde_json::json!(line.unwrap_or(0));
+    comment
+}
+
+/// Body of a comment posted on the PR itself
+/// Gitea has no file-level comments, so those name their file in the body
+fn issue_comment_body(comment: &PendingComment) -> String {
+    match &comment.file_path {
+        Some(path) => format!("`{}`\n\n{}", path, comment.body),
+        None => comment.body.clone(),
+    }
+}
+
+/// Map Gitea's review event names onto GitHub-style ones used by the app
+fn review_event(event: &str) -> &str {
+    match event {
+        "APPROVE" => "APPROVED",
+        other => other,
+    }
+}
+
+/// Convert review comments into GitHub-shaped ones so the shared thread builder applies
+/// Gitea has no reply chains: comments on the same path, line and side form a conversation,
+/// replies point at the earliest comment of their conversation
+fn to_review_comments(mut comments: Vec<ApiReviewComment>) -> Vec<ReviewComment> {
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+    let mut roots: HashMap<(String, u32, DiffSide), u64> = HashMap::new();
+    comments
+        .into_iter()
+        .map(|comment| {
+            let (line, side) = if comment.position > 0 {
+                (comment.position, DiffSide::Right)
+            } else {
+                (comment.original_position, DiffSide::Left)
+            };
+            let root = *roots
+                .entry((comment.path.clone(), line, side))
+                .or_insert(comment.id);
+
+            ReviewComment {
+                id: comment.id,
+                body: comment.body,
+                user: CommentUser {
+                    login: comment.user.login,
+                },
+                path: comment.path,
+                line: (line > 0).then_some(line),
+                side,
+                start_line: None,
+                created_at: comment.created_at,
+                in_reply_to_id: (root != comment.id).then_some(root),
+                commit_id: comment.commit_id,
+                original_commit_id: comment.original_commit_id,
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl ForgeBackend for GiteaApi {
+    fn name(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn web_url(&self, pr: &PrInfo) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/{}/pulls/{}",
+            self.host, pr.owner, pr.repo, pr.number
+        ))
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        if self.token.is_empty() {
+            return Err(ForgeError::Auth(format!(
+                "No Gitea token found for {}. Set GITEA_TOKEN or add a token to [[hosts]] in config.toml",
+                self.host
+            )));
+        }
+        self.current_user().await.map(|_| ())
+    }
+
+    async fn current_user(&self) -> Result<String, ForgeError> {
+        let user: ApiUser = self.http.get_json("user", &[]).await?;
+        Ok(user.login)
+    }
+
+    async fn fetch_review_prs(&self) -> Result<PrList, ForgeError> {
+        let start = Instant::now();
+
+        // Fetch current user, requested PRs, and reviewed PRs in parallel
+        let (current_user, requested, reviewed) = tokio::join!(
+            self.current_user(),
+            self.search_prs(&[("review_requested", "true")]),
+            self.search_prs(&[("reviewed", "true")])
+        );
+
+        let (requested, reviewed) = (requested?, reviewed?);
+        let prs = PrList {
+            prs: merge_review_prs(
+                &current_user?,
+                vec![
+                    (ReviewReason::Direct, requested.prs),
+                    (ReviewReason::Reviewed, reviewed.prs),
+                ],
+            ),
+            truncated: requested.truncated || reviewed.truncated,
+            ..Default::default()
+        };
+
+        perf_log("fetch_review_prs (total)", start.elapsed().as_millis());
+        Ok(prs)
+    }
+
+    async fn fetch_my_prs(&self) -> Result<PrList, ForgeError> {
+        self.search_prs(&[("created", "true")]).await
+    }
+
+    async fn fetch_prs_by_author(&self, username: &str) -> Result<PrList, ForgeError> {
+        // The search endpoint has no author filter, so pages are filtered as they come in
+        self.search_prs_matching(&[], |issue| issue.user.login.eq_ignore_ascii_case(username))
+            .await
+    }
+
+    async fn fetch_pr_diff(&self, pr: &PrInfo) -> Result<String, ForgeError> {
+        let diff = self
+            .http
+            .get_text(
+                &format!("{}.diff", Self::pr_path(pr)),
+                "text/plain",
+                &format!("fetch_pr_diff #{}", pr.number),
+            )
+            .await
+            .map_err(|e| match e {
+                ForgeError::NotFound(_) => ForgeError::NotFound(format!(
+                    "PR not found: {}/{}/pulls/{}",
+                    pr.owner, pr.repo, pr.number
+                )),
+                other => other,
+            })?;
+
+        if diff.is_empty() {
+            return Err(ForgeError::Other("PR has no changes".to_string()));
+        }
+
+        Ok(diff)
+    }
+
+    async fn fetch_pr_details(&self, pr: &PrInfo) -> Result<ReviewPr, ForgeError> {
+        let details: ApiPullRequest = self.http.get_json(&Self::pr_path(pr), &[]).await?;
+
+        Ok(ReviewPr {
+            host: pr.host.clone(),
+            number: details.number,
+            title: details.title,
+            repo_owner: pr.owner.clone(),
+            repo_name: pr.repo.clone(),
+            author: details.user.login,
+            created_at: details.created_at,
+            head_sha: Some(details.head.sha),
+            body: details.body,
+            is_draft: false,
+            review_decision: None,
+            ci_state: None,
+            size: None,
+            updated_at: None,
+            review_reasons: Vec::new(),
+            notification: None,
+        })
+    }
+
+    async fn fetch_comment_threads(&self, pr: &PrInfo) -> Result<Vec<CommentThread>, ForgeError> {
+        let reviews_path = format!("{}/reviews", Self::pr_path(pr));
+        let issue_path = format!(
+            "repos/{}/{}/issues/{}/comments",
+            pr.owner, pr.repo, pr.number
+        );
+
+        let (reviews, issue_comments) = tokio::join!(
+            self.http
+                .get_pages::<ApiReview>(&reviews_path, &[("limit", PER_PAGE)], None),
+            self.http
+                .get_pages::<IssueComment>(&issue_path, &[("limit", PER_PAGE)], None)
+        );
+
+        // Inline comments are only reachable through the review they belong to
+        let mut review_comments = Vec::new();
+        for review in reviews?.0.into_iter().filter(|r| r.comments_count > 0) {
+            let comments: Vec<ApiReviewComment> = self
+                .http
+                .get_json(&format!("{}/{}/comments", reviews_path, review.id), &[])
+                .await?;
+            review_comments.extend(comments);
+        }
+
+        Ok(build_comment_threads(
+            to_review_comments(review_comments),
+            issue_comments?.0,
+        ))
+    }
+
+    async fn submit_comments(
+        &self,
+        pr: &PrInfo,
+        comments: &[PendingComment],
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        if comments.is_empty() {
+            return Ok(0);
+        }
+
+        // Separate inline and general comments
+        let (inline_comments, general_comments): (Vec<_>, Vec<_>) =
+            comments.iter().partition(|c| c.is_inline());
+
+        let mut submitted = 0;
+
+        // Batch submit inline comments as a single COMMENT review
+        if !inline_comments.is_empty() {
+            let commit_id = self.resolve_head_sha(pr, head_sha).await?;
+            self.post_review(pr, "COMMENT", "", &inline_comments, &commit_id)
+                .await?;
+            submitted += inline_comments.len();
+        }
+
+        for comment in general_comments {
+            self.post_issue_comment(pr, &issue_comment_body(comment))
+                .await?;
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+
+    async fn submit_thread_reply(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        match &thread.file_path {
+            // A comment on the same line and side joins the conversation
+            Some(path) => {
+                let commit_id = self.resolve_head_sha(pr, None).await?;
+                let request_body = serde_json::json!({
+                    "event": "COMMENT",
+                    "body": "",
+                    "commit_id": commit_id,
+                    "comments": [review_comment_json(path, thread.line, thread.side, body)],
+                });
+                self.http
+                    .post_json(&format!("{}/reviews", Self::pr_path(pr)), &request_body)
+                    .await
+            }
+            None => self.post_issue_comment(pr, body).await,
+        }
+    }
+
+    async fn submit_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: Option<&str>,
+        pending_comments: Option<&[PendingComment]>,
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        // REQUEST_CHANGES requires a body
+        if event == "REQUEST_CHANGES" && body.map(|b| b.is_empty()).unwrap_or(true) {
+            return Err(ForgeError::Validation(
+                "Request changes requires a comment".to_string(),
+            ));
+        }
+
+        // Separate inline and general comments
+        let (inline_comments, general_comments): (Vec<_>, Vec<_>) = pending_comments
+            .map(|c| c.iter().partition(|c| c.is_inline()))
+            .unwrap_or_default();
+
+        // Get head SHA if we have inline comments
+        let commit_id = if !inline_comments.is_empty() {
+            self.resolve_head_sha(pr, head_sha).await?
+        } else {
+            String::new()
+        };
+
+        self.post_review(
+            pr,
+            review_event(event),
+            body.unwrap_or_default(),
+            &inline_comments,
+            &commit_id,
+        )
+        .await?;
+
+        let mut submitted = inline_comments.len();
+
+        // Submit general comments (these can't be batched into a review)
+        for comment in general_comments {
+            self.post_issue_comment(pr, &issue_comment_body(comment))
+                .await?;
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::{MockResponse, MockServer};
+
+    fn test_pr() -> PrInfo {
+        PrInfo {
+            host: "code.example.com".to_string(),
+            owner: "tools".to_string(),
+            repo: "ci".to_string(),
+            number: 12,
+        }
+    }
+
+    #[test]
+    fn test_to_review_comments_groups_by_line_and_side() {
+        let comments: Vec<ApiReviewComment> = serde_json::from_str(
+            r#"[
+                {"id": 3, "body": "Reply", "user": {"login": "alice"}, "path": "main.go",
+                 "position": 10, "created_at": "2024-01-02T00:00:00Z"},
+                {"id": 1, "body": "Root", "user": {"login": "bob"}, "path": "main.go",
+                 "position": 10, "created_at": "2024-01-01T00:00:00Z"},
+                {"id": 2, "body": "Removed line", "user": {"login": "bob"}, "path": "main.go",
+                 "position": 0, "original_position": 4, "created_at": "2024-01-01T00:00:00Z"},
+                {"id": 4, "body": "Old line 10", "user": {"login": "bob"}, "path": "main.go",
+                 "position": 0, "original_position": 10, "created_at": "2024-01-03T00:00:00Z"}
+            ]"#,
+        )
+        .unwrap();
+
+        let threads = build_comment_threads(to_review_comments(comments), Vec::new());
+        assert_eq!(threads.len(), 3);
+        assert_eq!(threads[0].line, Some(4));
+        assert_eq!(threads[0].side, DiffSide::Left);
+        assert_eq!(threads[0].comment_count(), 1);
+        assert_eq!(threads[1].id, 1);
+        assert_eq!(threads[1].line, Some(10));
+        assert_eq!(threads[1].comment_count(), 2);
+        assert_eq!(threads[1].comments[1].body, "Reply");
+        assert_eq!(threads[2].id, 4);
+        assert_eq!(threads[2].line, Some(10));
+        assert_eq!(threads[2].side, DiffSide::Left);
+        assert_eq!(threads[2].comment_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_diff_uses_diff_endpoint() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/tools/ci/pulls/12.diff",
+            MockResponse::text(200, "diff --git a/x b/x\n"),
+        );
+        let api = GiteaApi::new("code.example.com", &server.url, "secret");
+
+        let diff = api.fetch_pr_diff(&test_pr()).await.unwrap();
+        assert_eq!(diff, "diff --git a/x b/x\n");
+
+        let requests = server.requests_to("GET", "/repos/tools/ci/pulls/12.diff");
+        assert_eq!(requests[0].header("authorization"), Some("token secret"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_diff_not_found() {
+        let server = MockServer::start().await;
+        let api = GiteaApi::new("code.example.com", &server.url, "secret");
+
+        let err = api.fetch_pr_diff(&test_pr()).await.unwrap_err();
+        assert_eq!(
+            err,
+            ForgeError::NotFound("PR not found: tools/ci/pulls/12".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_without_token() {
+        let api = GiteaApi::new("code.example.com", "http://127.0.0.1:1", "");
+        assert!(matches!(api.check_auth().await, Err(ForgeError::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_prs() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(200, r#"{"login": "me"}"#),
+        );
+        server.route(
+            "GET",
+            "/repos/issues/search",
+            MockResponse::json(
+                200,
+                r#"[
+                    {"number": 1, "title": "One", "user": {"login": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "repository": {"name": "ci", "owner": "tools", "full_name": "tools/ci"}},
+                    {"number": 2, "title": "Mine", "user": {"login": "me"},
+                     "created_at": "2024-01-02T00:00:00Z",
+                     "repository": {"name": "web", "owner": "tools", "full_name": "tools/web"}}
+                ]"#,
+            ),
+        );
+        let api = GiteaApi::new("code.example.com", &server.url, "secret");
+
+        let prs = api.fetch_review_prs().await.unwrap().prs;
+        // Requested list keeps both, reviewed duplicates are dropped
+        assert_eq!(prs.len(), 2);
+        assert_eq!(prs[0].host, "code.example.com");
+        assert_eq!(prs[0].repo_full_name(), "code.example.com/tools/ci");
+
+        let queries: Vec<String> = server
+            .requests_to("GET", "/repos/issues/search")
+            .into_iter()
+            .map(|r| r.path)
+            .collect();
+        assert!(queries.iter().any(|q| q.contains("review_requested=true")));
+        assert!(queries.iter().any(|q| q.contains("reviewed=true")));
+        assert!(queries.iter().all(|q| q.contains("type=pulls")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_prs_by_author_pages_past_other_authors() {
+        let server = MockServer::start().await;
+        let pr = |number: u32, author: &str| {
+            format!(
+                r#"{{"number": {}, "title": "PR", "user": {{"login": "{}"}},
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "repository": {{"name": "web", "owner": "tools", "full_name": "tools/web"}}}}"#,
+                number, author
+            )
+        };
+        let page2 = format!("{}/search_page_2", server.url);
+        server.route(
+            "GET",
+            "/repos/issues/search",
+            MockResponse::json(200, &format!("[{}, {}]", pr(1, "bob"), pr(2, "carol")))
+                .with_header("link", &format!("<{}>; rel=\"next\"", page2)),
+        );
+        server.route(
+            "GET",
+            "/search_page_2",
+            MockResponse::json(200, &format!("[{}, {}]", pr(3, "alice"), pr(4, "bob"))),
+        );
+        let api = GiteaApi::new("code.example.com", &server.url, "secret").with_max_prs(1);
+
+        let list = api.fetch_prs_by_author("Alice").await.unwrap();
+        let numbers: Vec<u32> = list.prs.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![3]);
+        assert!(!list.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_comment_threads() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/tools/ci/pulls/12/reviews",
+            MockResponse::json(
+                200,
+                r#"[{"id": 5, "comments_count": 2}, {"id": 6, "comments_count": 0}]"#,
+            ),
+        );
+        server.route(
+            "GET",
+            "/repos/tools/ci/pulls/12/reviews/5/comments",
+            MockResponse::json(
+                200,
+                r#"[
+                    {"id": 1, "body": "Root", "user": {"login": "a"}, "path": "main.go",
+                     "position": 3, "created_at": "2024-01-01T00:00:00Z"},
+                    {"id": 2, "body": "Reply", "user": {"login": "b"}, "path": "main.go",
+                     "position": 3, "created_at": "2024-01-01T01:00:00Z"}
+                ]"#,
+            ),
+        );
+        server.route(
+            "GET",
+            "/repos/tools/ci/issues/12/comments",
+            MockResponse::json(
+                200,
+                r#"[{"id": 10, "body": "LGTM", "user": {"login": "c"}, "created_at": "2024-01-02T00:00:00Z"}]"#,
+            ),
+        );
+        let api = GiteaApi::new("code.example.com", &server.url, "secret");
+
+        let threads = api.fetch_comment_threads(&test_pr()).await.unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].file_path.as_deref(), Some("main.go"));
+        assert_eq!(threads[0].comment_count(), 2);
+        assert_eq!(threads[1].comments[0].body, "LGTM");
+        // Reviews without comments are not fetched
+        assert!(
+            server
+                .requests_to("GET", "/repos/tools/ci/pulls/12/reviews/6/comments")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_issue_comment_body() {
+        let general = PendingComment::new_general("LGTM".to_string());
+        assert_eq!(issue_comment_body(&general), "LGTM");
+
+        let file = PendingComment::new_file("Split this".to_string(), "src/a.rs".to_string());
+        assert_eq!(issue_comment_body(&file), "`src/a.rs`\n\nSplit this");
+    }
+
+    #[test]
+    fn test_review_comment_json_sides() {
+        let new = review_comment_json("src/a.rs", Some(4), DiffSide::Right, "Fix");
+        assert_eq!(new["new_position"], 4);
+        assert!(new.get("old_position").is_none());
+
+        let old = review_comment_json("src/a.rs", Some(9), DiffSide::Left, "Why remove?");
+        assert_eq!(old["old_position"], 9);
+        assert!(old.get("new_position").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_review_approve() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/repos/tools/ci/pulls/12/reviews",
+            MockResponse::json(200, r#"{"id": 1}"#),
+        );
+        server.route(
+            "POST",
+            "/repos/tools/ci/issues/12/comments",
+            MockResponse::json(201, r#"{"id": 2}"#),
+        );
+        let api = GiteaApi::new("code.example.com", &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_multiline("Fix".to_string(), "main.go".to_string(), 4, 6),
+            PendingComment::new_general("General".to_string()),
+        ];
+        let submitted = api
+            .submit_review(
+                &test_pr(),
+                "APPROVE",
+                Some("Looks good"),
+                Some(&comments),
+                Some("sha1"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(submitted, 2);
+
+        let review = server.requests_to("POST", "/repos/tools/ci/pulls/12/reviews");
+        assert_eq!(review.len(), 1);
+        let json = review[0].json();
+        assert_eq!(json["event"], "APPROVED");
+        assert_eq!(json["body"], "Looks good");
+        assert_eq!(json["commit_id"], "sha1");
+        assert_eq!(json["comments"][0]["path"], "main.go");
+        assert_eq!(json["comments"][0]["new_position"], 6);
+
+        let general = server.requests_to("POST", "/repos/tools/ci/issues/12/comments");
+        assert_eq!(general.len(), 1);
+        assert_eq!(general[0].json()["body"], "General");
+    }
+
+    #[tokio::test]
+    async fn test_submit_thread_reply_inline() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/tools/ci/pulls/12",
+            MockResponse::json(
+                200,
+                r#"{"number": 12, "title": "T", "user": {"login": "a"},
+                    "created_at": "2024-01-01T00:00:00Z", "head": {"sha": "head1"}}"#,
+            ),
+        );
+        server.route(
+            "POST",
+            "/repos/tools/ci/pulls/12/reviews",
+            MockResponse::json(200, r#"{"id": 1}"#),
+        );
+        let api = GiteaApi::new("code.example.com", &server.url, "secret");
+
+        let thread = CommentThread {
+            id: 1,
+            node_id: None,
+            file_path: Some("main.go".to_string()),
+            line: Some(3),
+            side: DiffSide::Left,
+            start_line: None,
+            comments: Vec::new(),
+            outdated: false,
+            resolved: false,
+        };
+        api.submit_thread_reply(&test_pr(), &thread, "Done")
+            .await
+            .unwrap();
+
+        let requests = server.requests_to("POST", "/repos/tools/ci/pulls/12/reviews");
+        assert_eq!(requests.len(), 1);
+        let json = requests[0].json();
+        assert_eq!(json["event"], "COMMENT");
+        assert_eq!(json["commit_id"], "head1");
+        assert_eq!(json["comments"][0]["old_position"], 3);
+        assert!(json["comments"][0].get("new_position").is_none());
+        assert_eq!(json["comments"][0]["body"], "Done");
+    }
+}
@@ -0,0 +1,1419 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use reqwest::Method;
+use serde::Deserialize;
+use std::time::Instant;
+
+use super::github_graphql::{self, GraphqlTransport};
+use super::http::HttpClient;
+use super::{ForgeBackend, ForgeError};
+use crate::config::DEFAULT_MAX_PRS;
+use crate::github::{
+    MAX_TEAM_SEARCHES, NotificationItem, build_comment_threads, build_review_comments_json,
+    build_review_request_body, comment_path, merge_review_prs, notification_prs, perf_log,
+    review_request_lists,
+};
+use crate::types::{
+    CheckRun, CommentThread, IssueComment, MergeInfo, MergeRequest, PendingComment, PrInfo, PrList,
+    PrMetadata, ReactionKind, ReviewComment, ReviewPr, ReviewReason, ReviewState,
+};
+
+/// Page size for list endpoints (GitHub's maximum)
+const PER_PAGE: &str = "100";
+
+/// Media type for regular JSON responses
+const JSON_MEDIA_TYPE: &str = "application/vnd.github+json";
+
+/// Media type for raw unified diffs
+const DIFF_MEDIA_TYPE: &str = "application/vnd.github.v3.diff";
+
+/// Native GitHub REST API client
+pub struct GithubApi {
+    http: HttpClient,
+    graphql_url: String,
+    host: String,
+    token: String,
+    max_prs: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTeam {
+    slug: String,
+    organization: ApiUser,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPullRequest {
+    number: u32,
+    title: String,
+    user: ApiUser,
+    created_at: String,
+    head: ApiRef,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiSearchItem {
+    number: u32,
+    title: String,
+    user: ApiUser,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    /// e.g. https://api.github.com/repos/owner/name
+    repository_url: String,
+}
+
+impl GithubApi {
+    /// Create a client for a host and its API base URL (e.g. https://api.github.com)
+    pub fn new(host: &str, api_url: &str, token: &str) -> Self {
+        let headers = vec![
+            ("Authorization", format!("Bearer {}", token)),
+            ("X-GitHub-Api-Version", "2022-11-28".to_string()),
+        ];
+
+        Self {
+            http: HttpClient::new(api_url, headers, JSON_MEDIA_TYPE),
+            graphql_url: github_graphql::graphql_url(api_url),
+            host: host.to_string(),
+            token: token.to_string(),
+            max_prs: DEFAULT_MAX_PRS,
+        }
+    }
+
+    /// Cap the number of PRs fetched per list query
+    pub fn with_max_prs(mut self, max_prs: usize) -> Self {
+        self.max_prs = max_prs;
+        self
+    }
+
+    fn pr_path(pr: &PrInfo) -> String {
+        format!("repos/{}/{}/pulls/{}", pr.owner, pr.repo, pr.number)
+    }
+
+    /// Teams of the current user, as "org/team" (needs the read:org scope)
+    async fn user_teams(&self) -> Result<Vec<String>, ForgeError> {
+        let (teams, _) = self
+            .http
+            .get_pages::<ApiTeam>("user/teams", &[("per_page", PER_PAGE)], None)
+            .await?;
+        Ok(teams
+            .into_iter()
+            .map(|team| format!("{}/{}", team.organization.login, team.slug))
+            .collect())
+    }
+
+    /// Run an issue search restricted to open PRs, following pages up to the PR cap
+    async fn search_prs(&self, qualifiers: &str) -> Result<PrList, ForgeError> {
+        let query = format!("is:pr is:open {}", qualifiers);
+        let (items, truncated) = self
+            .http
+            .get_pages::<ApiSearchItem>(
+                "search/issues",
+                &[("q", &query), ("per_page", PER_PAGE)],
+                Some(self.max_prs),
+            )
+            .await?;
+
+        let prs = items
+            .into_iter()
+            .map(|item| {
+                let mut parts = item.repository_url.rsplit('/');
+                let repo_name = parts.next().unwrap_or_default().to_string();
+                let repo_owner = parts.next().unwrap_or_default().to_string();
+
+                ReviewPr {
+                    host: self.host.clone(),
+                    number: item.number,
+                    title: item.title,
+                    repo_owner,
+                    repo_name,
+                    author: item.user.login,
+                    created_at: item.created_at,
+                    head_sha: None,
+                    body: None, // Not included in search results
+                    is_draft: item.draft,
+                    review_decision: None,
+                    ci_state: None,
+                    size: None,
+                    updated_at: item.updated_at,
+                    review_reasons: Vec::new(),
+                    notification: None,
+                }
+            })
+            .collect();
+
+        Ok(PrList {
+            prs,
+            truncated,
+            ..Default::default()
+        })
+    }
+
+    async fn post_issue_comment(&self, pr: &PrInfo, body: &str) -> Result<(), ForgeError> {
+        self.http
+            .post_json(
+                &format!(
+                    "repos/{}/{}/issues/{}/comments",
+                    pr.owner, pr.repo, pr.number
+                ),
+                &serde_json::json!({ "body": body }),
+            )
+            .await
+    }
+
+    /// Use the given head SHA, or fetch it when unknown
+    async fn resolve_head_sha(
+        &self,
+        pr: &PrInfo,
+        head_sha: Option<&str>,
+    ) -> Result<String, ForgeError> {
+        match head_sha {
+            Some(sha) => Ok(sha.to_string()),
+            None => self
+                .fetch_pr_details(pr)
+                .await?
+                .head_sha
+                .ok_or_else(|| ForgeError::Other("PR has no head SHA".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl GraphqlTransport for GithubApi {
+    async fn graphql(&self, body: &serde_json::Value) -> Result<serde_json::Value, ForgeError> {
+        self.http
+            .post_json_url(&self.graphql_url, body, "POST graphql")
+            .await
+    }
+}
+
+#[async_trait]
+impl ForgeBackend for GithubApi {
+    fn name(&self) -> &'static str {
+        "api"
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        if self.token.is_empty() {
+            return Err(ForgeError::Auth(
+                "No GitHub token found. Set GH_TOKEN, add github.token to config.toml, or run: gh auth login"
+                    .to_string(),
+            ));
+        }
+        self.current_user().await.map(|_| ())
+    }
+
+    async fn current_user(&self) -> Result<String, ForgeError> {
+        let user: ApiUser = self.http.get_json("user", &[]).await?;
+        Ok(user.login)
+    }
+
+    async fn fetch_review_prs(&self) -> Result<PrList, ForgeError> {
+        let start = Instant::now();
+
+        // Fetch current user, teams, requested PRs, and reviewed PRs in parallel
+        let (current_user, teams, requested, direct, reviewed) = tokio::join!(
+            self.current_user(),
+            self.user_teams(),
+            self.search_prs("review-requested:@me"),
+            self.search_prs("user-review-requested:@me"),
+            self.search_prs("reviewed-by:@me")
+        );
+
+        let (requested, reviewed) = (requested?, reviewed?);
+        let mut truncated = requested.truncated || reviewed.truncated;
+
+        // Team searches only tag requested PRs; failed searches leave their PRs untagged
+        let teams: Vec<String> = teams
+            .unwrap_or_default()
+            .into_iter()
+            .take(MAX_TEAM_SEARCHES)
+            .collect();
+        let team_results = join_all(teams.iter().map(|team| async move {
+            self.search_prs(&format!("team-review-requested:{}", team))
+                .await
+        }))
+        .await;
+        let team_prs = teams
+            .into_iter()
+            .zip(team_results)
+            .filter_map(|(team, result)| result.ok().map(|list| (team, list)))
+            .map(|(team, list)| {
+                truncated |= list.truncated;
+                (team, list.prs)
+            })
+            .collect();
+        let direct = direct.ok().map(|list| {
+            truncated |= list.truncated;
+            list.prs
+        });
+
+        let mut lists = review_request_lists(requested.prs, direct, team_prs);
+        lists.push((ReviewReason::Reviewed, reviewed.prs));
+
+        let mut prs = PrList {
+            prs: merge_review_prs(&current_user?, lists),
+            truncated,
+            ..Default::default()
+        };
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+
+        perf_log("fetch_review_prs (total)", start.elapsed().as_millis());
+        Ok(prs)
+    }
+
+    async fn fetch_my_prs(&self) -> Result<PrList, ForgeError> {
+        let mut prs = self.search_prs("author:@me").await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_prs_by_author(&self, username: &str) -> Result<PrList, ForgeError> {
+        let mut prs = self.search_prs(&format!("author:{}", username)).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_search_prs(&self, query: &str) -> Result<PrList, ForgeError> {
+        let mut prs = self.search_prs(query).await?;
+        github_graphql::add_list_details(self, &mut prs.prs).await;
+        Ok(prs)
+    }
+
+    async fn fetch_notifications(&self) -> Result<PrList, ForgeError> {
+        // Unread only, which is the endpoint's default
+        let (items, truncated) = self
+            .http
+            .get_pages::<NotificationItem>(
+                "notifications",
+                &[("per_page", PER_PAGE)],
+                Some(self.max_prs),
+            )
+            .await?;
+        let mut prs = notification_prs(&self.host, items);
+        github_graphql::add_list_details(self, &mut prs).await;
+        Ok(PrList {
+            prs,
+            truncated,
+            ..Default::default()
+        })
+    }
+
+    async fn mark_notification(&self, thread_id: &str, done: bool) -> Result<(), ForgeError> {
+        let path = format!("notifications/threads/{}", thread_id);
+        if done {
+            self.http.delete(&path).await
+        } else {
+            self.http
+                .send_json(Method::PATCH, &path, &serde_json::json!({}))
+                .await
+        }
+    }
+
+    async fn fetch_pr_diff(&self, pr: &PrInfo) -> Result<String, ForgeError> {
+        let diff = self
+            .http
+            .get_text(
+                &Self::pr_path(pr),
+                DIFF_MEDIA_TYPE,
+                &format!("fetch_pr_diff #{}", pr.number),
+            )
+            .await
+            .map_err(|e| match e {
+                ForgeError::NotFound(_) => ForgeError::NotFound(format!(
+                    "PR not found: {}/{}/pull/{}",
+                    pr.owner, pr.repo, pr.number
+                )),
+                other => other,
+            })?;
+
+        if diff.is_empty() {
+            return Err(ForgeError::Other("PR has no changes".to_string()));
+        }
+
+        Ok(diff)
+    }
+
+    async fn fetch_compare_diff(
+        &self,
+        pr: &PrInfo,
+        base: &str,
+        head: &str,
+    ) -> Result<String, ForgeError> {
+        let path = format!("repos/{}/{}/compare/{}...{}", pr.owner, pr.repo, base, head);
+        self.http
+            .get_text(
+                &path,
+                DIFF_MEDIA_TYPE,
+                &format!("fetch_compare_diff #{}", pr.number),
+            )
+            .await
+            .map_err(|e| match e {
+                ForgeError::NotFound(_) => ForgeError::NotFound(format!(
+                    "Commit {} not found in {}/{}",
+                    base, pr.owner, pr.repo
+                )),
+                other => other,
+            })
+    }
+
+    async fn fetch_pr_details(&self, pr: &PrInfo) -> Result<ReviewPr, ForgeError> {
+        let details: ApiPullRequest = self.http.get_json(&Self::pr_path(pr), &[]).await?;
+
+        Ok(ReviewPr {
+            host: pr.host.clone(),
+            number: details.number,
+            title: details.title,
+            repo_owner: pr.owner.clone(),
+            repo_name: pr.repo.clone(),
+            author: details.user.login,
+            created_at: details.created_at,
+            head_sha: Some(details.head.sha),
+            body: details.body,
+            is_draft: false,
+            review_decision: None,
+            ci_state: None,
+            size: None,
+            updated_at: None,
+            review_reasons: Vec::new(),
+            notification: None,
+        })
+    }
+
+    async fn fetch_comment_threads(&self, pr: &PrInfo) -> Result<Vec<CommentThread>, ForgeError> {
+        let review_path = format!("{}/comments", Self::pr_path(pr));
+        let issue_path = format!(
+            "repos/{}/{}/issues/{}/comments",
+            pr.owner, pr.repo, pr.number
+        );
+
+        // Fetch both types concurrently, every page
+        let (review_result, issue_result) = tokio::join!(
+            self.http
+                .get_pages::<ReviewComment>(&review_path, &[("per_page", PER_PAGE)], None),
+            self.http
+                .get_pages::<IssueComment>(&issue_path, &[("per_page", PER_PAGE)], None)
+        );
+
+        Ok(build_comment_threads(review_result?.0, issue_result?.0))
+    }
+
+    async fn fetch_review_state(&self, pr: &PrInfo) -> Result<ReviewState, ForgeError> {
+        let start = Instant::now();
+        let state = github_graphql::fetch_review_state(self, pr).await?;
+        perf_log(
+            &format!("fetch_review_state #{}", pr.number),
+            start.elapsed().as_millis(),
+        );
+        Ok(state)
+    }
+
+    async fn submit_comments(
+        &self,
+        pr: &PrInfo,
+        comments: &[PendingComment],
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        if comments.is_empty() {
+            return Ok(0);
+        }
+
+        // Separate inline (and file-level) comments from general ones
+        let (inline_comments, general_comments): (Vec<_>, Vec<_>) =
+            comments.iter().partition(|c| c.is_review_comment());
+
+        let pending =
+            github_graphql::submit_pending_review(self, pr, "COMMENT", None, &inline_comments)
+                .await?;
+        let mut submitted = pending.unwrap_or(0);
+
+        // Batch submit inline comments using the Review API (single request)
+        if pending.is_none() && !inline_comments.is_empty() {
+            let commit_id = self.resolve_head_sha(pr, head_sha).await?;
+            let request_body = serde_json::json!({
+                "commit_id": commit_id,
+                "event": "COMMENT",
+                "comments": build_review_comments_json(&inline_comments)
+            });
+            self.http
+                .post_json(&format!("{}/reviews", Self::pr_path(pr)), &request_body)
+                .await?;
+            submitted += inline_comments.len();
+        }
+
+        // General comments can't be batched via the Review API
+        for comment in general_comments {
+            self.post_issue_comment(pr, &comment.body).await?;
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+
+    async fn submit_thread_reply(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        if thread.is_inline() {
+            // Reply to review comment using in_reply_to
+            self.http
+                .post_json(
+                    &format!("{}/comments", Self::pr_path(pr)),
+                    &serde_json::json!({ "body": body, "in_reply_to": thread.id }),
+                )
+                .await
+        } else {
+            // Reply to issue comment (just add a new issue comment)
+            self.post_issue_comment(pr, body).await
+        }
+    }
+
+    async fn set_thread_resolved(
+        &self,
+        _pr: &PrInfo,
+        thread: &CommentThread,
+        resolved: bool,
+    ) -> Result<(), ForgeError> {
+        github_graphql::set_thread_resolved(self, thread, resolved).await
+    }
+
+    async fn set_reaction(
+        &self,
+        subject_id: &str,
+        kind: ReactionKind,
+        add: bool,
+    ) -> Result<(), ForgeError> {
+        github_graphql::set_reaction(self, subject_id, kind, add).await
+    }
+
+    async fn fetch_checks(&self, pr: &PrInfo, head_sha: &str) -> Result<Vec<CheckRun>, ForgeError> {
+        github_graphql::fetch_checks(self, pr, head_sha).await
+    }
+
+    async fn fetch_merge_info(&self, pr: &PrInfo) -> Result<MergeInfo, ForgeError> {
+        github_graphql::fetch_merge_info(self, pr).await
+    }
+
+    async fn merge_pr(&self, pr: &PrInfo, request: &MergeRequest) -> Result<(), ForgeError> {
+        github_graphql::merge_pr(self, pr, request).await
+    }
+
+    async fn fetch_metadata(&self, pr: &PrInfo) -> Result<PrMetadata, ForgeError> {
+        github_graphql::fetch_metadata(self, pr).await
+    }
+
+    async fn update_metadata(
+        &self,
+        pr: &PrInfo,
+        original: &PrMetadata,
+        updated: &PrMetadata,
+    ) -> Result<(), ForgeError> {
+        github_graphql::update_metadata(self, pr, original, updated).await
+    }
+
+    async fn edit_comment(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        self.http
+            .send_json(
+                Method::PATCH,
+                &comment_path(pr, thread, comment_id),
+                &serde_json::json!({ "body": body }),
+            )
+            .await
+    }
+
+    async fn delete_comment(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        comment_id: u64,
+    ) -> Result<(), ForgeError> {
+        self.http
+            .delete(&comment_path(pr, thread, comment_id))
+            .await
+    }
+
+    async fn add_pending_comment(
+        &self,
+        pr: &PrInfo,
+        comment: &PendingComment,
+    ) -> Result<String, ForgeError> {
+        github_graphql::add_pending_comment(self, pr, comment).await
+    }
+
+    async fn update_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        remote_id: &str,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        github_graphql::update_pending_comment(self, remote_id, body).await
+    }
+
+    async fn delete_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        remote_id: &str,
+    ) -> Result<(), ForgeError> {
+        github_graphql::delete_pending_comment(self, remote_id).await
+    }
+
+    async fn submit_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: Option<&str>,
+        pending_comments: Option<&[PendingComment]>,
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        // REQUEST_CHANGES requires a body
+        if event == "REQUEST_CHANGES" && body.map(|b| b.is_empty()).unwrap_or(true) {
+            return Err(ForgeError::Validation(
+                "Request changes requires a comment".to_string(),
+            ));
+        }
+
+        // Separate inline (and file-level) comments from general ones
+        let (inline_comments, general_comments): (Vec<_>, Vec<_>) = pending_comments
+            .map(|c| c.iter().partition(|c| c.is_review_comment()))
+            .unwrap_or_default();
+
+        let mut submitted =
+            match github_graphql::submit_pending_review(self, pr, event, body, &inline_comments)
+                .await?
+            {
+                Some(submitted) => submitted,
+                None => {
+                    // Get head SHA if we have inline comments
+                    let commit_id = if !inline_comments.is_empty() {
+                        self.resolve_head_sha(pr, head_sha).await?
+                    } else {
+                        String::new()
+                    };
+
+                    let request_body =
+                        build_review_request_body(event, body, &inline_comments, &commit_id);
+                    self.http
+                        .post_json(&format!("{}/reviews", Self::pr_path(pr)), &request_body)
+                        .await?;
+                    inline_comments.len()
+                }
+            };
+
+        // Submit general comments (these can't be batched via Review API)
+        for comment in general_comments {
+            self.post_issue_comment(pr, &comment.body).await?;
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::{MockResponse, MockServer};
+    use crate::types::{DEFAULT_HOST, DiffSide};
+
+    fn test_pr() -> PrInfo {
+        PrInfo {
+            host: DEFAULT_HOST.to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 7,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_diff_sends_auth_and_accept_headers() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/owner/repo/pulls/7",
+            MockResponse::text(200, "diff --git a/x b/x\n"),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let diff = api.fetch_pr_diff(&test_pr()).await.unwrap();
+        assert_eq!(diff, "diff --git a/x b/x\n");
+
+        let requests = server.requests_to("GET", "/repos/owner/repo/pulls/7");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+        assert_eq!(requests[0].header("accept"), Some(DIFF_MEDIA_TYPE));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_compare_diff() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/owner/repo/compare/old1...new2",
+            MockResponse::text(200, "diff --git a/x b/x\n"),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let diff = api
+            .fetch_compare_diff(&test_pr(), "old1", "new2")
+            .await
+            .unwrap();
+        assert_eq!(diff, "diff --git a/x b/x\n");
+        let requests = server.requests_to("GET", "/repos/owner/repo/compare/old1...new2");
+        assert_eq!(requests[0].header("accept"), Some(DIFF_MEDIA_TYPE));
+
+        let err = api
+            .fetch_compare_diff(&test_pr(), "gone", "new2")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ForgeError::NotFound("Commit gone not found in owner/repo".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_diff_not_found() {
+        let server = MockServer::start().await;
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let err = api.fetch_pr_diff(&test_pr()).await.unwrap_err();
+        assert_eq!(
+            err,
+            ForgeError::NotFound("PR not found: owner/repo/pull/7".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enterprise_host_is_stamped_on_prs() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/search/issues",
+            MockResponse::json(
+                200,
+                r#"{"items": [{"number": 3, "title": "GHE", "user": {"login": "bob"},
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "repository_url": "https://ghe.example.com/api/v3/repos/corp/svc"}]}"#,
+            ),
+        );
+        let api = GithubApi::new("ghe.example.com", &server.url, "secret");
+
+        let prs = api.fetch_my_prs().await.unwrap().prs;
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].host, "ghe.example.com");
+        assert_eq!(prs[0].repo_full_name(), "ghe.example.com/corp/svc");
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_rejected_token() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(401, r#"{"message": "Bad credentials"}"#),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "bad");
+
+        let err = api.check_auth().await.unwrap_err();
+        assert_eq!(
+            err,
+            ForgeError::Auth("Bad credentials (HTTP 401)".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_without_token() {
+        let api = GithubApi::new(DEFAULT_HOST, "http://127.0.0.1:1", "");
+        assert!(matches!(api.check_auth().await, Err(ForgeError::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_from_header() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(403, r#"{"message": "Forbidden"}"#)
+                .with_header("x-ratelimit-remaining", "0"),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let err = api.current_user().await.unwrap_err();
+        assert!(matches!(err, ForgeError::RateLimited(_)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_details() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/owner/repo/pulls/7",
+            MockResponse::json(
+                200,
+                r#"{"number": 7, "title": "Add feature", "user": {"login": "alice"},
+                    "created_at": "2024-01-01T00:00:00Z", "head": {"sha": "abc123"},
+                    "body": "Description"}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let pr = api.fetch_pr_details(&test_pr()).await.unwrap();
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.title, "Add feature");
+        assert_eq!(pr.author, "alice");
+        assert_eq!(pr.repo_full_name(), "owner/repo");
+        assert_eq!(pr.head_sha.as_deref(), Some("abc123"));
+        assert_eq!(pr.body.as_deref(), Some("Description"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_prs_merges_and_skips_own() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(200, r#"{"login": "me"}"#),
+        );
+        // All searches hit the same endpoint; the mock returns the same page for each
+        server.route(
+            "GET",
+            "/search/issues",
+            MockResponse::json(
+                200,
+                r#"{"items": [
+                    {"number": 1, "title": "One", "user": {"login": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/api"},
+                    {"number": 2, "title": "Mine", "user": {"login": "me"},
+                     "created_at": "2024-01-02T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/web"}
+                ]}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let prs = api.fetch_review_prs().await.unwrap().prs;
+        // Requested list keeps both, reviewed duplicates are dropped
+        assert_eq!(prs.len(), 2);
+        assert_eq!(prs[0].host, DEFAULT_HOST);
+        assert_eq!(prs[0].repo_owner, "org");
+        assert_eq!(prs[0].repo_name, "api");
+
+        let queries: Vec<String> = server
+            .requests_to("GET", "/search/issues")
+            .into_iter()
+            .map(|r| r.path)
+            .collect();
+        assert_eq!(queries.len(), 3);
+        assert!(
+            queries
+                .iter()
+                .any(|q| q.contains("review-requested%3A%40me"))
+        );
+        assert!(queries.iter().any(|q| q.contains("reviewed-by%3A%40me")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_prs_caps_team_searches() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(200, r#"{"login": "me"}"#),
+        );
+        let teams: Vec<String> = (1..=7)
+            .map(|i| {
+                format!(
+                    r#"{{"slug": "team{}", "organization": {{"login": "org"}}}}"#,
+                    i
+                )
+            })
+            .collect();
+        server.route(
+            "GET",
+            "/user/teams",
+            MockResponse::json(200, &format!("[{}]", teams.join(","))),
+        );
+        server.route(
+            "GET",
+            "/search/issues",
+            MockResponse::json(200, r#"{"items": []}"#),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        api.fetch_review_prs().await.unwrap();
+        let team_queries = server
+            .requests_to("GET", "/search/issues")
+            .into_iter()
+            .filter(|r| r.path.contains("team-review-requested"))
+            .count();
+        assert_eq!(team_queries, MAX_TEAM_SEARCHES);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_prs_tags_team_requests() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(200, r#"{"login": "me"}"#),
+        );
+        server.route(
+            "GET",
+            "/user/teams",
+            MockResponse::json(
+                200,
+                r#"[{"slug": "backend", "organization": {"login": "org"}}]"#,
+            ),
+        );
+        // Every search returns the same page
+        server.route(
+            "GET",
+            "/search/issues",
+            MockResponse::json(
+                200,
+                r#"{"items": [
+                    {"number": 1, "title": "One", "user": {"login": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/api"},
+                    {"number": 2, "title": "Mine", "user": {"login": "me"},
+                     "created_at": "2024-01-02T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/web"}
+                ]}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let prs = api.fetch_review_prs().await.unwrap().prs;
+        assert_eq!(prs.len(), 2);
+        let team = ReviewReason::Team("org/backend".to_string());
+        assert_eq!(
+            prs[0].review_reasons,
+            vec![ReviewReason::Direct, team.clone(), ReviewReason::Reviewed]
+        );
+        // The user's own PR isn't listed as reviewed
+        assert_eq!(
+            prs[1].review_reasons,
+            vec![ReviewReason::Direct, team.clone()]
+        );
+        assert_eq!(team.label(), "@backend");
+
+        let queries: Vec<String> = server
+            .requests_to("GET", "/search/issues")
+            .into_iter()
+            .map(|r| r.path)
+            .collect();
+        assert_eq!(queries.len(), 4);
+        assert!(
+            queries
+                .iter()
+                .any(|q| q.contains("+review-requested%3A%40me"))
+        );
+        assert!(
+            queries
+                .iter()
+                .any(|q| q.contains("+user-review-requested%3A%40me"))
+        );
+        assert!(
+            queries
+                .iter()
+                .any(|q| q.contains("team-review-requested%3Aorg%2Fbackend"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_notifications() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/notifications",
+            MockResponse::json(
+                200,
+                r#"[
+                    {"id": "11", "unread": true, "reason": "review_requested",
+                     "updated_at": "2024-01-03T00:00:00Z",
+                     "subject": {"title": "Add cache", "type": "PullRequest",
+                                 "url": "https://api.github.com/repos/org/api/pulls/42"},
+                     "repository": {"name": "api", "owner": {"login": "org"}}},
+                    {"id": "12", "unread": true, "reason": "mention",
+                     "subject": {"title": "Crash on start", "type": "Issue",
+                                 "url": "https://api.github.com/repos/org/api/issues/7"},
+                     "repository": {"name": "api", "owner": {"login": "org"}}}
+                ]"#,
+            ),
+        );
+        // Author and creation time come from the list details lookup
+        server.route(
+            "POST",
+            "/graphql",
+            MockResponse::json(
+                200,
+                r#"{"data": {"pr0": {"pullRequest": {
+                    "author": {"login": "carol"}, "createdAt": "2024-01-01T00:00:00Z",
+                    "isDraft": false, "reviewDecision": null, "additions": 1, "deletions": 2,
+                    "changedFiles": 1, "updatedAt": "2024-01-03T00:00:00Z",
+                    "commits": {"nodes": []}}}}}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let prs = api.fetch_notifications().await.unwrap().prs;
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].number, 42);
+        assert_eq!(prs[0].repo_full_name(), "org/api");
+        assert_eq!(prs[0].author, "carol");
+        assert_eq!(prs[0].created_at, "2024-01-01T00:00:00Z");
+        let notification = prs[0].notification.as_ref().unwrap();
+        assert_eq!(notification.thread_id, "11");
+        assert!(notification.unread);
+        assert_eq!(notification.label(), "review");
+    }
+
+    #[tokio::test]
+    async fn test_mark_notification() {
+        let server = MockServer::start().await;
+        server.route(
+            "PATCH",
+            "/notifications/threads/11",
+            MockResponse::text(205, ""),
+        );
+        server.route(
+            "DELETE",
+            "/notifications/threads/11",
+            MockResponse::text(204, ""),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        api.mark_notification("11", false).await.unwrap();
+        assert_eq!(
+            server
+                .requests_to("PATCH", "/notifications/threads/11")
+                .len(),
+            1
+        );
+
+        api.mark_notification("11", true).await.unwrap();
+        assert_eq!(
+            server
+                .requests_to("DELETE", "/notifications/threads/11")
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_comment_threads() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/repos/owner/repo/pulls/7/comments",
+            MockResponse::json(
+                200,
+                r#"[
+                    {"id": 1, "body": "Root", "user": {"login": "a"}, "path": "src/lib.rs",
+                     "line": 3, "created_at": "2024-01-01T00:00:00Z"},
+                    {"id": 2, "body": "Reply", "user": {"login": "b"}, "path": "src/lib.rs",
+                     "line": 3, "created_at": "2024-01-01T01:00:00Z", "in_reply_to_id": 1}
+                ]"#,
+            ),
+        );
+        server.route(
+            "GET",
+            "/repos/owner/repo/issues/7/comments",
+            MockResponse::json(
+                200,
+                r#"[{"id": 10, "body": "LGTM", "user": {"login": "c"}, "created_at": "2024-01-02T00:00:00Z"}]"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let threads = api.fetch_comment_threads(&test_pr()).await.unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].file_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(threads[0].comment_count(), 2);
+        assert!(threads[1].file_path.is_none());
+        assert_eq!(threads[1].comments[0].body, "LGTM");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_comment_threads_follows_pages() {
+        let server = MockServer::start().await;
+        let next = format!("<{}/review-comments-page-2>; rel=\"next\"", server.url);
+        server.route(
+            "GET",
+            "/repos/owner/repo/pulls/7/comments",
+            MockResponse::json(
+                200,
+                r#"[{"id": 1, "body": "Root", "user": {"login": "a"}, "path": "src/lib.rs",
+                     "line": 3, "created_at": "2024-01-01T00:00:00Z"}]"#,
+            )
+            .with_header("link", &next),
+        );
+        server.route(
+            "GET",
+            "/review-comments-page-2",
+            MockResponse::json(
+                200,
+                r#"[{"id": 2, "body": "Reply", "user": {"login": "b"}, "path": "src/lib.rs",
+                     "line": 3, "created_at": "2024-01-01T01:00:00Z", "in_reply_to_id": 1}]"#,
+            ),
+        );
+        server.route(
+            "GET",
+            "/repos/owner/repo/issues/7/comments",
+            MockResponse::json(200, "[]"),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let threads = api.fetch_comment_threads(&test_pr()).await.unwrap();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].comment_count(), 2);
+        // The next page is requested with the same auth
+        let page2 = server.requests_to("GET", "/review-comments-page-2");
+        assert_eq!(page2[0].header("authorization"), Some("Bearer secret"));
+    }
+
+    #[tokio::test]
+    async fn test_search_prs_capped_is_truncated() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/search/issues",
+            MockResponse::json(
+                200,
+                r#"{"total_count": 2, "items": [
+                    {"number": 1, "title": "One", "user": {"login": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/api"},
+                    {"number": 2, "title": "Two", "user": {"login": "bob"},
+                     "created_at": "2024-01-02T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/api"}
+                ]}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret").with_max_prs(1);
+
+        let list = api.fetch_my_prs().await.unwrap();
+        assert_eq!(list.prs.len(), 1);
+        assert!(list.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_search_prs_passes_query() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/search/issues",
+            MockResponse::json(
+                200,
+                r#"{"total_count": 1, "items": [
+                    {"number": 7, "title": "Fix CVE", "user": {"login": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "repository_url": "https://api.github.com/repos/org/api"}
+                ]}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let list = api
+            .fetch_search_prs("repo:org/api label:security")
+            .await
+            .unwrap();
+        assert_eq!(list.prs.len(), 1);
+        assert_eq!(list.prs[0].repo_full_name(), "org/api");
+
+        let search = server.requests_to("GET", "/search/issues");
+        assert!(
+            search[0]
+                .path
+                .contains("q=is%3Apr+is%3Aopen+repo%3Aorg%2Fapi+label%3Asecurity"),
+            "{}",
+            search[0].path
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_state_is_one_graphql_request() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/graphql",
+            MockResponse::json(
+                200,
+                r#"{"data": {"repository": {"pullRequest": {
+                    "number": 7, "title": "Fix", "body": null,
+                    "createdAt": "2024-01-01T00:00:00Z", "author": {"login": "alice"},
+                    "headRefOid": "abc", "baseRefOid": "def",
+                    "reviewThreads": {"pageInfo": {"hasNextPage": false}, "nodes": [
+                        {"id": "PRRT_1", "isResolved": false, "isOutdated": true, "path": "a.rs", "line": null,
+                         "comments": {"nodes": [{"databaseId": 5, "body": "x",
+                            "createdAt": "2024-01-01T00:00:00Z", "author": {"login": "bob"}}]}}
+                    ]},
+                    "comments": {"pageInfo": {"hasNextPage": false}, "nodes": []},
+                    "reviews": {"pageInfo": {"hasNextPage": false}, "nodes": []},
+                    "files": {"pageInfo": {"hasNextPage": false}, "nodes": []}
+                }}}}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let state = api.fetch_review_state(&test_pr()).await.unwrap();
+        assert_eq!(state.pr.head_sha.as_deref(), Some("abc"));
+        assert_eq!(state.threads.len(), 1);
+        assert!(state.threads[0].outdated);
+
+        let requests = server.requests_to("POST", "/graphql");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+        assert_eq!(requests[0].json()["variables"]["owner"], "owner");
+    }
+
+    #[tokio::test]
+    async fn test_submit_review_sends_inline_comments() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/repos/owner/repo/pulls/7/reviews",
+            MockResponse::json(200, r#"{"id": 1}"#),
+        );
+        server.route(
+            "POST",
+            "/repos/owner/repo/issues/7/comments",
+            MockResponse::json(201, r#"{"id": 2}"#),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_multiline("Fix".to_string(), "src/a.rs".to_string(), 4, 6),
+            PendingComment::new_general("General".to_string()),
+        ];
+        let submitted = api
+            .submit_review(
+                &test_pr(),
+                "APPROVE",
+                Some("Looks good"),
+                Some(&comments),
+                Some("sha1"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(submitted, 2);
+
+        let review = server.requests_to("POST", "/repos/owner/repo/pulls/7/reviews");
+        assert_eq!(review.len(), 1);
+        let json = review[0].json();
+        assert_eq!(json["event"], "APPROVE");
+        assert_eq!(json["body"], "Looks good");
+        assert_eq!(json["commit_id"], "sha1");
+        assert_eq!(json["comments"][0]["path"], "src/a.rs");
+        assert_eq!(json["comments"][0]["start_line"], 4);
+        assert_eq!(json["comments"][0]["line"], 6);
+
+        let general = server.requests_to("POST", "/repos/owner/repo/issues/7/comments");
+        assert_eq!(general.len(), 1);
+        assert_eq!(general[0].json()["body"], "General");
+    }
+
+    #[tokio::test]
+    async fn test_submit_comments_on_deleted_lines() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/repos/owner/repo/pulls/7/reviews",
+            MockResponse::json(200, r#"{"id": 1}"#),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_inline("Why remove?".to_string(), "src/a.rs".to_string(), 9)
+                .with_side(DiffSide::Left),
+            PendingComment::new_multiline("Replaced".to_string(), "src/a.rs".to_string(), 12, 14)
+                .with_start_side(DiffSide::Left),
+        ];
+        api.submit_comments(&test_pr(), &comments, Some("sha1"))
+            .await
+            .unwrap();
+
+        let review = server.requests_to("POST", "/repos/owner/repo/pulls/7/reviews");
+        let json = review[0].json();
+        assert_eq!(json["comments"][0]["side"], "LEFT");
+        assert_eq!(json["comments"][0]["line"], 9);
+        assert!(json["comments"][0].get("start_side").is_none());
+        assert_eq!(json["comments"][1]["start_side"], "LEFT");
+        assert_eq!(json["comments"][1]["start_line"], 12);
+        assert_eq!(json["comments"][1]["side"], "RIGHT");
+    }
+
+    #[tokio::test]
+    async fn test_submit_file_level_comment() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/repos/owner/repo/pulls/7/reviews",
+            MockResponse::json(200, r#"{"id": 1}"#),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_file("Split this file".to_string(), "src/a.rs".to_string()),
+            PendingComment::new_inline("Typo".to_string(), "src/a.rs".to_string(), 3),
+        ];
+        let submitted = api
+            .submit_comments(&test_pr(), &comments, Some("sha1"))
+            .await
+            .unwrap();
+        assert_eq!(submitted, 2);
+
+        // Both go on one review; no issue comment is posted
+        let review = server.requests_to("POST", "/repos/owner/repo/pulls/7/reviews");
+        assert_eq!(review.len(), 1);
+        let json = review[0].json();
+        assert_eq!(json["comments"][0]["subject_type"], "file");
+        assert_eq!(json["comments"][0]["path"], "src/a.rs");
+        assert!(json["comments"][0].get("line").is_none());
+        assert_eq!(json["comments"][1]["line"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_submit_review_request_changes_requires_body() {
+        let api = GithubApi::new(DEFAULT_HOST, "http://127.0.0.1:1", "secret");
+        let err = api
+            .submit_review(&test_pr(), "REQUEST_CHANGES", None, None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_submit_review_validation_error() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/repos/owner/repo/pulls/7/reviews",
+            MockResponse::json(
+                422,
+                r#"{"message": "Unprocessable Entity", "errors": ["Line could not be resolved"]}"#,
+            ),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let comments = vec![PendingComment::new_inline(
+            "Nit".to_string(),
+            "src/a.rs".to_string(),
+            999,
+        )];
+        let err = api
+            .submit_review(&test_pr(), "COMMENT", None, Some(&comments), Some("sha1"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ForgeError::Validation(
+                "Unprocessable Entity: Line could not be resolved (HTTP 422)".to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_thread_reply_inline() {
+        let server = MockServer::start().await;
+        server.route(
+            "POST",
+            "/repos/owner/repo/pulls/7/comments",
+            MockResponse::json(201, r#"{"id": 5}"#),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let thread = CommentThread {
+            id: 42,
+            node_id: None,
+            file_path: Some("src/a.rs".to_string()),
+            line: Some(3),
+            side: DiffSide::Right,
+            start_line: None,
+            comments: Vec::new(),
+            outdated: false,
+            resolved: false,
+        };
+        api.submit_thread_reply(&test_pr(), &thread, "Done")
+            .await
+            .unwrap();
+
+        let requests = server.requests_to("POST", "/repos/owner/repo/pulls/7/comments");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].json()["in_reply_to"], 42);
+        assert_eq!(requests[0].json()["body"], "Done");
+    }
+
+    #[tokio::test]
+    async fn test_edit_and_delete_comments() {
+        let server = MockServer::start().await;
+        server.route(
+            "PATCH",
+            "/repos/owner/repo/pulls/comments/43",
+            MockResponse::json(200, r#"{"id": 43}"#),
+        );
+        server.route(
+            "DELETE",
+            "/repos/owner/repo/issues/comments/50",
+            MockResponse::json(204, ""),
+        );
+        let api = GithubApi::new(DEFAULT_HOST, &server.url, "secret");
+
+        let review_thread = CommentThread {
+            id: 42,
+            node_id: None,
+            file_path: Some("src/a.rs".to_string()),
+            line: Some(3),
+            side: DiffSide::Right,
+            start_line: None,
+            comments: Vec::new(),
+            outdated: false,
+            resolved: false,
+        };
+        let issue_thread = CommentThread {
+            file_path: None,
+            line: None,
+            ..review_thread.clone()
+        };
+
+        api.edit_comment(&test_pr(), &review_thread, 43, "Reworded")
+            .await
+            .unwrap();
+        api.delete_comment(&test_pr(), &issue_thread, 50)
+            .await
+            .unwrap();
+
+        let edits = server.requests_to("PATCH", "/repos/owner/repo/pulls/comments/43");
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].json()["body"], "Reworded");
+        assert_eq!(
+            server
+                .requests_to("DELETE", "/repos/owner/repo/issues/comments/50")
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,2145 @@
+//! GitHub GraphQL loader: a PR's details, threads, reviews and file stats in one request,
+//! the review decision, CI state and size of listed PRs,
+//! the CI checks of its head commit, its mergeability and its reviewers, assignees and labels,
+//! plus the mutations behind thread resolution, pending reviews, reactions, merging and
+//! editing those lists.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use super::ForgeError;
+use crate::github::sort_comment_threads;
+use crate::types::{
+    AnnotationLevel, CheckAnnotation, CheckRun, CheckState, CommentThread, DiffSide, FileStat,
+    MergeInfo, MergeMessage, MergeMethod, MergeRequest, MergeStatus, MetadataEntry, PendingComment,
+    PendingReview, PrInfo, PrMetadata, PrReview, PrSize, Reaction, ReactionKind, ReviewDecision,
+    ReviewPr, ReviewState, ThreadComment,
+};
+
+/// Items per connection page (GitHub's maximum)
+const PAGE_SIZE: u32 = 100;
+
+/// Login GitHub shows for deleted accounts
+const GHOST_LOGIN: &str = "ghost";
+
+/// Paginated connections of a pull request and the fields read from their nodes
+/// Comments inside a review thread are read from the first page only
+/// Comments and the PR carry `reactionGroups { content viewerHasReacted reactors { totalCount } }`
+const CONNECTIONS: [(&str, &str); 4] = [
+    (
+        "reviewThreads",
+        "id isResolved isOutdated path line startLine diffSide \
+         comments(first: 100) { nodes { id databaseId body createdAt viewerDidAuthor author { login } \
+         reactionGroups { content viewerHasReacted reactors { totalCount } } } }",
+    ),
+    (
+        "comments",
+        "id databaseId body createdAt viewerDidAuthor author { login } \
+         reactionGroups { content viewerHasReacted reactors { totalCount } }",
+    ),
+    (
+        "reviews",
+        "author { login } state viewerDidAuthor commit { oid }",
+    ),
+    ("files", "path additions deletions"),
+];
+
+/// The viewer's pending review (GitHub allows one per user and PR) with its draft comments
+const PENDING_REVIEW: &str = "pendingReviews: reviews(states: PENDING, first: 1) { nodes { \
+     comments(first: 100) { nodes { id path line startLine subjectType body \
+     pullRequestReviewThread { diffSide startDiffSide } } } } }";
+
+/// Fields read from the check runs and commit statuses of a commit's status rollup
+/// Annotations of a check run are read from the first page only
+const CHECK_CONTEXTS: &str = "__typename \
+     ... on CheckRun { name status conclusion startedAt completedAt detailsUrl \
+     isRequired(pullRequestNumber: $number) \
+     annotations(first: 100) { nodes { path annotationLevel title message \
+     location { start { line } end { line } } } } } \
+     ... on StatusContext { context state targetUrl createdAt isRequired(pullRequestNumber: $number) }";
+
+/// PRs looked up per list details query
+const LIST_DETAILS_BATCH: usize = 50;
+
+/// Fields shown in the PR list that search results lack
+const LIST_DETAILS: &str = "author { login } createdAt isDraft reviewDecision additions deletions changedFiles updatedAt \
+     commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }";
+
+/// Runs GraphQL requests against a GitHub host
+#[async_trait]
+pub(crate) trait GraphqlTransport: Send + Sync {
+    /// Send `{"query": ..., "variables": ...}` and return the raw response
+    async fn graphql(&self, body: &Value) -> Result<Value, ForgeError>;
+}
+
+/// GraphQL endpoint for a REST API base URL
+/// github.com: https://api.github.com/graphql, GHE: https://host/api/graphql
+pub(crate) fn graphql_url(api_url: &str) -> String {
+    let api_url = api_url.trim_end_matches('/');
+    match api_url.strip_suffix("/v3") {
+        Some(api_root) => format!("{}/graphql", api_root),
+        None => format!("{}/graphql", api_url),
+    }
+}
+
+/// Load a PR's review state
+/// Connections longer than one page are completed with one follow-up query per page
+pub(crate) async fn fetch_review_state(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+) -> Result<ReviewState, ForgeError> {
+    let variables = json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number });
+    let mut pull = pull_request(transport, &review_state_query(), &variables).await?;
+
+    for (name, fields) in CONNECTIONS {
+        let mut cursor = next_cursor(&pull[name]);
+        while let Some(after) = cursor {
+            let mut page_variables = variables.clone();
+            page_variables["after"] = json!(after);
+            let page = pull_request(transport, &page_query(name, fields), &page_variables).await?;
+
+            let new_nodes = page[name]["nodes"].as_array().cloned().unwrap_or_default();
+            // An empty page with a next cursor would loop forever on a misbehaving server
+            cursor = if new_nodes.is_empty() {
+                None
+            } else {
+                next_cursor(&page[name])
+            };
+            if let Some(nodes) = pull[name]["nodes"].as_array_mut() {
+                nodes.extend(new_nodes);
+            }
+        }
+    }
+
+    let pull: GqlPullRequest = serde_json::from_value(pull)
+        .map_err(|e| ForgeError::Other(format!("Failed to parse GraphQL response: {}", e)))?;
+    Ok(pull.into_review_state(pr))
+}
+
+/// Load the check runs and commit statuses of a PR's head commit, failing and pending ones first
+pub(crate) async fn fetch_checks(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+    head_sha: &str,
+) -> Result<Vec<CheckRun>, ForgeError> {
+    let query = format!(
+        "query($owner: String!, $name: String!, $number: Int!, $sha: GitObjectID!, $after: String) {{ \
+         repository(owner: $owner, name: $name) {{ object(oid: $sha) {{ ... on Commit {{ \
+         statusCheckRollup {{ contexts(first: {}, after: $after) {{ \
+         pageInfo {{ hasNextPage endCursor }} nodes {{ {} }} }} }} }} }} }} }}",
+        PAGE_SIZE, CHECK_CONTEXTS
+    );
+    let mut variables =
+        json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number, "sha": head_sha });
+
+    let mut checks = Vec::new();
+    loop {
+        let mut repo = repository(transport, &query, &variables).await?;
+        let commit = repo["object"].take();
+        if commit.is_null() {
+            return Err(ForgeError::NotFound(format!(
+                "Commit {} not found",
+                head_sha
+            )));
+        }
+
+        // No rollup means no checks have reported on the commit
+        let contexts = &commit["statusCheckRollup"]["contexts"];
+        let nodes: Vec<GqlCheckContext> = match contexts["nodes"].clone() {
+            Value::Null => Vec::new(),
+            nodes => serde_json::from_value(nodes).map_err(|e| {
+                ForgeError::Other(format!("Failed to parse GraphQL response: {}", e))
+            })?,
+        };
+        let page_len = nodes.len();
+        checks.extend(nodes.into_iter().map(GqlCheckContext::into_check_run));
+
+        match next_cursor(contexts) {
+            Some(after) if page_len > 0 => variables["after"] = json!(after),
+            _ => break,
+        }
+    }
+
+    checks.sort_by_key(|check| {
+        let rank = match check.state {
+            CheckState::Failure => 0,
+            CheckState::Pending => 1,
+            CheckState::Success => 2,
+            CheckState::Neutral => 3,
+        };
+        (rank, check.name.to_lowercase())
+    });
+    Ok(checks)
+}
+
+/// Fill in the review decision, CI state and size of listed PRs, looking up a batch of PRs
+/// per query; PRs that can't be read (or a failed batch) keep what the search returned
+pub(crate) async fn add_list_details(transport: &dyn GraphqlTransport, prs: &mut [ReviewPr]) {
+    for batch in prs.chunks_mut(LIST_DETAILS_BATCH) {
+        let mut declarations = Vec::new();
+        let mut lookups = Vec::new();
+        let mut variables = json!({});
+        for (i, pr) in batch.iter().enumerate() {
+            declarations.push(format!(
+                "$owner{0}: String!, $name{0}: String!, $number{0}: Int!",
+                i
+            ));
+            lookups.push(format!(
+                "pr{0}: repository(owner: $owner{0}, name: $name{0}) {{ pullRequest(number: $number{0}) {{ {1} }} }}",
+                i, LIST_DETAILS
+            ));
+            variables[format!("owner{}", i)] = json!(pr.repo_owner);
+            variables[format!("name{}", i)] = json!(pr.repo_name);
+            variables[format!("number{}", i)] = json!(pr.number);
+        }
+        let query = format!(
+            "query({}) {{ {} }}",
+            declarations.join(", "),
+            lookups.join(" ")
+        );
+
+        // PRs that can't be read come back as null with an error, next to the others' data
+        let Ok(response) = transport
+            .graphql(&json!({ "query": query, "variables": variables }))
+            .await
+        else {
+            continue;
+        };
+        for (i, pr) in batch.iter_mut().enumerate() {
+            let details = response["data"][format!("pr{}", i)]["pullRequest"].clone();
+            if let Ok(details) = serde_json::from_value::<GqlListDetails>(details) {
+                details.apply(pr);
+            }
+        }
+    }
+}
+
+/// Load what the merge dialog needs: allowed methods, mergeability and default commit messages
+pub(crate) async fn fetch_merge_info(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+) -> Result<MergeInfo, ForgeError> {
+    let query = "query($owner: String!, $name: String!, $number: Int!) { \
+         repository(owner: $owner, name: $name) { \
+         mergeCommitAllowed squashMergeAllowed rebaseMergeAllowed autoMergeAllowed deleteBranchOnMerge \
+         pullRequest(number: $number) { isDraft mergeable mergeStateStatus headRefName \
+         viewerCanDeleteHeadRef autoMergeRequest { enabledAt } \
+         mergeTitle: viewerMergeHeadlineText(mergeType: MERGE) mergeBody: viewerMergeBodyText(mergeType: MERGE) \
+         squashTitle: viewerMergeHeadlineText(mergeType: SQUASH) squashBody: viewerMergeBodyText(mergeType: SQUASH) } } }";
+    let variables = json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number });
+    let repo = repository(transport, query, &variables).await?;
+    if repo["pullRequest"].is_null() {
+        return Err(ForgeError::NotFound("PR not found".to_string()));
+    }
+
+    let repo: GqlMergeRepository = serde_json::from_value(repo)
+        .map_err(|e| ForgeError::Other(format!("Failed to parse GraphQL response: {}", e)))?;
+    Ok(repo.into_merge_info())
+}
+
+/// Merge a PR, or enable auto-merge for it, then delete its head branch if asked to
+pub(crate) async fn merge_pr(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+    request: &MergeRequest,
+) -> Result<(), ForgeError> {
+    let query = "query($owner: String!, $name: String!, $number: Int!) { \
+         repository(owner: $owner, name: $name) { pullRequest(number: $number) { id headRef { id } } } }";
+    let variables = json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number });
+    let pull = pull_request(transport, query, &variables).await?;
+    let pull_id = string_at(&pull["id"])?;
+
+    let mut input = json!({ "pullRequestId": pull_id, "mergeMethod": request.method.as_str() });
+    if request.method.has_commit_message() {
+        input["commitHeadline"] = json!(request.title);
+        input["commitBody"] = json!(request.body);
+    }
+
+    if request.auto_merge {
+        // The repository's delete-branch setting applies once auto-merge goes through
+        mutate(
+            transport,
+            "mutation($input: EnablePullRequestAutoMergeInput!) { \
+             enablePullRequestAutoMerge(input: $input) { pullRequest { id } } }",
+            json!({ "input": input }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if let Some(ref head_sha) = request.head_sha {
+        input["expectedHeadOid"] = json!(head_sha);
+    }
+    mutate(
+        transport,
+        "mutation($input: MergePullRequestInput!) { mergePullRequest(input: $input) { pullRequest { merged } } }",
+        json!({ "input": input }),
+    )
+    .await?;
+
+    if request.delete_branch
+        && let Some(ref_id) = pull["headRef"]["id"].as_str()
+    {
+        mutate(
+            transport,
+            "mutation($id: ID!) { deleteRef(input: { refId: $id }) { clientMutationId } }",
+            json!({ "id": ref_id }),
+        )
+        .await
+        .map_err(|e| ForgeError::Other(format!("Merged, but deleting the branch failed: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Load a PR's requested reviewers, assignees and labels, with the repository's collaborators,
+/// teams and labels to choose from
+pub(crate) async fn fetch_metadata(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+) -> Result<PrMetadata, ForgeError> {
+    let query = "query($owner: String!, $name: String!, $number: Int!) { \
+         repository(owner: $owner, name: $name) { pullRequest(number: $number) { \
+         reviewRequests(first: 100) { nodes { requestedReviewer { __typename \
+         ... on User { id login } ... on Team { id combinedSlug } } } } \
+         assignees(first: 100) { nodes { id login } } labels(first: 100) { nodes { id name } } } } }";
+    let variables = json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number });
+    let pull: GqlMetadataPullRequest =
+        serde_json::from_value(pull_request(transport, query, &variables).await?)
+            .map_err(|e| ForgeError::Other(format!("Failed to parse GraphQL response: {}", e)))?;
+
+    let users = repository_nodes(transport, pr, "assignableUsers", "id login").await?;
+    let repo_labels = repository_nodes(transport, pr, "labels", "id name").await?;
+
+    // Listing teams needs the read:org scope; without it only users are offered as reviewers
+    let teams_query = "query($owner: String!, $name: String!) { repository(owner: $owner, name: $name) { \
+         owner { ... on Organization { teams(first: 100) { nodes { id combinedSlug } } } } } }";
+    let teams = match repository(transport, teams_query, &variables).await {
+        Ok(repo) => {
+            serde_json::from_value(repo["owner"]["teams"]["nodes"].clone()).unwrap_or_default()
+        }
+        Err(_) => Vec::new(),
+    };
+
+    Ok(PrMetadata {
+        reviewers: pull
+            .review_requests
+            .nodes
+            .into_iter()
+            .filter_map(|request| {
+                request
+                    .requested_reviewer
+                    .and_then(GqlMetadataNode::into_entry)
+            })
+            .collect(),
+        assignees: pull
+            .assignees
+            .nodes
+            .into_iter()
+            .filter_map(GqlMetadataNode::into_entry)
+            .collect(),
+        labels: pull
+            .labels
+            .nodes
+            .into_iter()
+            .filter_map(GqlMetadataNode::into_entry)
+            .collect(),
+        users: parse_metadata_nodes(users)?,
+        teams: teams
+            .into_iter()
+            .filter_map(GqlMetadataNode::into_entry)
+            .collect(),
+        repo_labels: parse_metadata_nodes(repo_labels)?,
+    })
+}
+
+/// Apply the differences between a PR's original and edited reviewers, assignees and labels
+pub(crate) async fn update_metadata(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+    original: &PrMetadata,
+    updated: &PrMetadata,
+) -> Result<(), ForgeError> {
+    let query = "query($owner: String!, $name: String!, $number: Int!) { \
+         repository(owner: $owner, name: $name) { pullRequest(number: $number) { id } } }";
+    let variables = json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number });
+    let pull_id = string_at(&pull_request(transport, query, &variables).await?["id"])?;
+
+    // Without `union`, requestReviews replaces the requested reviewers, which also removes them
+    let reviewers_changed = !added(&original.reviewers, &updated.reviewers).is_empty()
+        || !added(&updated.reviewers, &original.reviewers).is_empty();
+    if reviewers_changed {
+        let ids = |teams: bool| -> Vec<&str> {
+            updated
+                .reviewers
+                .iter()
+                .filter(|r| r.is_team == teams)
+                .map(|r| r.id.as_str())
+                .collect()
+        };
+        mutate(
+            transport,
+            "mutation($input: RequestReviewsInput!) { requestReviews(input: $input) { pullRequest { id } } }",
+            json!({ "input": { "pullRequestId": pull_id, "userIds": ids(false), "teamIds": ids(true), "union": false } }),
+        )
+        .await?;
+    }
+
+    let changes = [
+        (
+            "addAssigneesToAssignable",
+            "assignableId",
+            "assigneeIds",
+            added(&original.assignees, &updated.assignees),
+        ),
+        (
+            "removeAssigneesFromAssignable",
+            "assignableId",
+            "assigneeIds",
+            added(&updated.assignees, &original.assignees),
+        ),
+        (
+            "addLabelsToLabelable",
+            "labelableId",
+            "labelIds",
+            added(&original.labels, &updated.labels),
+        ),
+        (
+            "removeLabelsFromLabelable",
+            "labelableId",
+            "labelIds",
+            added(&updated.labels, &original.labels),
+        ),
+    ];
+    for (mutation, subject, list, ids) in changes {
+        if ids.is_empty() {
+            continue;
+        }
+        let query = format!(
+            "mutation($subject: ID!, $ids: [ID!]!) {{ {}(input: {{ {}: $subject, {}: $ids }}) {{ clientMutationId }} }}",
+            mutation, subject, list
+        );
+        mutate(transport, &query, json!({ "subject": pull_id, "ids": ids })).await?;
+    }
+    Ok(())
+}
+
+/// IDs of the entries in `after` that are not in `before`
+fn added<'a>(before: &[MetadataEntry], after: &'a [MetadataEntry]) -> Vec<&'a str> {
+    after
+        .iter()
+        .filter(|entry| !before.iter().any(|b| b.id == entry.id))
+        .map(|entry| entry.id.as_str())
+        .collect()
+}
+
+/// Every node of a connection on the repository, following pages
+async fn repository_nodes(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+    name: &str,
+    fields: &str,
+) -> Result<Vec<Value>, ForgeError> {
+    let query = format!(
+        "query($owner: String!, $name: String!, $after: String) {{ \
+         repository(owner: $owner, name: $name) {{ {} }} }}",
+        connection(name, fields, true)
+    );
+    let mut variables = json!({ "owner": pr.owner, "name": pr.repo });
+
+    let mut nodes = Vec::new();
+    loop {
+        let repo = repository(transport, &query, &variables).await?;
+        let page = repo[name]["nodes"].as_array().cloned().unwrap_or_default();
+        let page_len = page.len();
+        nodes.extend(page);
+
+        match next_cursor(&repo[name]) {
+            Some(after) if page_len > 0 => variables["after"] = json!(after),
+            _ => return Ok(nodes),
+        }
+    }
+}
+
+fn parse_metadata_nodes(nodes: Vec<Value>) -> Result<Vec<MetadataEntry>, ForgeError> {
+    let nodes: Vec<GqlMetadataNode> = serde_json::from_value(Value::Array(nodes))
+        .map_err(|e| ForgeError::Other(format!("Failed to parse GraphQL response: {}", e)))?;
+    Ok(nodes
+        .into_iter()
+        .filter_map(GqlMetadataNode::into_entry)
+        .collect())
+}
+
+/// Resolve or unresolve a review thread
+pub(crate) async fn set_thread_resolved(
+    transport: &dyn GraphqlTransport,
+    thread: &CommentThread,
+    resolved: bool,
+) -> Result<(), ForgeError> {
+    // General PR comments are not threads on GitHub
+    let Some(thread_id) = thread.node_id.as_deref().filter(|_| thread.is_inline()) else {
+        return Err(ForgeError::Validation(
+            "Only review threads can be resolved".to_string(),
+        ));
+    };
+
+    let mutation = if resolved {
+        "resolveReviewThread"
+    } else {
+        "unresolveReviewThread"
+    };
+    let query = format!(
+        "mutation($threadId: ID!) {{ {}(input: {{ threadId: $threadId }}) {{ thread {{ isResolved }} }} }}",
+        mutation
+    );
+    mutate(transport, &query, json!({ "threadId": thread_id })).await?;
+    Ok(())
+}
+
+/// Add or remove the viewer's reaction on a comment or PR
+pub(crate) async fn set_reaction(
+    transport: &dyn GraphqlTransport,
+    subject_id: &str,
+    kind: ReactionKind,
+    add: bool,
+) -> Result<(), ForgeError> {
+    let mutation = if add { "addReaction" } else { "removeReaction" };
+    let query = format!(
+        "mutation($subjectId: ID!, $content: ReactionContent!) {{ {}(input: \
+         {{ subjectId: $subjectId, content: $content }}) {{ reaction {{ content }} }} }}",
+        mutation
+    );
+    mutate(
+        transport,
+        &query,
+        json!({ "subjectId": subject_id, "content": kind.as_str() }),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Add an inline draft to the viewer's pending review, starting one if there is none
+/// Returns the id of the new review comment
+pub(crate) async fn add_pending_comment(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+    comment: &PendingComment,
+) -> Result<String, ForgeError> {
+    let (pr_id, review_id) = pending_review_id(transport, pr).await?;
+    let review_id = match review_id {
+        Some(id) => id,
+        None => {
+            // A review created without an event stays pending
+            let data = mutate(
+                transport,
+                "mutation($pullRequestId: ID!) { addPullRequestReview(input: { pullRequestId: $pullRequestId }) \
+                 { pullRequestReview { id } } }",
+                json!({ "pullRequestId": pr_id }),
+            )
+            .await?;
+            string_at(&data["addPullRequestReview"]["pullRequestReview"]["id"])?
+        }
+    };
+    add_review_thread(transport, &review_id, comment).await
+}
+
+/// Replace the text of a comment on the viewer's pending review
+pub(crate) async fn update_pending_comment(
+    transport: &dyn GraphqlTransport,
+    comment_id: &str,
+    body: &str,
+) -> Result<(), ForgeError> {
+    mutate(
+        transport,
+        "mutation($id: ID!, $body: String!) { updatePullRequestReviewComment(input: \
+         { pullRequestReviewCommentId: $id, body: $body }) { pullRequestReviewComment { id } } }",
+        json!({ "id": comment_id, "body": body }),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Remove a comment from the viewer's pending review
+pub(crate) async fn delete_pending_comment(
+    transport: &dyn GraphqlTransport,
+    comment_id: &str,
+) -> Result<(), ForgeError> {
+    mutate(
+        transport,
+        "mutation($id: ID!) { deletePullRequestReviewComment(input: { id: $id }) { pullRequestReview { id } } }",
+        json!({ "id": comment_id }),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Submit the viewer's pending review with a verdict, first adding the inline drafts not on it yet
+/// GitHub refuses to start a second review while one is pending, so drafts loaded from or synced
+/// to a pending review must go through it
+/// Returns None when no draft came from a pending review, in which case callers create a review as before
+pub(crate) async fn submit_pending_review(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+    event: &str,
+    body: Option<&str>,
+    inline_comments: &[&PendingComment],
+) -> Result<Option<usize>, ForgeError> {
+    if inline_comments.iter().all(|c| c.remote_id.is_none()) {
+        return Ok(None);
+    }
+    let Some(review_id) = pending_review_id(transport, pr).await?.1 else {
+        return Ok(None);
+    };
+
+    for comment in inline_comments.iter().filter(|c| c.remote_id.is_none()) {
+        add_review_thread(transport, &review_id, comment).await?;
+    }
+
+    mutate(
+        transport,
+        "mutation($id: ID!, $event: PullRequestReviewEvent!, $body: String) { submitPullRequestReview(input: \
+         { pullRequestReviewId: $id, event: $event, body: $body }) { pullRequestReview { id } } }",
+        json!({ "id": review_id, "event": event, "body": body.filter(|b| !b.is_empty()) }),
+    )
+    .await?;
+    Ok(Some(inline_comments.len()))
+}
+
+/// Node id of the PR and of the viewer's pending review on it
+async fn pending_review_id(
+    transport: &dyn GraphqlTransport,
+    pr: &PrInfo,
+) -> Result<(String, Option<String>), ForgeError> {
+    let query = "query($owner: String!, $name: String!, $number: Int!) { \
+         repository(owner: $owner, name: $name) { pullRequest(number: $number) { \
+         id pendingReviews: reviews(states: PENDING, first: 1) { nodes { id } } } } }";
+    let variables = json!({ "owner": pr.owner, "name": pr.repo, "number": pr.number });
+    let pull = pull_request(transport, query, &variables).await?;
+
+    let review_id = pull["pendingReviews"]["nodes"][0]["id"]
+        .as_str()
+        .map(|s| s.to_string());
+    Ok((string_at(&pull["id"])?, review_id))
+}
+
+/// Add an inline or file-level comment to a pending review, returning the new comment's id
+async fn add_review_thread(
+    transport: &dyn GraphqlTransport,
+    review_id: &str,
+    comment: &PendingComment,
+) -> Result<String, ForgeError> {
+    let mut input = json!({
+        "pullRequestReviewId": review_id,
+        "path": comment.file_path,
+        "body": comment.body,
+    });
+    match comment.line_number {
+        Some(line) => {
+            input["line"] = json!(line);
+            input["side"] = json!(comment.side.as_str());
+        }
+        None => input["subjectType"] = json!("FILE"),
+    }
+    if let Some(start_line) = comment.start_line {
+        input["startLine"] = json!(start_line);
+        input["startSide"] = json!(comment.start_side.as_str());
+    }
+
+    let data = mutate(
+        transport,
+        "mutation($input: AddPullRequestReviewThreadInput!) { addPullRequestReviewThread(input: $input) \
+         { thread { comments(first: 1) { nodes { id } } } } }",
+        json!({ "input": input }),
+    )
+    .await?;
+    string_at(&data["addPullRequestReviewThread"]["thread"]["comments"]["nodes"][0]["id"])
+}
+
+/// Run a mutation and return its `data`
+async fn mutate(
+    transport: &dyn GraphqlTransport,
+    query: &str,
+    variables: Value,
+) -> Result<Value, ForgeError> {
+    let mut response = transport
+        .graphql(&json!({ "query": query, "variables": variables }))
+        .await?;
+
+    match response["errors"].as_array() {
+        Some(errors) if !errors.is_empty() => Err(graphql_error(errors)),
+        _ => Ok(response["data"].take()),
+    }
+}
+
+/// A string field that must be present in a response
+fn string_at(value: &Value) -> Result<String, ForgeError> {
+    value
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| ForgeError::Other("Unexpected GraphQL response".to_string()))
+}
+
+/// Query for the PR fields and the first page of every connection
+fn review_state_query() -> String {
+    let connections: Vec<String> = CONNECTIONS
+        .iter()
+        .map(|(name, fields)| connection(name, fields, false))
+        .collect();
+
+    format!(
+        "query($owner: String!, $name: String!, $number: Int!) {{ \
+         repository(owner: $owner, name: $name) {{ pullRequest(number: $number) {{ \
+         id number title body createdAt author {{ login }} headRefOid baseRefOid \
+         reactionGroups {{ content viewerHasReacted reactors {{ totalCount }} }} {} {} }} }} }}",
+        connections.join(" "),
+        PENDING_REVIEW
+    )
+}
+
+/// Query for one further page of a connection
+fn page_query(name: &str, fields: &str) -> String {
+    format!(
+        "query($owner: String!, $name: String!, $number: Int!, $after: String!) {{ \
+         repository(owner: $owner, name: $name) {{ pullRequest(number: $number) {{ {} }} }} }}",
+        connection(name, fields, true)
+    )
+}
+
+fn connection(name: &str, fields: &str, paged: bool) -> String {
+    format!(
+        "{}(first: {}{}) {{ pageInfo {{ hasNextPage endCursor }} nodes {{ {} }} }}",
+        name,
+        PAGE_SIZE,
+        if paged { ", after: $after" } else { "" },
+        fields
+    )
+}
+
+/// Cursor of the next page, if the connection has one
+fn next_cursor(connection: &Value) -> Option<String> {
+    let page_info = &connection["pageInfo"];
+    if page_info["hasNextPage"].as_bool() != Some(true) {
+        return None;
+    }
+    page_info["endCursor"].as_str().map(|s| s.to_string())
+}
+
+/// Run a query and return `data.repository.pullRequest`
+async fn pull_request(
+    transport: &dyn GraphqlTransport,
+    query: &str,
+    variables: &Value,
+) -> Result<Value, ForgeError> {
+    let mut repo = repository(transport, query, variables).await?;
+    match repo["pullRequest"].take() {
+        Value::Null => Err(ForgeError::NotFound("PR not found".to_string())),
+        pull => Ok(pull),
+    }
+}
+
+/// Run a query and return `data.repository`
+async fn repository(
+    transport: &dyn GraphqlTransport,
+    query: &str,
+    variables: &Value,
+) -> Result<Value, ForgeError> {
+    let mut response = transport
+        .graphql(&json!({ "query": query, "variables": variables }))
+        .await?;
+
+    // GraphQL reports most failures with HTTP 200 and an "errors" array
+    if let Some(errors) = response["errors"].as_array()
+        && !errors.is_empty()
+    {
+        return Err(graphql_error(errors));
+    }
+
+    match response["data"]["repository"].take() {
+        Value::Null => Err(ForgeError::NotFound("Repository not found".to_string())),
+        repo => Ok(repo),
+    }
+}
+
+/// Classify GraphQL errors by the type of the first one
+fn graphql_error(errors: &[Value]) -> ForgeError {
+    let message = errors
+        .iter()
+        .filter_map(|e| e["message"].as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match errors.first().and_then(|e| e["type"].as_str()) {
+        Some("NOT_FOUND") => ForgeError::NotFound(message),
+        Some("FORBIDDEN") | Some("INSUFFICIENT_SCOPES") => ForgeError::Auth(message),
+        Some("RATE_LIMITED") => ForgeError::RateLimited(message),
+        _ => ForgeError::Other(message),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlActor {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlNodes<T> {
+    nodes: Vec<T>,
+}
+
+impl<T> Default for GqlNodes<T> {
+    fn default() -> Self {
+        Self { nodes: Vec::new() }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlPullRequest {
+    #[serde(default)]
+    id: Option<String>,
+    number: u32,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    created_at: String,
+    author: Option<GqlActor>,
+    head_ref_oid: String,
+    base_ref_oid: Option<String>,
+    review_threads: GqlNodes<GqlThread>,
+    comments: GqlNodes<GqlComment>,
+    reviews: GqlNodes<GqlReview>,
+    /// Null for PRs too large for GitHub to list
+    files: Option<GqlNodes<GqlFile>>,
+    #[serde(default)]
+    pending_reviews: GqlNodes<GqlPendingReview>,
+    #[serde(default)]
+    reaction_groups: Vec<GqlReactionGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlThread {
+    id: String,
+    is_resolved: bool,
+    is_outdated: bool,
+    path: String,
+    line: Option<u32>,
+    start_line: Option<u32>,
+    #[serde(default)]
+    diff_side: DiffSide,
+    comments: GqlNodes<GqlComment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlComment {
+    #[serde(default)]
+    id: Option<String>,
+    database_id: Option<u64>,
+    body: String,
+    created_at: String,
+    #[serde(default)]
+    viewer_did_author: bool,
+    author: Option<GqlActor>,
+    #[serde(default)]
+    reaction_groups: Vec<GqlReactionGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlReactionGroup {
+    content: String,
+    viewer_has_reacted: bool,
+    reactors: GqlCount,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlCount {
+    total_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlReview {
+    author: Option<GqlActor>,
+    state: String,
+    #[serde(default)]
+    viewer_did_author: bool,
+    /// Null when the commit is gone (force pushed away and collected)
+    #[serde(default)]
+    commit: Option<GqlOid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlOid {
+    oid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlPendingReview {
+    comments: GqlNodes<GqlPendingComment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlPendingComment {
+    id: String,
+    path: String,
+    /// Null once the line is no longer part of the diff
+    line: Option<u32>,
+    start_line: Option<u32>,
+    /// LINE or FILE
+    #[serde(default)]
+    subject_type: Option<String>,
+    body: String,
+    /// Sides of the lines, kept on the comment's thread
+    #[serde(default)]
+    pull_request_review_thread: Option<GqlThreadSides>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlThreadSides {
+    diff_side: DiffSide,
+    #[serde(default)]
+    start_diff_side: Option<DiffSide>,
+}
+
+/// A node of a commit's status rollup
+#[derive(Debug, Deserialize)]
+#[serde(tag = "__typename")]
+enum GqlCheckContext {
+    #[serde(rename_all = "camelCase")]
+    CheckRun {
+        name: String,
+        /// QUEUED, IN_PROGRESS, COMPLETED, ...
+        status: String,
+        /// Set once the run completed: SUCCESS, FAILURE, NEUTRAL, SKIPPED, TIMED_OUT, ...
+        conclusion: Option<String>,
+        started_at: Option<String>,
+        completed_at: Option<String>,
+        details_url: Option<String>,
+        #[serde(default)]
+        is_required: bool,
+        #[serde(default)]
+        annotations: Option<GqlNodes<GqlAnnotation>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatusContext {
+        context: String,
+        /// SUCCESS, PENDING, EXPECTED, FAILURE or ERROR
+        state: String,
+        target_url: Option<String>,
+        created_at: Option<String>,
+        #[serde(default)]
+        is_required: bool,
+    },
+}
+
+impl GqlCheckContext {
+    fn into_check_run(self) -> CheckRun {
+        match self {
+            GqlCheckContext::CheckRun {
+                name,
+                status,
+                conclusion,
+                started_at,
+                completed_at,
+                details_url,
+                is_required,
+                annotations,
+            } => CheckRun {
+                name,
+                state: match conclusion.as_deref() {
+                    _ if status != "COMPLETED" => CheckState::Pending,
+                    Some("SUCCESS") => CheckState::Success,
+                    Some("NEUTRAL") | Some("SKIPPED") | Some("STALE") => CheckState::Neutral,
+                    None => CheckState::Pending,
+                    Some(_) => CheckState::Failure,
+                },
+                required: is_required,
+                started_at,
+                completed_at,
+                url: details_url,
+                annotations: annotations
+                    .unwrap_or_default()
+                    .nodes
+                    .into_iter()
+                    .map(GqlAnnotation::into_annotation)
+                    .collect(),
+            },
+            // Commit statuses only report when they were last set
+            GqlCheckContext::StatusContext {
+                context,
+                state,
+                target_url,
+                created_at,
+                is_required,
+            } => CheckRun {
+                name: context,
+                state: match state.as_str() {
+                    "SUCCESS" => CheckState::Success,
+                    "PENDING" | "EXPECTED" => CheckState::Pending,
+                    _ => CheckState::Failure,
+                },
+                required: is_required,
+                started_at: created_at,
+                completed_at: None,
+                url: target_url,
+                annotations: Vec::new(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlMergeRepository {
+    merge_commit_allowed: bool,
+    squash_merge_allowed: bool,
+    rebase_merge_allowed: bool,
+    #[serde(default)]
+    auto_merge_allowed: bool,
+    #[serde(default)]
+    delete_branch_on_merge: bool,
+    pull_request: GqlMergePullRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlMergePullRequest {
+    is_draft: bool,
+    /// MERGEABLE, CONFLICTING or UNKNOWN
+    mergeable: String,
+    /// CLEAN, UNSTABLE, HAS_HOOKS, BLOCKED, BEHIND, DIRTY, DRAFT or UNKNOWN
+    merge_state_status: String,
+    head_ref_name: String,
+    #[serde(default)]
+    viewer_can_delete_head_ref: bool,
+    auto_merge_request: Option<Value>,
+    #[serde(default)]
+    merge_title: String,
+    #[serde(default)]
+    merge_body: String,
+    #[serde(default)]
+    squash_title: String,
+    #[serde(default)]
+    squash_body: String,
+}
+
+impl GqlMergeRepository {
+    fn into_merge_info(self) -> MergeInfo {
+        let pull = self.pull_request;
+        let allowed = [
+            self.merge_commit_allowed,
+            self.squash_merge_allowed,
+            self.rebase_merge_allowed,
+        ];
+        let status = match (pull.mergeable.as_str(), pull.merge_state_status.as_str()) {
+            _ if pull.is_draft => MergeStatus::Draft,
+            ("CONFLICTING", _) | (_, "DIRTY") => MergeStatus::Conflicting,
+            (_, "CLEAN") | (_, "HAS_HOOKS") => MergeStatus::Clean,
+            (_, "UNSTABLE") => MergeStatus::Unstable,
+            (_, "BLOCKED") => MergeStatus::Blocked,
+            (_, "BEHIND") => MergeStatus::Behind,
+            _ => MergeStatus::Unknown,
+        };
+
+        MergeInfo {
+            methods: MergeMethod::ALL
+                .into_iter()
+                .zip(allowed)
+                .filter_map(|(method, allowed)| allowed.then_some(method))
+                .collect(),
+            status,
+            messages: vec![
+                MergeMessage {
+                    method: MergeMethod::Merge,
+                    title: pull.merge_title,
+                    body: pull.merge_body,
+                },
+                MergeMessage {
+                    method: MergeMethod::Squash,
+                    title: pull.squash_title,
+                    body: pull.squash_body,
+                },
+            ],
+            auto_merge_allowed: self.auto_merge_allowed,
+            auto_merge_enabled: pull.auto_merge_request.is_some(),
+            head_ref: pull.head_ref_name,
+            can_delete_branch: pull.viewer_can_delete_head_ref,
+            deletes_branch_on_merge: self.delete_branch_on_merge,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlListDetails {
+    /// Missing from notifications, and null for deleted accounts
+    author: Option<GqlActor>,
+    #[serde(default)]
+    created_at: String,
+    is_draft: bool,
+    review_decision: Option<ReviewDecision>,
+    additions: u32,
+    deletions: u32,
+    changed_files: u32,
+    updated_at: String,
+    commits: GqlNodes<GqlListCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlListCommit {
+    commit: GqlRollupCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlRollupCommit {
+    status_check_rollup: Option<GqlRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlRollup {
+    /// SUCCESS, FAILURE, ERROR, PENDING or EXPECTED
+    state: String,
+}
+
+impl GqlListDetails {
+    fn apply(self, pr: &mut ReviewPr) {
+        if pr.author.is_empty()
+            && let Some(author) = self.author
+        {
+            pr.author = author.login;
+        }
+        if pr.created_at.is_empty() {
+            pr.created_at = self.created_at;
+        }
+        pr.is_draft = self.is_draft;
+        pr.review_decision = self.review_decision;
+        pr.ci_state = self
+            .commits
+            .nodes
+            .into_iter()
+            .next()
+            .and_then(|node| node.commit.status_check_rollup)
+            .map(|rollup| match rollup.state.as_str() {
+                "SUCCESS" => CheckState::Success,
+                "FAILURE" | "ERROR" => CheckState::Failure,
+                _ => CheckState::Pending,
+            });
+        pr.size = Some(PrSize {
+            additions: self.additions,
+            deletions: self.deletions,
+            changed_files: self.changed_files,
+        });
+        pr.updated_at = Some(self.updated_at);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlMetadataPullRequest {
+    review_requests: GqlNodes<GqlReviewRequest>,
+    assignees: GqlNodes<GqlMetadataNode>,
+    labels: GqlNodes<GqlMetadataNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlReviewRequest {
+    requested_reviewer: Option<GqlMetadataNode>,
+}
+
+/// A user, team or label; bots and mannequins requested for review have no login or slug
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlMetadataNode {
+    id: Option<String>,
+    login: Option<String>,
+    combined_slug: Option<String>,
+    name: Option<String>,
+}
+
+impl GqlMetadataNode {
+    fn into_entry(self) -> Option<MetadataEntry> {
+        let is_team = self.combined_slug.is_some();
+        Some(MetadataEntry {
+            id: self.id?,
+            name: self.login.or(self.combined_slug).or(self.name)?,
+            is_team,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlAnnotation {
+    path: String,
+    /// NOTICE, WARNING or FAILURE
+    annotation_level: Option<String>,
+    title: Option<String>,
+    message: String,
+    location: GqlAnnotationLocation,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlAnnotationLocation {
+    start: GqlAnnotationPosition,
+    end: GqlAnnotationPosition,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlAnnotationPosition {
+    line: u32,
+}
+
+impl GqlAnnotation {
+    fn into_annotation(self) -> CheckAnnotation {
+        let start_line = self.location.start.line;
+        CheckAnnotation {
+            path: self.path,
+            start_line,
+            end_line: self.location.end.line.max(start_line),
+            level: match self.annotation_level.as_deref() {
+                Some("FAILURE") => AnnotationLevel::Failure,
+                Some("WARNING") => AnnotationLevel::Warning,
+                _ => AnnotationLevel::Notice,
+            },
+            // Titles are often empty strings rather than null
+            title: self.title.filter(|title| !title.is_empty()),
+            message: self.message,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlFile {
+    path: String,
+    additions: u32,
+    deletions: u32,
+}
+
+fn login(author: Option<GqlActor>) -> String {
+    author
+        .map(|a| a.login)
+        .unwrap_or_else(|| GHOST_LOGIN.to_string())
+}
+
+/// Reactions that were used, skipping GitHub's empty groups and kinds kensa doesn't know
+fn reactions(groups: Vec<GqlReactionGroup>) -> Vec<Reaction> {
+    groups
+        .into_iter()
+        .filter(|group| group.reactors.total_count > 0)
+        .filter_map(|group| {
+            Some(Reaction {
+                kind: ReactionKind::from_name(&group.content)?,
+                count: group.reactors.total_count,
+                by_viewer: group.viewer_has_reacted,
+            })
+        })
+        .collect()
+}
+
+impl GqlComment {
+    fn into_thread_comment(self) -> ThreadComment {
+        ThreadComment {
+            id: self.database_id.unwrap_or_default(),
+            node_id: self.id,
+            body: self.body,
+            author: login(self.author),
+            created_at: self.created_at,
+            by_viewer: self.viewer_did_author,
+            reactions: reactions(self.reaction_groups),
+        }
+    }
+}
+
+impl GqlPullRequest {
+    fn into_review_state(self, pr: &PrInfo) -> ReviewState {
+        let mut threads: Vec<CommentThread> = self
+            .review_threads
+            .nodes
+            .into_iter()
+            .map(|thread| {
+                // Replies go to the root comment's REST id
+                let id = thread
+                    .comments
+                    .nodes
+                    .first()
+                    .and_then(|c| c.database_id)
+                    .unwrap_or_default();
+
+                CommentThread {
+                    id,
+                    node_id: Some(thread.id),
+                    file_path: Some(thread.path),
+                    line: thread.line,
+                    side: thread.diff_side,
+                    start_line: thread.start_line,
+                    comments: thread
+                        .comments
+                        .nodes
+                        .into_iter()
+                        .map(GqlComment::into_thread_comment)
+                        .collect(),
+                    outdated: thread.is_outdated,
+                    resolved: thread.is_resolved,
+                }
+            })
+            .collect();
+
+        threads.extend(
+            self.comments
+                .nodes
+                .into_iter()
+                .map(|comment| CommentThread {
+                    id: comment.database_id.unwrap_or_default(),
+                    node_id: None,
+                    file_path: None,
+                    line: None,
+                    side: DiffSide::Right,
+                    start_line: None,
+                    comments: vec![comment.into_thread_comment()],
+                    outdated: false,
+                    resolved: false,
+                }),
+        );
+        sort_comment_threads(&mut threads);
+
+        ReviewState {
+            pr_node_id: self.id,
+            reactions: reactions(self.reaction_groups),
+            pr: ReviewPr {
+                host: pr.host.clone(),
+                number: self.number,
+                title: self.title,
+                repo_owner: pr.owner.clone(),
+                repo_name: pr.repo.clone(),
+                author: login(self.author),
+                created_at: self.created_at,
+                head_sha: Some(self.head_ref_oid),
+                body: self.body,
+                is_draft: false,
+                review_decision: None,
+                ci_state: None,
+                size: None,
+                updated_at: None,
+                review_reasons: Vec::new(),
+                notification: None,
+            },
+            base_sha: self.base_ref_oid,
+            threads,
+            reviews: self
+                .reviews
+                .nodes
+                .into_iter()
+                .map(|review| PrReview {
+                    author: login(review.author),
+                    state: review.state,
+                    by_viewer: review.viewer_did_author,
+                    commit_id: review.commit.map(|commit| commit.oid),
+                })
+                .collect(),
+            file_stats: self
+                .files
+                .unwrap_or_default()
+                .nodes
+                .into_iter()
+                .map(|file| FileStat {
+                    path: file.path,
+                    additions: file.additions,
+                    deletions: file.deletions,
+                })
+                .collect(),
+            pending_review: self.pending_reviews.nodes.into_iter().next().map(|review| {
+                PendingReview {
+                    comments: review
+                        .comments
+                        .nodes
+                        .into_iter()
+                        .filter_map(|comment| {
+                            if comment.subject_type.as_deref() == Some("FILE") {
+                                let mut draft =
+                                    PendingComment::new_file(comment.body, comment.path);
+                                draft.remote_id = Some(comment.id);
+                                return Some(draft);
+                            }
+                            let line = comment.line?;
+                            let (side, start_side) = comment
+                                .pull_request_review_thread
+                                .map(|thread| {
+                                    (
+                                        thread.diff_side,
+                                        thread.start_diff_side.unwrap_or(thread.diff_side),
+                                    )
+                                })
+                                .unwrap_or_default();
+                            Some(PendingComment {
+                                body: comment.body,
+                                file_path: Some(comment.path),
+                                line_number: Some(line),
+                                start_line: comment.start_line.filter(|&start| start != line),
+                                side,
+                                start_side,
+                                remote_id: Some(comment.id),
+                                synced_body: None,
+                            })
+                        })
+                        .collect(),
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Replays canned responses and records the requests
+    struct FakeTransport {
+        responses: Mutex<Vec<Value>>,
+        requests: Mutex<Vec<Value>>,
+    }
+
+    impl FakeTransport {
+        fn new(responses: Vec<Value>) -> Self {
+            Self {
+                responses: Mutex::new(responses),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl GraphqlTransport for FakeTransport {
+        async fn graphql(&self, body: &Value) -> Result<Value, ForgeError> {
+            self.requests.lock().unwrap().push(body.clone());
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    fn test_pr() -> PrInfo {
+        PrInfo {
+            host: "github.com".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 7,
+        }
+    }
+
+    fn connection_json(nodes: Value, next: Option<&str>) -> Value {
+        json!({
+            "pageInfo": { "hasNextPage": next.is_some(), "endCursor": next },
+            "nodes": nodes
+        })
+    }
+
+    fn pull_json(threads: Value, comments: Value) -> Value {
+        json!({ "data": { "repository": { "pullRequest": {
+            "id": "PR_7",
+            "number": 7,
+            "title": "Add parser",
+            "body": "Details",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "author": { "login": "alice" },
+            "headRefOid": "head123",
+            "baseRefOid": "base456",
+            "reactionGroups": [
+                { "content": "ROCKET", "viewerHasReacted": false, "reactors": { "totalCount": 3 } },
+                { "content": "EYES", "viewerHasReacted": false, "reactors": { "totalCount": 0 } }
+            ],
+            "reviewThreads": threads,
+            "comments": comments,
+            "reviews": connection_json(json!([
+                { "author": { "login": "bob" }, "state": "APPROVED", "commit": { "oid": "head100" } },
+                { "author": { "login": "me" }, "state": "COMMENTED", "viewerDidAuthor": true, "commit": null }
+            ]), None),
+            "files": connection_json(json!([
+                { "path": "src/lib.rs", "additions": 10, "deletions": 2 }
+            ]), None),
+            "pendingReviews": { "nodes": [{ "comments": { "nodes": [
+                { "id": "PRRC_1", "path": "src/lib.rs", "line": 8, "startLine": 6, "body": "Draft",
+                  "pullRequestReviewThread": { "diffSide": "RIGHT", "startDiffSide": "LEFT" } },
+                { "id": "PRRC_2", "path": "src/lib.rs", "line": null, "startLine": null, "body": "Outdated" },
+                { "id": "PRRC_3", "path": "src/main.rs", "line": null, "startLine": null, "subjectType": "FILE", "body": "Split" },
+                { "id": "PRRC_4", "path": "src/lib.rs", "line": 3, "startLine": null, "body": "Why remove this?",
+                  "pullRequestReviewThread": { "diffSide": "LEFT", "startDiffSide": null } }
+            ]}}]}
+        }}}})
+    }
+
+    #[test]
+    fn test_graphql_url() {
+        assert_eq!(
+            graphql_url("https://api.github.com"),
+            "https://api.github.com/graphql"
+        );
+        assert_eq!(
+            graphql_url("https://ghe.example.com/api/v3/"),
+            "https://ghe.example.com/api/graphql"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_state() {
+        let threads = connection_json(
+            json!([{
+                "id": "PRRT_1",
+                "isResolved": true,
+                "isOutdated": false,
+                "path": "src/lib.rs",
+                "line": 4,
+                "startLine": 2,
+                "diffSide": "LEFT",
+                "comments": { "nodes": [
+                    { "id": "PRRC_11", "databaseId": 11, "body": "Why?", "createdAt": "2024-01-01T01:00:00Z", "author": { "login": "bob" },
+                      "reactionGroups": [{ "content": "THUMBS_UP", "viewerHasReacted": true, "reactors": { "totalCount": 2 } }] },
+                    { "databaseId": 12, "body": "Because", "createdAt": "2024-01-01T02:00:00Z", "viewerDidAuthor": true, "author": null }
+                ]}
+            }]),
+            None,
+        );
+        let comments = connection_json(
+            json!([{ "databaseId": 20, "body": "LGTM", "createdAt": "2024-01-01T03:00:00Z", "author": { "login": "carol" } }]),
+            None,
+        );
+        let transport = FakeTransport::new(vec![pull_json(threads, comments)]);
+
+        let state = fetch_review_state(&transport, &test_pr()).await.unwrap();
+        assert_eq!(state.pr.head_sha.as_deref(), Some("head123"));
+        assert_eq!(state.pr.body.as_deref(), Some("Details"));
+        assert_eq!(state.base_sha.as_deref(), Some("base456"));
+
+        assert_eq!(state.threads.len(), 2);
+        let inline = &state.threads[0];
+        assert_eq!(inline.id, 11);
+        assert_eq!(inline.node_id.as_deref(), Some("PRRT_1"));
+        assert_eq!(inline.line, Some(4));
+        assert_eq!(inline.start_line, Some(2));
+        assert_eq!(inline.side, DiffSide::Left);
+        assert!(inline.resolved);
+        assert_eq!(inline.comments[1].author, "ghost");
+        assert_eq!(inline.comments[1].id, 12);
+        assert!(inline.comments[1].by_viewer);
+        assert!(!inline.comments[0].by_viewer);
+        assert_eq!(inline.comments[0].node_id.as_deref(), Some("PRRC_11"));
+        assert_eq!(
+            inline.comments[0].reactions,
+            vec![Reaction {
+                kind: ReactionKind::ThumbsUp,
+                count: 2,
+                by_viewer: true
+            }]
+        );
+        assert!(inline.comments[1].reactions.is_empty());
+        assert!(!state.threads[1].is_inline());
+
+        // Empty reaction groups are dropped
+        assert_eq!(state.pr_node_id.as_deref(), Some("PR_7"));
+        assert_eq!(state.reactions.len(), 1);
+        assert_eq!(state.reactions[0].kind, ReactionKind::Rocket);
+
+        assert_eq!(state.reviews[0].state, "APPROVED");
+        assert_eq!(state.reviews[0].commit_id.as_deref(), Some("head100"));
+        assert!(!state.reviews[0].by_viewer);
+        assert!(state.reviews[1].by_viewer);
+        assert_eq!(state.reviews[1].commit_id, None);
+        assert_eq!(state.file_stats[0].path, "src/lib.rs");
+        assert_eq!(state.file_stats[0].additions, 10);
+
+        let pending = state.pending_review.unwrap();
+        assert_eq!(pending.comments.len(), 3);
+        assert_eq!(pending.comments[0].remote_id.as_deref(), Some("PRRC_1"));
+        assert_eq!(pending.comments[0].start_line, Some(6));
+        assert_eq!(pending.comments[0].line_number, Some(8));
+        assert_eq!(pending.comments[0].side, DiffSide::Right);
+        assert_eq!(pending.comments[0].start_side, DiffSide::Left);
+        // A draft started in the browser on a removed line
+        assert_eq!(pending.comments[2].remote_id.as_deref(), Some("PRRC_4"));
+        assert_eq!(pending.comments[2].line_number, Some(3));
+        assert_eq!(pending.comments[2].side, DiffSide::Left);
+        assert_eq!(pending.comments[2].start_side, DiffSide::Left);
+        assert!(pending.comments[1].is_file_level());
+        assert_eq!(
+            pending.comments[1].file_path.as_deref(),
+            Some("src/main.rs")
+        );
+        assert_eq!(pending.comments[1].remote_id.as_deref(), Some("PRRC_3"));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["variables"]["number"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_state_follows_connection_pages() {
+        let comment = |id: u64| json!({ "databaseId": id, "body": "c", "createdAt": "2024-01-01T00:00:00Z", "author": { "login": "bob" } });
+        let transport = FakeTransport::new(vec![
+            pull_json(
+                connection_json(json!([]), None),
+                connection_json(json!([comment(1)]), Some("c1")),
+            ),
+            json!({ "data": { "repository": { "pullRequest": {
+                "comments": connection_json(json!([comment(2)]), Some("c2"))
+            }}}}),
+            json!({ "data": { "repository": { "pullRequest": {
+                "comments": connection_json(json!([comment(3)]), None)
+            }}}}),
+        ]);
+
+        let state = fetch_review_state(&transport, &test_pr()).await.unwrap();
+        let ids: Vec<u64> = state.threads.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2]["variables"]["after"], "c2");
+        assert!(
+            requests[2]["query"]
+                .as_str()
+                .unwrap()
+                .contains("comments(first: 100, after: $after)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checks() {
+        let rollup = |nodes: Value, next: Option<&str>| {
+            json!({ "data": { "repository": { "object": {
+                "statusCheckRollup": { "contexts": connection_json(nodes, next) }
+            }}}})
+        };
+        let transport = FakeTransport::new(vec![
+            rollup(
+                json!([
+                    { "__typename": "CheckRun", "name": "test", "status": "COMPLETED", "conclusion": "SUCCESS",
+                      "startedAt": "2024-01-01T00:00:00Z", "completedAt": "2024-01-01T00:03:12Z",
+                      "detailsUrl": "https://ci.example.com/1", "isRequired": true },
+                    { "__typename": "CheckRun", "name": "lint", "status": "IN_PROGRESS", "conclusion": null,
+                      "startedAt": "2024-01-01T00:00:00Z", "completedAt": null, "detailsUrl": null, "isRequired": false,
+                      "annotations": { "nodes": [
+                          { "path": "src/lib.rs", "annotationLevel": "WARNING", "title": "", "message": "unused variable",
+                            "location": { "start": { "line": 12 }, "end": { "line": 12 } } }
+                      ]}}
+                ]),
+                Some("c1"),
+            ),
+            rollup(
+                json!([{ "__typename": "StatusContext", "context": "ci/build", "state": "ERROR",
+                         "targetUrl": "https://ci.example.com/2", "createdAt": "2024-01-01T00:00:00Z", "isRequired": true }]),
+                None,
+            ),
+        ]);
+
+        let checks = fetch_checks(&transport, &test_pr(), "head123")
+            .await
+            .unwrap();
+        let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["ci/build", "lint", "test"]);
+        assert_eq!(checks[0].state, CheckState::Failure);
+        assert!(checks[0].required);
+        assert_eq!(checks[0].url.as_deref(), Some("https://ci.example.com/2"));
+        assert_eq!(checks[1].state, CheckState::Pending);
+        assert_eq!(
+            checks[1].annotations,
+            vec![CheckAnnotation {
+                path: "src/lib.rs".to_string(),
+                start_line: 12,
+                end_line: 12,
+                level: AnnotationLevel::Warning,
+                title: None,
+                message: "unused variable".to_string(),
+            }]
+        );
+        assert!(checks[0].annotations.is_empty());
+        assert_eq!(checks[2].state, CheckState::Success);
+        assert_eq!(checks[2].duration(), Some(chrono::TimeDelta::seconds(192)));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0]["variables"]["sha"], "head123");
+        assert_eq!(requests[1]["variables"]["after"], "c1");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checks_without_rollup() {
+        let transport = FakeTransport::new(vec![
+            json!({ "data": { "repository": { "object": { "statusCheckRollup": null } } } }),
+            json!({ "data": { "repository": { "object": null } } }),
+        ]);
+
+        assert!(
+            fetch_checks(&transport, &test_pr(), "head123")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let err = fetch_checks(&transport, &test_pr(), "gone")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_merge_info() {
+        let transport = FakeTransport::new(vec![json!({ "data": { "repository": {
+            "mergeCommitAllowed": false,
+            "squashMergeAllowed": true,
+            "rebaseMergeAllowed": true,
+            "autoMergeAllowed": true,
+            "deleteBranchOnMerge": false,
+            "pullRequest": {
+                "isDraft": false,
+                "mergeable": "MERGEABLE",
+                "mergeStateStatus": "BLOCKED",
+                "headRefName": "feature",
+                "viewerCanDeleteHeadRef": true,
+                "autoMergeRequest": null,
+                "mergeTitle": "Merge pull request #42 from owner/feature",
+                "mergeBody": "Add feature",
+                "squashTitle": "Add feature (#42)",
+                "squashBody": "* first commit\n* second commit"
+            }
+        } } })]);
+
+        let info = fetch_merge_info(&transport, &test_pr()).await.unwrap();
+        assert_eq!(info.methods, vec![MergeMethod::Squash, MergeMethod::Rebase]);
+        assert_eq!(info.status, MergeStatus::Blocked);
+        assert!(info.auto_merge_allowed);
+        assert!(!info.auto_merge_enabled);
+        assert!(info.can_delete_branch);
+        assert_eq!(info.head_ref, "feature");
+        assert_eq!(
+            info.message(MergeMethod::Squash),
+            (
+                "Add feature (#42)".to_string(),
+                "* first commit\n* second commit".to_string()
+            )
+        );
+        assert_eq!(
+            info.message(MergeMethod::Rebase),
+            (String::new(), String::new())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_pr() {
+        let pull = json!({ "data": { "repository": { "pullRequest": { "id": "PR_1", "headRef": { "id": "REF_1" } } } } });
+        let transport = FakeTransport::new(vec![
+            pull.clone(),
+            json!({ "data": { "mergePullRequest": { "pullRequest": { "merged": true } } } }),
+            json!({ "data": { "deleteRef": { "clientMutationId": null } } }),
+            pull,
+            json!({ "data": { "enablePullRequestAutoMerge": { "pullRequest": { "id": "PR_1" } } } }),
+        ]);
+
+        let mut request = MergeRequest {
+            method: MergeMethod::Squash,
+            title: "Add feature (#42)".to_string(),
+            body: "Details".to_string(),
+            delete_branch: true,
+            auto_merge: false,
+            head_sha: Some("head123".to_string()),
+        };
+        merge_pr(&transport, &test_pr(), &request).await.unwrap();
+
+        request.method = MergeMethod::Rebase;
+        request.auto_merge = true;
+        merge_pr(&transport, &test_pr(), &request).await.unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 5);
+        let merge = &requests[1]["variables"]["input"];
+        assert!(
+            requests[1]["query"]
+                .as_str()
+                .unwrap()
+                .contains("mergePullRequest(input")
+        );
+        assert_eq!(merge["pullRequestId"], "PR_1");
+        assert_eq!(merge["mergeMethod"], "SQUASH");
+        assert_eq!(merge["commitHeadline"], "Add feature (#42)");
+        assert_eq!(merge["expectedHeadOid"], "head123");
+        assert!(requests[2]["query"].as_str().unwrap().contains("deleteRef"));
+        assert_eq!(requests[2]["variables"]["id"], "REF_1");
+
+        // Auto-merge neither pins the head nor deletes the branch itself
+        let auto = &requests[4]["variables"]["input"];
+        assert!(
+            requests[4]["query"]
+                .as_str()
+                .unwrap()
+                .contains("enablePullRequestAutoMerge(input")
+        );
+        assert_eq!(auto["mergeMethod"], "REBASE");
+        assert!(auto.get("expectedHeadOid").is_none());
+        assert!(auto.get("commitHeadline").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_metadata() {
+        let transport = FakeTransport::new(vec![
+            json!({ "data": { "repository": { "pullRequest": {
+                "reviewRequests": { "nodes": [
+                    { "requestedReviewer": { "__typename": "User", "id": "U1", "login": "alice" } },
+                    { "requestedReviewer": { "__typename": "Team", "id": "T1", "combinedSlug": "org/backend" } },
+                    { "requestedReviewer": { "__typename": "Bot" } },
+                    { "requestedReviewer": null }
+                ] },
+                "assignees": { "nodes": [{ "id": "U2", "login": "bob" }] },
+                "labels": { "nodes": [{ "id": "L1", "name": "bug" }] }
+            } } } }),
+            json!({ "data": { "repository": { "assignableUsers": {
+                "pageInfo": { "hasNextPage": true, "endCursor": "c1" },
+                "nodes": [{ "id": "U1", "login": "alice" }, { "id": "U2", "login": "bob" }]
+            } } } }),
+            json!({ "data": { "repository": { "assignableUsers": {
+                "pageInfo": { "hasNextPage": false, "endCursor": null },
+                "nodes": [{ "id": "U3", "login": "carla" }]
+            } } } }),
+            json!({ "data": { "repository": { "labels": {
+                "pageInfo": { "hasNextPage": false, "endCursor": null },
+                "nodes": [{ "id": "L1", "name": "bug" }, { "id": "L2", "name": "docs" }]
+            } } } }),
+            // Token without read:org
+            json!({ "errors": [{ "type": "INSUFFICIENT_SCOPES", "message": "Your token has not been granted the required scopes" }] }),
+        ]);
+
+        let metadata = fetch_metadata(&transport, &test_pr()).await.unwrap();
+        let names =
+            |entries: &[MetadataEntry]| entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&metadata.reviewers), vec!["alice", "org/backend"]);
+        assert!(metadata.reviewers[1].is_team);
+        assert_eq!(names(&metadata.assignees), vec!["bob"]);
+        assert_eq!(names(&metadata.labels), vec!["bug"]);
+        assert_eq!(names(&metadata.users), vec!["alice", "bob", "carla"]);
+        assert_eq!(names(&metadata.repo_labels), vec!["bug", "docs"]);
+        assert!(metadata.teams.is_empty());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[2]["variables"]["after"], "c1");
+    }
+
+    #[tokio::test]
+    async fn test_update_metadata() {
+        let entry = |id: &str, name: &str, is_team: bool| MetadataEntry {
+            id: id.to_string(),
+            name: name.to_string(),
+            is_team,
+        };
+        let original = PrMetadata {
+            reviewers: vec![entry("U1", "alice", false)],
+            assignees: vec![entry("U2", "bob", false)],
+            labels: vec![entry("L1", "bug", false)],
+            ..Default::default()
+        };
+        let updated = PrMetadata {
+            reviewers: vec![entry("T1", "org/backend", true)],
+            assignees: vec![entry("U2", "bob", false)],
+            labels: vec![entry("L2", "docs", false)],
+            ..Default::default()
+        };
+        let transport = FakeTransport::new(vec![
+            json!({ "data": { "repository": { "pullRequest": { "id": "PR_1" } } } }),
+            json!({ "data": { "requestReviews": { "pullRequest": { "id": "PR_1" } } } }),
+            json!({ "data": { "addLabelsToLabelable": { "clientMutationId": null } } }),
+            json!({ "data": { "removeLabelsFromLabelable": { "clientMutationId": null } } }),
+        ]);
+
+        update_metadata(&transport, &test_pr(), &original, &updated)
+            .await
+            .unwrap();
+
+        // Unchanged assignees send nothing
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        let review_input = &requests[1]["variables"]["input"];
+        assert_eq!(review_input["pullRequestId"], "PR_1");
+        assert_eq!(review_input["userIds"], json!([]));
+        assert_eq!(review_input["teamIds"], json!(["T1"]));
+        assert_eq!(review_input["union"], false);
+        assert!(
+            requests[2]["query"]
+                .as_str()
+                .unwrap()
+                .contains("addLabelsToLabelable(input")
+        );
+        assert_eq!(requests[2]["variables"]["ids"], json!(["L2"]));
+        assert!(
+            requests[3]["query"]
+                .as_str()
+                .unwrap()
+                .contains("removeLabelsFromLabelable(input")
+        );
+        assert_eq!(requests[3]["variables"]["ids"], json!(["L1"]));
+        assert_eq!(requests[3]["variables"]["subject"], "PR_1");
+    }
+
+    #[tokio::test]
+    async fn test_add_list_details() {
+        let listed = |number: u32| ReviewPr {
+            host: "github.com".to_string(),
+            number,
+            title: format!("PR {}", number),
+            repo_owner: "owner".to_string(),
+            repo_name: "repo".to_string(),
+            author: "alice".to_string(),
+            created_at: "2024-01-15T10:30:00Z".to_string(),
+            head_sha: None,
+            body: None,
+            is_draft: false,
+            review_decision: None,
+            ci_state: None,
+            size: None,
+            updated_at: None,
+            review_reasons: Vec::new(),
+            notification: None,
+        };
+        let mut prs = vec![listed(1), listed(2)];
+        let transport = FakeTransport::new(vec![json!({
+            "data": {
+                "pr0": { "pullRequest": {
+                    "isDraft": true,
+                    "reviewDecision": "APPROVED",
+                    "additions": 120,
+                    "deletions": 4,
+                    "changedFiles": 3,
+                    "updatedAt": "2024-01-16T08:00:00Z",
+                    "commits": { "nodes": [{ "commit": { "statusCheckRollup": { "state": "ERROR" } } }] }
+                } },
+                "pr1": null
+            },
+            "errors": [{ "type": "NOT_FOUND", "message": "Could not resolve to a PullRequest with the number of 2." }]
+        })]);
+
+        add_list_details(&transport, &mut prs).await;
+
+        assert!(prs[0].is_draft);
+        assert_eq!(prs[0].review_decision, Some(ReviewDecision::Approved));
+        assert_eq!(prs[0].ci_state, Some(CheckState::Failure));
+        assert_eq!(
+            prs[0].size,
+            Some(PrSize {
+                additions: 120,
+                deletions: 4,
+                changed_files: 3
+            })
+        );
+        // A PR that can't be read keeps what the search returned
+        assert_eq!(prs[1].size, None);
+
+        let requests = transport.requests.lock().unwrap();
+        assert!(
+            requests[0]["query"]
+                .as_str()
+                .unwrap()
+                .contains("pr1: repository(owner: $owner1")
+        );
+        assert_eq!(requests[0]["variables"]["number1"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_set_reaction() {
+        let transport = FakeTransport::new(vec![
+            json!({ "data": { "addReaction": { "reaction": { "content": "HEART" } } } }),
+            json!({ "data": { "removeReaction": { "reaction": { "content": "HEART" } } } }),
+        ]);
+
+        set_reaction(&transport, "PRRC_1", ReactionKind::Heart, true)
+            .await
+            .unwrap();
+        set_reaction(&transport, "PRRC_1", ReactionKind::Heart, false)
+            .await
+            .unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        assert!(
+            requests[0]["query"]
+                .as_str()
+                .unwrap()
+                .contains("addReaction(input")
+        );
+        assert!(
+            requests[1]["query"]
+                .as_str()
+                .unwrap()
+                .contains("removeReaction(input")
+        );
+        assert_eq!(requests[0]["variables"]["subjectId"], "PRRC_1");
+        assert_eq!(requests[0]["variables"]["content"], "HEART");
+    }
+
+    #[tokio::test]
+    async fn test_set_thread_resolved() {
+        let mut thread = CommentThread {
+            id: 11,
+            node_id: Some("PRRT_1".to_string()),
+            file_path: Some("src/lib.rs".to_string()),
+            line: Some(4),
+            side: DiffSide::Right,
+            start_line: None,
+            comments: Vec::new(),
+            outdated: false,
+            resolved: false,
+        };
+        let transport = FakeTransport::new(vec![
+            json!({ "data": { "resolveReviewThread": { "thread": { "isResolved": true } } } }),
+            json!({ "errors": [{ "type": "FORBIDDEN", "message": "Resource not accessible by integration" }] }),
+        ]);
+
+        set_thread_resolved(&transport, &thread, true)
+            .await
+            .unwrap();
+        let err = set_thread_resolved(&transport, &thread, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::Auth(_)));
+
+        {
+            let requests = transport.requests.lock().unwrap();
+            assert!(
+                requests[0]["query"]
+                    .as_str()
+                    .unwrap()
+                    .contains("resolveReviewThread(input")
+            );
+            assert!(
+                requests[1]["query"]
+                    .as_str()
+                    .unwrap()
+                    .contains("unresolveReviewThread(input")
+            );
+            assert_eq!(requests[0]["variables"]["threadId"], "PRRT_1");
+        }
+
+        thread.file_path = None;
+        let err = set_thread_resolved(&transport, &thread, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_state_errors() {
+        let transport = FakeTransport::new(vec![json!({
+            "data": { "repository": { "pullRequest": null } },
+            "errors": [{ "type": "NOT_FOUND", "message": "Could not resolve to a PullRequest with the number of 7." }]
+        })]);
+        let err = fetch_review_state(&transport, &test_pr())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::NotFound(_)));
+
+        let transport = FakeTransport::new(vec![
+            json!({ "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }] }),
+        ]);
+        let err = fetch_review_state(&transport, &test_pr())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::RateLimited(_)));
+    }
+
+    fn pending_review_json(review_id: Option<&str>) -> Value {
+        let nodes = match review_id {
+            Some(id) => json!([{ "id": id }]),
+            None => json!([]),
+        };
+        json!({ "data": { "repository": { "pullRequest": {
+            "id": "PR_1", "pendingReviews": { "nodes": nodes }
+        }}}})
+    }
+
+    fn thread_json(comment_id: &str) -> Value {
+        json!({ "data": { "addPullRequestReviewThread": { "thread": { "comments": { "nodes": [{ "id": comment_id }] } } } } })
+    }
+
+    #[tokio::test]
+    async fn test_add_pending_comment_starts_review() {
+        let transport = FakeTransport::new(vec![
+            pending_review_json(None),
+            json!({ "data": { "addPullRequestReview": { "pullRequestReview": { "id": "PRR_1" } } } }),
+            thread_json("PRRC_5"),
+        ]);
+        let comment =
+            PendingComment::new_multiline("Fix".to_string(), "src/a.rs".to_string(), 4, 6);
+
+        let id = add_pending_comment(&transport, &test_pr(), &comment)
+            .await
+            .unwrap();
+        assert_eq!(id, "PRRC_5");
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1]["variables"]["pullRequestId"], "PR_1");
+        let input = &requests[2]["variables"]["input"];
+        assert_eq!(input["pullRequestReviewId"], "PRR_1");
+        assert_eq!(input["path"], "src/a.rs");
+        assert_eq!(input["startLine"], 4);
+        assert_eq!(input["line"], 6);
+    }
+
+    #[tokio::test]
+    async fn test_add_pending_file_comment() {
+        let transport = FakeTransport::new(vec![
+            pending_review_json(Some("PRR_1")),
+            thread_json("PRRC_6"),
+        ]);
+        let comment = PendingComment::new_file("Split this".to_string(), "src/a.rs".to_string());
+
+        let id = add_pending_comment(&transport, &test_pr(), &comment)
+            .await
+            .unwrap();
+        assert_eq!(id, "PRRC_6");
+
+        let requests = transport.requests.lock().unwrap();
+        let input = &requests[1]["variables"]["input"];
+        assert_eq!(input["subjectType"], "FILE");
+        assert_eq!(input["path"], "src/a.rs");
+        assert!(input.get("line").is_none());
+        assert!(input.get("side").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_pending_review() {
+        let mut synced =
+            PendingComment::new_inline("Synced".to_string(), "src/a.rs".to_string(), 2);
+        synced.remote_id = Some("PRRC_1".to_string());
+        let local = PendingComment::new_inline("Local".to_string(), "src/a.rs".to_string(), 3);
+
+        // Without synced drafts nothing is looked up
+        let transport = FakeTransport::new(Vec::new());
+        let submitted = submit_pending_review(&transport, &test_pr(), "COMMENT", None, &[&local])
+            .await
+            .unwrap();
+        assert_eq!(submitted, None);
+
+        let transport = FakeTransport::new(vec![
+            pending_review_json(Some("PRR_1")),
+            thread_json("PRRC_2"),
+            json!({ "data": { "submitPullRequestReview": { "pullRequestReview": { "id": "PRR_1" } } } }),
+        ]);
+        let submitted = submit_pending_review(
+            &transport,
+            &test_pr(),
+            "APPROVE",
+            Some("Ship it"),
+            &[&synced, &local],
+        )
+        .await
+        .unwrap();
+        assert_eq!(submitted, Some(2));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1]["variables"]["input"]["body"], "Local");
+        assert_eq!(requests[2]["variables"]["id"], "PRR_1");
+        assert_eq!(requests[2]["variables"]["event"], "APPROVE");
+        assert_eq!(requests[2]["variables"]["body"], "Ship it");
+    }
+}
@@ -0,0 +1,1237 @@
+use async_trait::async_trait;
+use reqwest::Method;
+use serde::Deserialize;
+use std::time::Instant;
+
+use super::http::HttpClient;
+use super::{ForgeBackend, ForgeError};
+use crate::config::DEFAULT_MAX_PRS;
+use crate::github::perf_log;
+use crate::types::{
+    CommentThread, DiffSide, LineKind, PendingComment, PrInfo, PrList, ReviewPr, ReviewReason,
+    ThreadComment,
+};
+
+/// Page size for list endpoints (GitLab's maximum)
+const PER_PAGE: &str = "100";
+
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// Native GitLab REST API (v4) client for merge requests
+pub struct GitlabApi {
+    http: HttpClient,
+    host: String,
+    token: String,
+    max_prs: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReferences {
+    /// e.g. "group/subgroup/project!42"
+    full: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ApiDiffRefs {
+    base_sha: String,
+    start_sha: String,
+    head_sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiMergeRequest {
+    iid: u32,
+    title: String,
+    author: ApiUser,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    sha: Option<String>,
+    #[serde(default)]
+    diff_refs: Option<ApiDiffRefs>,
+    #[serde(default)]
+    references: Option<ApiReferences>,
+}
+
+/// Result of comparing two commits (only the diffs are read)
+#[derive(Debug, Deserialize)]
+struct ApiCompare {
+    diffs: Vec<ApiDiff>,
+}
+
+/// One file of a merge request diff
+#[derive(Debug, Deserialize)]
+struct ApiDiff {
+    old_path: String,
+    new_path: String,
+    #[serde(default)]
+    diff: String,
+    #[serde(default)]
+    new_file: bool,
+    #[serde(default)]
+    renamed_file: bool,
+    #[serde(default)]
+    deleted_file: bool,
+    #[serde(default)]
+    a_mode: Option<String>,
+    #[serde(default)]
+    b_mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDiscussion {
+    id: String,
+    notes: Vec<ApiNote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiNote {
+    id: u64,
+    body: String,
+    author: ApiUser,
+    created_at: String,
+    /// System notes record events ("added 1 commit") rather than comments
+    #[serde(default)]
+    system: bool,
+    /// Set on every note of a resolved discussion
+    #[serde(default)]
+    resolved: bool,
+    #[serde(default)]
+    position: Option<ApiPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPosition {
+    #[serde(default)]
+    new_path: Option<String>,
+    #[serde(default)]
+    old_path: Option<String>,
+    #[serde(default)]
+    new_line: Option<u32>,
+    #[serde(default)]
+    old_line: Option<u32>,
+    #[serde(default)]
+    head_sha: Option<String>,
+}
+
+impl GitlabApi {
+    /// Create a client for a host and its API base URL (e.g. https://gitlab.com/api/v4)
+    pub fn new(host: &str, api_url: &str, token: &str) -> Self {
+        let headers = vec![("PRIVATE-TOKEN", token.to_string())];
+
+        Self {
+            http: HttpClient::new(api_url, headers, JSON_MEDIA_TYPE),
+            host: host.to_string(),
+            token: token.to_string(),
+            max_prs: DEFAULT_MAX_PRS,
+        }
+    }
+
+    /// Cap the number of merge requests fetched per list query
+    pub fn with_max_prs(mut self, max_prs: usize) -> Self {
+        self.max_prs = max_prs;
+        self
+    }
+
+    /// URL-encoded project path, usable wherever the API expects a project ID
+    fn project_id(pr: &PrInfo) -> String {
+        let full_path = format!("{}/{}", pr.owner, pr.repo);
+        url::form_urlencoded::byte_serialize(full_path.as_bytes()).collect()
+    }
+
+    fn mr_path(pr: &PrInfo) -> String {
+        format!(
+            "projects/{}/merge_requests/{}",
+            Self::project_id(pr),
+            pr.number
+        )
+    }
+
+    /// List open merge requests across all projects visible to the user, up to the PR cap
+    async fn list_merge_requests(&self, filters: &[(&str, &str)]) -> Result<PrList, ForgeError> {
+        let mut query = vec![("state", "opened"), ("per_page", PER_PAGE)];
+        query.extend_from_slice(filters);
+
+        let (mrs, truncated) = self
+            .http
+            .get_pages::<ApiMergeRequest>("merge_requests", &query, Some(self.max_prs))
+            .await?;
+        let prs = mrs
+            .into_iter()
+            .filter_map(|mr| {
+                // Project path comes from the full reference: "group/project!42"
+                let full = mr.references.as_ref()?.full.clone();
+                let project = full.split('!').next()?;
+                let (owner, repo) = project.rsplit_once('/')?;
+                Some(self.to_review_pr(mr, owner, repo))
+            })
+            .collect();
+
+        Ok(PrList {
+            prs,
+            truncated,
+            ..Default::default()
+        })
+    }
+
+    fn to_review_pr(&self, mr: ApiMergeRequest, owner: &str, repo: &str) -> ReviewPr {
+        let head_sha = mr
+            .diff_refs
+            .as_ref()
+            .map(|refs| refs.head_sha.clone())
+            .or(mr.sha);
+
+        ReviewPr {
+            host: self.host.clone(),
+            number: mr.iid,
+            title: mr.title,
+            repo_owner: owner.to_string(),
+            repo_name: repo.to_string(),
+            author: mr.author.username,
+            created_at: mr.created_at,
+            head_sha,
+            body: mr.description,
+            is_draft: mr.draft,
+            review_decision: None,
+            ci_state: None,
+            size: None,
+            updated_at: mr.updated_at,
+            review_reasons: Vec::new(),
+            notification: None,
+        }
+    }
+
+    async fn fetch_merge_request(&self, pr: &PrInfo) -> Result<ApiMergeRequest, ForgeError> {
+        self.http.get_json(&Self::mr_path(pr), &[]).await
+    }
+
+    async fn post_note(&self, pr: &PrInfo, body: &str) -> Result<(), ForgeError> {
+        self.http
+            .post_json(
+                &format!("{}/notes", Self::mr_path(pr)),
+                &serde_json::json!({ "body": body }),
+            )
+            .await
+    }
+
+    /// Files of the merge request diff, as reported by GitLab
+    async fn fetch_mr_diffs(&self, pr: &PrInfo) -> Result<Vec<ApiDiff>, ForgeError> {
+        let (diffs, _) = self
+            .http
+            .get_pages::<ApiDiff>(
+                &format!("{}/diffs", Self::mr_path(pr)),
+                &[("per_page", PER_PAGE)],
+                None,
+            )
+            .await
+            .map_err(|e| match e {
+                ForgeError::NotFound(_) => ForgeError::NotFound(format!(
+                    "Merge request not found: {}/{}!{}",
+                    pr.owner, pr.repo, pr.number
+                )),
+                other => other,
+            })?;
+        Ok(diffs)
+    }
+
+    /// Start a diff discussion on a line of the old or new version of a file, or on the whole file
+    /// Multi-line comments are anchored at their last line
+    async fn post_inline_comment(
+        &self,
+        pr: &PrInfo,
+        refs: &ApiDiffRefs,
+        diffs: &[ApiDiff],
+        comment: &PendingComment,
+    ) -> Result<(), ForgeError> {
+        let position_type = if comment.is_file_level() {
+            "file"
+        } else {
+            "text"
+        };
+        let mut position = comment_position(diffs, comment);
+        position["position_type"] = serde_json::json!(position_type);
+        position["base_sha"] = serde_json::json!(refs.base_sha);
+        position["start_sha"] = serde_json::json!(refs.start_sha);
+        position["head_sha"] = serde_json::json!(refs.head_sha);
+        let request_body = serde_json::json!({
+            "body": comment.body,
+            "position": position,
+        });
+        self.http
+            .post_json(&format!("{}/discussions", Self::mr_path(pr)), &request_body)
+            .await
+    }
+
+    /// Submit comments: inline and file-level ones as diff discussions, general ones as notes
+    async fn post_comments(
+        &self,
+        pr: &PrInfo,
+        comments: &[PendingComment],
+    ) -> Result<usize, ForgeError> {
+        let (inline_comments, general_comments): (Vec<_>, Vec<_>) =
+            comments.iter().partition(|c| c.is_review_comment());
+
+        let mut submitted = 0;
+
+        // Diff positions need the MR's current base/start/head SHAs, and its diff for the
+        // file paths and the line numbers of context lines
+        if !inline_comments.is_empty() {
+            let (mr, diffs) = tokio::join!(self.fetch_merge_request(pr), self.fetch_mr_diffs(pr));
+            let refs = mr?
+                .diff_refs
+                .ok_or_else(|| ForgeError::Other("Merge request has no diff refs".to_string()))?;
+            let diffs = diffs?;
+            for comment in inline_comments {
+                self.post_inline_comment(pr, &refs, &diffs, comment).await?;
+                submitted += 1;
+            }
+        }
+
+        for comment in general_comments {
+            self.post_note(pr, &comment.body).await?;
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+}
+
+/// Paths and line numbers of the diff position of a comment
+/// Renamed files are named by their old and new paths; an unchanged context line needs both
+/// line numbers, a changed line only the one of its side
+fn comment_position(diffs: &[ApiDiff], comment: &PendingComment) -> serde_json::Value {
+    let path = comment.file_path.as_deref().unwrap_or_default();
+    let file = diffs.iter().find(|file| file.new_path == path);
+    let mut position = serde_json::json!({
+        "old_path": file.map_or(path, |file| file.old_path.as_str()),
+        "new_path": path,
+    });
+
+    let Some(line) = comment.line_number else {
+        return position;
+    };
+    let context_line = file
+        .and_then(|file| {
+            crate::parser::parse_diff(&mr_diffs_to_unified(std::slice::from_ref(file))).pop()
+        })
+        .and_then(|parsed| {
+            parsed
+                .hunks
+                .into_iter()
+                .flat_map(|hunk| hunk.lines)
+                .find(|diff_line| {
+                    let number = match comment.side {
+                        DiffSide::Left => diff_line.old_ln,
+                        DiffSide::Right => diff_line.new_ln,
+                    };
+                    number == Some(line)
+                })
+        })
+        .filter(|diff_line| diff_line.kind == LineKind::Context);
+
+    match (context_line, comment.side) {
+        (Some(context), _) => {
+            position["old_line"] = serde_json::json!(context.old_ln);
+            position["new_line"] = serde_json::json!(context.new_ln);
+        }
+        (None, DiffSide::Left) => position["old_line"] = serde_json::json!(line),
+        (None, DiffSide::Right) => position["new_line"] = serde_json::json!(line),
+    }
+    position
+}
+
+/// Convert GitLab's per-file diffs into a unified diff the parser understands
+fn mr_diffs_to_unified(diffs: &[ApiDiff]) -> String {
+    let mut out = String::new();
+
+    for file in diffs {
+        out.push_str(&format!(
+            "diff --git a/{} b/{}\n",
+            file.old_path, file.new_path
+        ));
+        if file.new_file {
+            out.push_str(&format!(
+                "new file mode {}\n",
+                file.b_mode.as_deref().unwrap_or("100644")
+            ));
+        } else if file.deleted_file {
+            out.push_str(&format!(
+                "deleted file mode {}\n",
+                file.a_mode.as_deref().unwrap_or("100644")
+            ));
+        }
+        if file.renamed_file {
+            out.push_str(&format!(
+                "rename from {}\nrename to {}\n",
+                file.old_path, file.new_path
+            ));
+        }
+
+        // Pure renames and binary files have no hunks
+        if file.diff.is_empty() {
+            continue;
+        }
+
+        let old = if file.new_file {
+            "/dev/null".to_string()
+        } else {
+            format!("a/{}", file.old_path)
+        };
+        let new = if file.deleted_file {
+            "/dev/null".to_string()
+        } else {
+            format!("b/{}", file.new_path)
+        };
+        out.push_str(&format!("--- {}\n+++ {}\n", old, new));
+        out.push_str(&file.diff);
+        if !file.diff.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Convert discussions into comment threads, skipping system notes
+/// A diff thread is outdated when it was left on an older head than the current one
+fn discussions_to_threads(
+    discussions: Vec<ApiDiscussion>,
+    head_sha: Option<&str>,
+) -> Vec<CommentThread> {
+    let mut threads: Vec<CommentThread> = discussions
+        .into_iter()
+        .filter_map(|discussion| {
+            let mut notes = discussion
+                .notes
+                .into_iter()
+                .filter(|n| !n.system)
+                .peekable();
+            let root = notes.peek()?;
+            let id = root.id;
+            let resolved = root.resolved;
+
+            let (file_path, line, side, outdated) = match &root.position {
+                Some(position) => {
+                    let path = position
+                        .new_path
+                        .clone()
+                        .or_else(|| position.old_path.clone());
+                    let (line, side) = match position.new_line {
+                        Some(line) => (Some(line), DiffSide::Right),
+                        None => (position.old_line, DiffSide::Left),
+                    };
+                    let outdated = match (position.head_sha.as_deref(), head_sha) {
+                        (Some(left_on), Some(current)) => left_on != current,
+                        _ => false,
+                    };
+                    (path, line, side, outdated)
+                }
+                None => (None, None, DiffSide::Right, false),
+            };
+
+            let comments = notes
+                .map(|note| ThreadComment {
+                    id: note.id,
+                    node_id: None,
+                    body: note.body,
+                    author: note.author.username,
+                    created_at: note.created_at,
+                    by_viewer: false,
+                    reactions: Vec::new(),
+                })
+                .collect();
+
+            Some(CommentThread {
+                id,
+                node_id: Some(discussion.id),
+                file_path,
+                line,
+                side,
+                start_line: None,
+                comments,
+                outdated,
+                resolved,
+            })
+        })
+        .collect();
+
+    // Same order as GitHub threads: inline by file/line, then general
+    threads.sort_by(|a, b| match (&a.file_path, &b.file_path) {
+        (Some(pa), Some(pb)) => pa.cmp(pb).then_with(|| a.line.cmp(&b.line)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.id.cmp(&b.id),
+    });
+
+    threads
+}
+
+#[async_trait]
+impl ForgeBackend for GitlabApi {
+    fn name(&self) -> &'static str {
+        "gitlab"
+    }
+
+    fn web_url(&self, pr: &PrInfo) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/{}/-/merge_requests/{}",
+            self.host, pr.owner, pr.repo, pr.number
+        ))
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        if self.token.is_empty() {
+            return Err(ForgeError::Auth(format!(
+                "No GitLab token found for {}. Set GITLAB_TOKEN or add a token to [[hosts]] in config.toml",
+                self.host
+            )));
+        }
+        self.current_user().await.map(|_| ())
+    }
+
+    async fn current_user(&self) -> Result<String, ForgeError> {
+        let user: ApiUser = self.http.get_json("user", &[]).await?;
+        Ok(user.username)
+    }
+
+    async fn fetch_review_prs(&self) -> Result<PrList, ForgeError> {
+        let start = Instant::now();
+
+        // GitLab has no "reviewed by" filter, so this lists MRs the user is a reviewer on
+        let current_user = self.current_user().await?;
+        let mut prs = self
+            .list_merge_requests(&[("scope", "all"), ("reviewer_username", &current_user)])
+            .await?;
+        for pr in &mut prs.prs {
+            pr.review_reasons = vec![ReviewReason::Direct];
+        }
+
+        perf_log("fetch_review_prs (total)", start.elapsed().as_millis());
+        Ok(prs)
+    }
+
+    async fn fetch_my_prs(&self) -> Result<PrList, ForgeError> {
+        self.list_merge_requests(&[("scope", "created_by_me")])
+            .await
+    }
+
+    async fn fetch_prs_by_author(&self, username: &str) -> Result<PrList, ForgeError> {
+        self.list_merge_requests(&[("scope", "all"), ("author_username", username)])
+            .await
+    }
+
+    async fn fetch_pr_diff(&self, pr: &PrInfo) -> Result<String, ForgeError> {
+        let diffs = self.fetch_mr_diffs(pr).await?;
+
+        if diffs.is_empty() {
+            return Err(ForgeError::Other(
+                "Merge request has no changes".to_string(),
+            ));
+        }
+
+        Ok(mr_diffs_to_unified(&diffs))
+    }
+
+    async fn fetch_compare_diff(
+        &self,
+        pr: &PrInfo,
+        base: &str,
+        head: &str,
+    ) -> Result<String, ForgeError> {
+        let path = format!("projects/{}/repository/compare", Self::project_id(pr));
+        let compare: ApiCompare = self
+            .http
+            .get_json(&path, &[("from", base), ("to", head)])
+            .await?;
+        Ok(mr_diffs_to_unified(&compare.diffs))
+    }
+
+    async fn fetch_pr_details(&self, pr: &PrInfo) -> Result<ReviewPr, ForgeError> {
+        let mr = self.fetch_merge_request(pr).await?;
+        Ok(self.to_review_pr(mr, &pr.owner, &pr.repo))
+    }
+
+    async fn fetch_comment_threads(&self, pr: &PrInfo) -> Result<Vec<CommentThread>, ForgeError> {
+        let discussions_path = format!("{}/discussions", Self::mr_path(pr));
+
+        let (mr, discussions) = tokio::join!(
+            self.fetch_merge_request(pr),
+            self.http.get_pages::<ApiDiscussion>(
+                &discussions_path,
+                &[("per_page", PER_PAGE)],
+                None
+            )
+        );
+
+        let head_sha = mr?.diff_refs.map(|refs| refs.head_sha);
+        Ok(discussions_to_threads(discussions?.0, head_sha.as_deref()))
+    }
+
+    async fn submit_comments(
+        &self,
+        pr: &PrInfo,
+        comments: &[PendingComment],
+        _head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        self.post_comments(pr, comments).await
+    }
+
+    async fn submit_thread_reply(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        match &thread.node_id {
+            Some(discussion_id) => {
+                self.http
+                    .post_json(
+                        &format!("{}/discussions/{}/notes", Self::mr_path(pr), discussion_id),
+                        &serde_json::json!({ "body": body }),
+                    )
+                    .await
+            }
+            None => self.post_note(pr, body).await,
+        }
+    }
+
+    async fn set_thread_resolved(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        resolved: bool,
+    ) -> Result<(), ForgeError> {
+        let Some(discussion_id) = &thread.node_id else {
+            return Err(ForgeError::Validation(
+                "Only discussions can be resolved".to_string(),
+            ));
+        };
+        self.http
+            .send_json(
+                Method::PUT,
+                &format!("{}/discussions/{}", Self::mr_path(pr), discussion_id),
+                &serde_json::json!({ "resolved": resolved }),
+            )
+            .await
+    }
+
+    async fn submit_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: Option<&str>,
+        pending_comments: Option<&[PendingComment]>,
+        _head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError> {
+        // REQUEST_CHANGES requires a body
+        if event == "REQUEST_CHANGES" && body.map(|b| b.is_empty()).unwrap_or(true) {
+            return Err(ForgeError::Validation(
+                "Request changes requires a comment".to_string(),
+            ));
+        }
+
+        let submitted = self
+            .post_comments(pr, pending_comments.unwrap_or_default())
+            .await?;
+
+        if let Some(body) = body.filter(|b| !b.is_empty()) {
+            self.post_note(pr, body).await?;
+        }
+
+        match event {
+            "APPROVE" => {
+                self.http
+                    .send_json(
+                        Method::POST,
+                        &format!("{}/approve", Self::mr_path(pr)),
+                        &serde_json::json!({}),
+                    )
+                    .await?;
+            }
+            "REQUEST_CHANGES" => {
+                // GitLab has no "request changes" verdict; withdraw any earlier approval.
+                // Unapproving an MR the user never approved answers 404, which is fine.
+                match self
+                    .http
+                    .send_json(
+                        Method::POST,
+                        &format!("{}/unapprove", Self::mr_path(pr)),
+                        &serde_json::json!({}),
+                    )
+                    .await
+                {
+                    Ok(_) | Err(ForgeError::NotFound(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => {}
+        }
+
+        Ok(submitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::{MockResponse, MockServer};
+
+    const MR_PATH: &str = "/projects/group%2Fproject/merge_requests/42";
+
+    fn test_pr() -> PrInfo {
+        PrInfo {
+            host: "gitlab.com".to_string(),
+            owner: "group".to_string(),
+            repo: "project".to_string(),
+            number: 42,
+        }
+    }
+
+    /// src/a.rs, renamed from src/old_a.rs: context lines 1→1, 2→3 and 4→4 around an added and a removed line
+    fn diffs_json() -> &'static str {
+        r#"[{"old_path": "src/old_a.rs", "new_path": "src/a.rs", "renamed_file": true,
+             "diff": "@@ -1,4 +1,4 @@\n a\n+b\n c\n-d\n e\n"}]"#
+    }
+
+    fn mr_json() -> &'static str {
+        r#"{"iid": 42, "title": "Add feature", "author": {"username": "alice"},
+            "created_at": "2024-01-01T00:00:00Z", "description": "Details",
+            "sha": "head1",
+            "diff_refs": {"base_sha": "base1", "start_sha": "start1", "head_sha": "head1"},
+            "references": {"full": "group/project!42"}}"#
+    }
+
+    #[test]
+    fn test_project_id_encodes_nested_namespace() {
+        let pr = PrInfo {
+            host: "gitlab.com".to_string(),
+            owner: "org/team".to_string(),
+            repo: "my.project".to_string(),
+            number: 1,
+        };
+        assert_eq!(GitlabApi::project_id(&pr), "org%2Fteam%2Fmy.project");
+    }
+
+    #[test]
+    fn test_mr_diffs_to_unified_parses() {
+        let diffs = vec![
+            ApiDiff {
+                old_path: "src/lib.rs".to_string(),
+                new_path: "src/lib.rs".to_string(),
+                diff: "@@ -1,2 +1,2 @@\n fn a() {}\n-fn b() {}\n+fn c() {}\n".to_string(),
+                new_file: false,
+                renamed_file: false,
+                deleted_file: false,
+                a_mode: Some("100644".to_string()),
+                b_mode: Some("100644".to_string()),
+            },
+            ApiDiff {
+                old_path: "new.txt".to_string(),
+                new_path: "new.txt".to_string(),
+                diff: "@@ -0,0 +1 @@\n+hello".to_string(),
+                new_file: true,
+                renamed_file: false,
+                deleted_file: false,
+                a_mode: Some("0".to_string()),
+                b_mode: Some("100644".to_string()),
+            },
+            ApiDiff {
+                old_path: "old_name.rs".to_string(),
+                new_path: "new_name.rs".to_string(),
+                diff: String::new(),
+                new_file: false,
+                renamed_file: true,
+                deleted_file: false,
+                a_mode: None,
+                b_mode: None,
+            },
+        ];
+
+        let unified = mr_diffs_to_unified(&diffs);
+        assert!(unified.contains("--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+hello\n"));
+
+        let files = crate::parser::parse_diff(&unified);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 1);
+        assert_eq!(files[1].status, crate::types::FileStatus::Added);
+        assert_eq!(files[2].path, "new_name.rs");
+        assert_eq!(files[2].status, crate::types::FileStatus::Renamed);
+    }
+
+    #[test]
+    fn test_discussions_to_threads() {
+        let discussions: Vec<ApiDiscussion> = serde_json::from_str(
+            r#"[
+                {"id": "d1", "notes": [
+                    {"id": 1, "body": "added 1 commit", "author": {"username": "bot"},
+                     "created_at": "2024-01-01T00:00:00Z", "system": true}
+                ]},
+                {"id": "d2", "notes": [
+                    {"id": 2, "body": "Looks off", "author": {"username": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z", "resolved": true,
+                     "position": {"new_path": "src/lib.rs", "new_line": 5, "head_sha": "head1"}},
+                    {"id": 3, "body": "Fixed", "author": {"username": "alice"},
+                     "created_at": "2024-01-01T01:00:00Z"}
+                ]},
+                {"id": "d3", "notes": [
+                    {"id": 4, "body": "Old", "author": {"username": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "position": {"old_path": "src/a.rs", "new_path": "src/a.rs", "old_line": 9, "head_sha": "head0"}}
+                ]},
+                {"id": "d4", "notes": [
+                    {"id": 5, "body": "Thanks!", "author": {"username": "carol"},
+                     "created_at": "2024-01-02T00:00:00Z"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let threads = discussions_to_threads(discussions, Some("head1"));
+        assert_eq!(threads.len(), 3);
+
+        assert_eq!(threads[0].file_path.as_deref(), Some("src/a.rs"));
+        assert_eq!(threads[0].line, Some(9));
+        assert_eq!(threads[0].side, DiffSide::Left);
+        assert!(threads[0].outdated);
+
+        assert_eq!(threads[1].file_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(threads[1].node_id.as_deref(), Some("d2"));
+        assert_eq!(threads[1].side, DiffSide::Right);
+        assert_eq!(threads[1].comment_count(), 2);
+        assert!(!threads[1].outdated);
+        assert!(threads[1].resolved);
+        assert!(!threads[0].resolved);
+
+        assert!(threads[2].file_path.is_none());
+        assert_eq!(threads[2].author(), "carol");
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_sends_private_token() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(200, r#"{"username": "me"}"#),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "glpat-secret");
+
+        api.check_auth().await.unwrap();
+
+        let requests = server.requests_to("GET", "/user");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("private-token"), Some("glpat-secret"));
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_without_token() {
+        let api = GitlabApi::new("gitlab.com", "http://127.0.0.1:1", "");
+        assert!(matches!(api.check_auth().await, Err(ForgeError::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_prs() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/user",
+            MockResponse::json(200, r#"{"username": "me"}"#),
+        );
+        server.route(
+            "GET",
+            "/merge_requests",
+            MockResponse::json(
+                200,
+                r#"[
+                    {"iid": 1, "title": "One", "author": {"username": "bob"},
+                     "created_at": "2024-01-01T00:00:00Z",
+                     "references": {"full": "org/team/api!1"}},
+                    {"iid": 2, "title": "Two", "author": {"username": "carol"},
+                     "created_at": "2024-01-02T00:00:00Z",
+                     "references": {"full": "org/web!2"}}
+                ]"#,
+            ),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let prs = api.fetch_review_prs().await.unwrap().prs;
+        assert_eq!(prs.len(), 2);
+        assert_eq!(prs[0].host, "gitlab.com");
+        assert_eq!(prs[0].repo_owner, "org/team");
+        assert_eq!(prs[0].repo_name, "api");
+        assert_eq!(prs[0].repo_full_name(), "gitlab.com/org/team/api");
+
+        let requests = server.requests_to("GET", "/merge_requests");
+        assert!(requests[0].path.contains("reviewer_username=me"));
+        assert!(requests[0].path.contains("state=opened"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_my_prs_follows_pages_up_to_cap() {
+        let server = MockServer::start().await;
+        let next = format!("<{}/merge_requests_page_2>; rel=\"next\"", server.url);
+        server.route(
+            "GET",
+            "/merge_requests",
+            MockResponse::json(
+                200,
+                r#"[{"iid": 1, "title": "One", "author": {"username": "me"},
+                     "created_at": "2024-01-01T00:00:00Z", "references": {"full": "org/api!1"}}]"#,
+            )
+            .with_header("link", &next),
+        );
+        server.route(
+            "GET",
+            "/merge_requests_page_2",
+            MockResponse::json(
+                200,
+                r#"[{"iid": 2, "title": "Two", "author": {"username": "me"},
+                     "created_at": "2024-01-02T00:00:00Z", "references": {"full": "org/api!2"}},
+                    {"iid": 3, "title": "Three", "author": {"username": "me"},
+                     "created_at": "2024-01-03T00:00:00Z", "references": {"full": "org/api!3"}}]"#,
+            ),
+        );
+
+        let all = GitlabApi::new("gitlab.com", &server.url, "secret")
+            .fetch_my_prs()
+            .await
+            .unwrap();
+        assert_eq!(all.prs.len(), 3);
+        assert!(!all.truncated);
+
+        let capped = GitlabApi::new("gitlab.com", &server.url, "secret")
+            .with_max_prs(2)
+            .fetch_my_prs()
+            .await
+            .unwrap();
+        assert_eq!(
+            capped.prs.iter().map(|pr| pr.number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(capped.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_details() {
+        let server = MockServer::start().await;
+        server.route("GET", MR_PATH, MockResponse::json(200, mr_json()));
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let pr = api.fetch_pr_details(&test_pr()).await.unwrap();
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.author, "alice");
+        assert_eq!(pr.head_sha.as_deref(), Some("head1"));
+        assert_eq!(pr.body.as_deref(), Some("Details"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_compare_diff() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/projects/group%2Fproject/repository/compare",
+            MockResponse::json(
+                200,
+                r#"{"commits": [], "diffs": [{"old_path": "src/lib.rs", "new_path": "src/lib.rs",
+                    "diff": "@@ -1 +1 @@\n-a\n+b\n"}]}"#,
+            ),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let diff = api
+            .fetch_compare_diff(&test_pr(), "head0", "head1")
+            .await
+            .unwrap();
+        let files = crate::parser::parse_diff(&diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+
+        let requests = server.requests_to("GET", "/projects/group%2Fproject/repository/compare");
+        assert!(requests[0].path.contains("from=head0"));
+        assert!(requests[0].path.contains("to=head1"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_diff_not_found() {
+        let server = MockServer::start().await;
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let err = api.fetch_pr_diff(&test_pr()).await.unwrap_err();
+        assert_eq!(
+            err,
+            ForgeError::NotFound("Merge request not found: group/project!42".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_review_approve_with_comments() {
+        let server = MockServer::start().await;
+        let discussions_path = format!("{}/discussions", MR_PATH);
+        let notes_path = format!("{}/notes", MR_PATH);
+        let approve_path = format!("{}/approve", MR_PATH);
+        server.route("GET", MR_PATH, MockResponse::json(200, mr_json()));
+        server.route(
+            "GET",
+            &format!("{}/diffs", MR_PATH),
+            MockResponse::json(200, diffs_json()),
+        );
+        server.route(
+            "POST",
+            &discussions_path,
+            MockResponse::json(201, r#"{"id": "d1"}"#),
+        );
+        server.route("POST", &notes_path, MockResponse::json(201, r#"{"id": 1}"#));
+        server.route("POST", &approve_path, MockResponse::json(201, r#"{}"#));
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_multiline("Fix".to_string(), "src/a.rs".to_string(), 4, 6),
+            PendingComment::new_general("General".to_string()),
+        ];
+        let submitted = api
+            .submit_review(
+                &test_pr(),
+                "APPROVE",
+                Some("Looks good"),
+                Some(&comments),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(submitted, 2);
+
+        let inline = server.requests_to("POST", &discussions_path);
+        assert_eq!(inline.len(), 1);
+        let json = inline[0].json();
+        assert_eq!(json["body"], "Fix");
+        assert_eq!(json["position"]["new_path"], "src/a.rs");
+        assert_eq!(json["position"]["new_line"], 6);
+        assert_eq!(json["position"]["base_sha"], "base1");
+        assert_eq!(json["position"]["head_sha"], "head1");
+
+        let notes: Vec<String> = server
+            .requests_to("POST", &notes_path)
+            .iter()
+            .map(|r| r.json()["body"].as_str().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(notes, vec!["General".to_string(), "Looks good".to_string()]);
+
+        assert_eq!(server.requests_to("POST", &approve_path).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_changes_unapproves() {
+        let server = MockServer::start().await;
+        let notes_path = format!("{}/notes", MR_PATH);
+        let unapprove_path = format!("{}/unapprove", MR_PATH);
+        server.route("GET", MR_PATH, MockResponse::json(200, mr_json()));
+        server.route(
+            "GET",
+            &format!("{}/diffs", MR_PATH),
+            MockResponse::json(200, diffs_json()),
+        );
+        server.route("POST", &notes_path, MockResponse::json(201, r#"{"id": 1}"#));
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        // Never approved: GitLab answers 404, which is not an error
+        let submitted = api
+            .submit_review(&test_pr(), "REQUEST_CHANGES", Some("Fix it"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(submitted, 0);
+        assert_eq!(server.requests_to("POST", &unapprove_path).len(), 1);
+
+        // Any other failure is reported
+        server.route(
+            "POST",
+            &unapprove_path,
+            MockResponse::json(401, r#"{"message": "401 Unauthorized"}"#),
+        );
+        let err = api
+            .submit_review(&test_pr(), "REQUEST_CHANGES", Some("Fix it"), None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::Auth(_)));
+    }
+
+    #[tokio::test]
+    async fn test_submit_comment_on_deleted_line() {
+        let server = MockServer::start().await;
+        let discussions_path = format!("{}/discussions", MR_PATH);
+        server.route("GET", MR_PATH, MockResponse::json(200, mr_json()));
+        server.route(
+            "GET",
+            &format!("{}/diffs", MR_PATH),
+            MockResponse::json(200, diffs_json()),
+        );
+        server.route(
+            "POST",
+            &discussions_path,
+            MockResponse::json(201, r#"{"id": "d1"}"#),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_inline("Why remove?".to_string(), "src/a.rs".to_string(), 9)
+                .with_side(DiffSide::Left),
+        ];
+        api.submit_comments(&test_pr(), &comments, None)
+            .await
+            .unwrap();
+
+        let json = server.requests_to("POST", &discussions_path)[0].json();
+        assert_eq!(json["position"]["old_line"], 9);
+        assert!(json["position"].get("new_line").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_comment_on_context_line() {
+        let server = MockServer::start().await;
+        let discussions_path = format!("{}/discussions", MR_PATH);
+        server.route("GET", MR_PATH, MockResponse::json(200, mr_json()));
+        server.route(
+            "GET",
+            &format!("{}/diffs", MR_PATH),
+            MockResponse::json(200, diffs_json()),
+        );
+        server.route(
+            "POST",
+            &discussions_path,
+            MockResponse::json(201, r#"{"id": "d1"}"#),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let comments = vec![
+            PendingComment::new_inline("Context".to_string(), "src/a.rs".to_string(), 3),
+            PendingComment::new_inline("Added".to_string(), "src/a.rs".to_string(), 2),
+            PendingComment::new_inline("Removed".to_string(), "src/a.rs".to_string(), 3)
+                .with_side(DiffSide::Left),
+        ];
+        api.submit_comments(&test_pr(), &comments, None)
+            .await
+            .unwrap();
+
+        let positions: Vec<serde_json::Value> = server
+            .requests_to("POST", &discussions_path)
+            .iter()
+            .map(|r| r.json()["position"].clone())
+            .collect();
+        assert_eq!(positions.len(), 3);
+        // Unchanged lines carry both numbers; renamed files both paths
+        assert_eq!(positions[0]["old_line"], 2);
+        assert_eq!(positions[0]["new_line"], 3);
+        assert_eq!(positions[0]["old_path"], "src/old_a.rs");
+        assert_eq!(positions[0]["new_path"], "src/a.rs");
+        assert_eq!(positions[1]["new_line"], 2);
+        assert!(positions[1].get("old_line").is_none());
+        assert_eq!(positions[2]["old_line"], 3);
+        assert!(positions[2].get("new_line").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_file_level_comment() {
+        let server = MockServer::start().await;
+        let discussions_path = format!("{}/discussions", MR_PATH);
+        server.route("GET", MR_PATH, MockResponse::json(200, mr_json()));
+        server.route(
+            "GET",
+            &format!("{}/diffs", MR_PATH),
+            MockResponse::json(200, diffs_json()),
+        );
+        server.route(
+            "POST",
+            &discussions_path,
+            MockResponse::json(201, r#"{"id": "d1"}"#),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let comments = vec![PendingComment::new_file(
+            "Split this".to_string(),
+            "src/a.rs".to_string(),
+        )];
+        api.submit_comments(&test_pr(), &comments, None)
+            .await
+            .unwrap();
+
+        let json = server.requests_to("POST", &discussions_path)[0].json();
+        assert_eq!(json["position"]["position_type"], "file");
+        assert_eq!(json["position"]["new_path"], "src/a.rs");
+        assert!(json["position"].get("new_line").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_thread_reply_to_discussion() {
+        let server = MockServer::start().await;
+        let reply_path = format!("{}/discussions/abc123/notes", MR_PATH);
+        server.route("POST", &reply_path, MockResponse::json(201, r#"{"id": 9}"#));
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let thread = CommentThread {
+            id: 1,
+            node_id: Some("abc123".to_string()),
+            file_path: Some("src/a.rs".to_string()),
+            line: Some(3),
+            side: DiffSide::Right,
+            start_line: None,
+            comments: Vec::new(),
+            outdated: false,
+            resolved: false,
+        };
+        api.submit_thread_reply(&test_pr(), &thread, "Done")
+            .await
+            .unwrap();
+
+        let requests = server.requests_to("POST", &reply_path);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].json()["body"], "Done");
+    }
+
+    #[tokio::test]
+    async fn test_set_thread_resolved_updates_discussion() {
+        let server = MockServer::start().await;
+        let discussion_path = format!("{}/discussions/abc123", MR_PATH);
+        server.route(
+            "PUT",
+            &discussion_path,
+            MockResponse::json(200, r#"{"id": "abc123"}"#),
+        );
+        let api = GitlabApi::new("gitlab.com", &server.url, "secret");
+
+        let mut thread = CommentThread {
+            id: 1,
+            node_id: Some("abc123".to_string()),
+            file_path: Some("src/a.rs".to_string()),
+            line: Some(3),
+            side: DiffSide::Right,
+            start_line: None,
+            comments: Vec::new(),
+            outdated: false,
+            resolved: false,
+        };
+        api.set_thread_resolved(&test_pr(), &thread, true)
+            .await
+            .unwrap();
+
+        let requests = server.requests_to("PUT", &discussion_path);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].json()["resolved"], true);
+
+        thread.node_id = None;
+        let err = api
+            .set_thread_resolved(&test_pr(), &thread, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ForgeError::Validation(_)));
+    }
+}
@@ -0,0 +1,349 @@
+use reqwest::header::{ACCEPT, HeaderMap, LINK};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use std::time::Instant;
+
+use super::ForgeError;
+use crate::github::perf_log;
+
+/// Thin JSON-over-HTTP client shared by the native forge backends
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    base_url: String,
+    /// Headers sent with every request (auth, API version)
+    headers: Vec<(&'static str, String)>,
+    /// Accept header for JSON requests
+    json_accept: &'static str,
+}
+
+impl HttpClient {
+    pub fn new(
+        base_url: &str,
+        headers: Vec<(&'static str, String)>,
+        json_accept: &'static str,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("kensa/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            headers,
+            json_accept,
+        }
+    }
+
+    /// Build a request for a path relative to the base URL
+    pub fn request(&self, method: Method, path: &str, accept: &str) -> RequestBuilder {
+        self.request_url(method, &format!("{}/{}", self.base_url, path), accept)
+    }
+
+    /// Build a request for an absolute URL (e.g. a pagination link)
+    fn request_url(&self, method: Method, url: &str, accept: &str) -> RequestBuilder {
+        let mut request = self.client.request(method, url).header(ACCEPT, accept);
+        for (name, value) in &self.headers {
+            request = request.header(*name, value);
+        }
+        request
+    }
+
+    /// Send a request, turning non-2xx responses into typed errors
+    pub async fn send(
+        &self,
+        request: RequestBuilder,
+        operation: &str,
+    ) -> Result<Response, ForgeError> {
+        let start = Instant::now();
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ForgeError::Other(format!("{} failed: {}", operation, e)))?;
+        perf_log(operation, start.elapsed().as_millis());
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        // GitHub uses x-ratelimit-remaining, GitLab uses ratelimit-remaining
+        let rate_limit_exhausted = ["x-ratelimit-remaining", "ratelimit-remaining"]
+            .iter()
+            .any(|name| response.headers().get(*name).and_then(|v| v.to_str().ok()) == Some("0"));
+        let body = response.text().await.unwrap_or_default();
+        Err(ForgeError::from_http(
+            status.as_u16(),
+            rate_limit_exhausted,
+            &body,
+        ))
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, ForgeError> {
+        let operation = format!("GET {}", path);
+        let request = self
+            .request(Method::GET, path, self.json_accept)
+            .query(query);
+        let response = self.send(request, &operation).await?;
+        response.json().await.map_err(|e| {
+            ForgeError::Other(format!("Failed to parse response from {}: {}", path, e))
+        })
+    }
+
+    /// GET every page of a list endpoint, following `Link: <...>; rel="next"` headers
+    /// Pages are JSON arrays, or objects with an "items" array (GitHub search)
+    /// With a cap, stops once `cap` items were collected and reports whether more were available
+    pub async fn get_pages<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        cap: Option<usize>,
+    ) -> Result<(Vec<T>, bool), ForgeError> {
+        self.get_pages_matching(path, query, cap, |_| true).await
+    }
+
+    /// Like `get_pages`, keeping only the items that pass `keep` (a filter the API lacks)
+    /// Only kept items count toward the cap, so paging goes on until enough of them are found
+    pub async fn get_pages_matching<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        cap: Option<usize>,
+        keep: impl Fn(&T) -> bool,
+    ) -> Result<(Vec<T>, bool), ForgeError> {
+        let operation = format!("GET {}", path);
+        let mut request = self
+            .request(Method::GET, path, self.json_accept)
+            .query(query);
+        let mut items = Vec::new();
+
+        loop {
+            let response = self.send(request, &operation).await?;
+            let next = next_page_url(response.headers());
+            let page: serde_json::Value = response.json().await.map_err(|e| {
+                ForgeError::Other(format!("Failed to parse response from {}: {}", path, e))
+            })?;
+            let page_items = match page {
+                serde_json::Value::Object(mut object) => object.remove("items").unwrap_or_default(),
+                other => other,
+            };
+            let page_items: Vec<T> = serde_json::from_value(page_items).map_err(|e| {
+                ForgeError::Other(format!("Failed to parse response from {}: {}", path, e))
+            })?;
+            let page_len = page_items.len();
+            items.extend(page_items.into_iter().filter(|item| keep(item)));
+
+            if let Some(cap) = cap
+                && items.len() >= cap
+            {
+                let truncated = items.len() > cap || next.is_some();
+                items.truncate(cap);
+                return Ok((items, truncated));
+            }
+
+            match next {
+                // An empty page with a next link would loop forever on a misbehaving server
+                Some(url) if page_len > 0 => {
+                    request = self.request_url(Method::GET, &url, self.json_accept);
+                }
+                _ => return Ok((items, false)),
+            }
+        }
+    }
+
+    pub async fn get_text(
+        &self,
+        path: &str,
+        accept: &str,
+        operation: &str,
+    ) -> Result<String, ForgeError> {
+        let response = self
+            .send(self.request(Method::GET, path, accept), operation)
+            .await?;
+        response
+            .text()
+            .await
+            .map_err(|e| ForgeError::Other(format!("Failed to read response from {}: {}", path, e)))
+    }
+
+    /// Send a JSON body with the given method, ignoring the response body
+    pub async fn send_json(
+        &self,
+        method: Method,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<(), ForgeError> {
+        let operation = format!("{} {}", method, path);
+        let request = self.request(method, path, self.json_accept).json(body);
+        self.send(request, &operation).await?;
+        Ok(())
+    }
+
+    pub async fn post_json(&self, path: &str, body: &serde_json::Value) -> Result<(), ForgeError> {
+        self.send_json(Method::POST, path, body).await
+    }
+
+    /// Send a DELETE request, ignoring the response body
+    pub async fn delete(&self, path: &str) -> Result<(), ForgeError> {
+        let operation = format!("DELETE {}", path);
+        self.send(
+            self.request(Method::DELETE, path, self.json_accept),
+            &operation,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// POST a JSON body to an absolute URL (e.g. a GraphQL endpoint) and parse the JSON response
+    pub async fn post_json_url<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+        operation: &str,
+    ) -> Result<T, ForgeError> {
+        let request = self
+            .request_url(Method::POST, url, self.json_accept)
+            .json(body);
+        let response = self.send(request, operation).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| ForgeError::Other(format!("Failed to parse response from {}: {}", url, e)))
+    }
+}
+
+/// URL of the next page from an RFC 8288 `Link` header, as sent by GitHub, GitLab and Gitea
+fn next_page_url(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| {
+                let param = param.trim();
+                param == r#"rel="next""# || param == "rel=next"
+            })
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::{MockResponse, MockServer};
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_next_page_url() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_page_url(&headers), None);
+
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                r#"<https://api.example.com/x?page=3>; rel="next", <https://api.example.com/x?page=9>; rel="last""#,
+            ),
+        );
+        assert_eq!(
+            next_page_url(&headers).as_deref(),
+            Some("https://api.example.com/x?page=3")
+        );
+
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(r#"<https://api.example.com/x?page=1>; rel="prev""#),
+        );
+        assert_eq!(next_page_url(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_pages_follows_links_and_caps() {
+        let server = MockServer::start().await;
+        let page2 = format!("{}/items2", server.url);
+        server.route(
+            "GET",
+            "/items",
+            MockResponse::json(200, "[1, 2]")
+                .with_header("link", &format!("<{}>; rel=\"next\"", page2)),
+        );
+        server.route("GET", "/items2", MockResponse::json(200, "[3]"));
+
+        let client = HttpClient::new(&server.url, Vec::new(), "application/json");
+
+        let (all, truncated) = client.get_pages::<u32>("items", &[], None).await.unwrap();
+        assert_eq!(all, vec![1, 2, 3]);
+        assert!(!truncated);
+
+        let (capped, truncated) = client
+            .get_pages::<u32>("items", &[], Some(2))
+            .await
+            .unwrap();
+        assert_eq!(capped, vec![1, 2]);
+        assert!(truncated);
+
+        let (exact, truncated) = client
+            .get_pages::<u32>("items", &[], Some(3))
+            .await
+            .unwrap();
+        assert_eq!(exact, vec![1, 2, 3]);
+        assert!(!truncated);
+    }
+
+    #[tokio::test]
+    async fn test_get_pages_matching_caps_kept_items() {
+        let server = MockServer::start().await;
+        let page2 = format!("{}/items2", server.url);
+        server.route(
+            "GET",
+            "/items",
+            MockResponse::json(200, "[1, 2, 3]")
+                .with_header("link", &format!("<{}>; rel=\"next\"", page2)),
+        );
+        server.route("GET", "/items2", MockResponse::json(200, "[4, 5, 6]"));
+
+        let client = HttpClient::new(&server.url, Vec::new(), "application/json");
+        let even = |n: &u32| n.is_multiple_of(2);
+
+        // Two matches on the first page aren't enough, so the second page is fetched
+        let (items, truncated) = client
+            .get_pages_matching::<u32>("items", &[], Some(2), even)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![2, 4]);
+        assert!(truncated);
+
+        let (items, truncated) = client
+            .get_pages_matching::<u32>("items", &[], Some(5), even)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![2, 4, 6]);
+        assert!(!truncated);
+    }
+
+    #[tokio::test]
+    async fn test_get_pages_search_items() {
+        let server = MockServer::start().await;
+        server.route(
+            "GET",
+            "/search",
+            MockResponse::json(200, r#"{"total_count": 2, "items": [5, 6]}"#),
+        );
+
+        let client = HttpClient::new(&server.url, Vec::new(), "application/json");
+        let (items, truncated) = client
+            .get_pages::<u32>("search", &[], Some(10))
+            .await
+            .unwrap();
+        assert_eq!(items, vec![5, 6]);
+        assert!(!truncated);
+    }
+}
@@ -0,0 +1,986 @@
+//! Forge backends for talking to code review hosts (native API or gh CLI).
+
+mod gh_cli;
+mod gitea;
+mod github_api;
+mod github_graphql;
+mod gitlab;
+mod http;
+#[cfg(test)]
+pub(crate) mod mock;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use crate::config::{Config, ForgeKind};
+use crate::types::{
+    CheckRun, CommentThread, DEFAULT_HOST, MergeInfo, MergeRequest, PendingComment, PrInfo, PrList,
+    PrMetadata, ReactionKind, ReviewPr, ReviewState,
+};
+
+pub use gh_cli::GhCli;
+pub use gitea::GiteaApi;
+pub use github_api::GithubApi;
+pub use gitlab::GitlabApi;
+
+/// Base URL of the public GitHub REST API
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Typed errors returned by forge backends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForgeError {
+    /// The PR, repository or comment does not exist (or is not visible)
+    NotFound(String),
+    /// Missing, invalid or insufficient credentials
+    Auth(String),
+    /// The API rate limit has been exhausted
+    RateLimited(String),
+    /// The request was rejected as invalid (e.g. a comment on a line outside the diff)
+    Validation(String),
+    /// The host's forge doesn't offer the operation (e.g. notifications on GitLab)
+    Unsupported(String),
+    /// Anything else: network failures, unexpected responses, CLI errors
+    Other(String),
+}
+
+impl ForgeError {
+    /// Get the human readable message
+    pub fn message(&self) -> &str {
+        match self {
+            ForgeError::NotFound(msg)
+            | ForgeError::Auth(msg)
+            | ForgeError::RateLimited(msg)
+            | ForgeError::Validation(msg)
+            | ForgeError::Unsupported(msg)
+            | ForgeError::Other(msg) => msg,
+        }
+    }
+
+    /// Classify a failed HTTP response
+    /// `rate_limit_exhausted` is true when the server reported no remaining requests
+    pub fn from_http(status: u16, rate_limit_exhausted: bool, body: &str) -> Self {
+        let message = format!("{} (HTTP {})", error_message_from_body(body), status);
+
+        match status {
+            401 => ForgeError::Auth(message),
+            403 if rate_limit_exhausted || body.to_lowercase().contains("rate limit") => {
+                ForgeError::RateLimited(message)
+            }
+            403 => ForgeError::Auth(message),
+            404 => ForgeError::NotFound(message),
+            409 | 422 => ForgeError::Validation(message),
+            429 => ForgeError::RateLimited(message),
+            _ => ForgeError::Other(message),
+        }
+    }
+
+    /// Classify an error message produced by a CLI tool (e.g. `gh` stderr)
+    pub fn from_cli_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let message = message.to_string();
+
+        if lower.contains("rate limit") || lower.contains("http 429") {
+            ForgeError::RateLimited(message)
+        } else if lower.contains("http 404") || lower.contains("not found") {
+            ForgeError::NotFound(message)
+        } else if lower.contains("http 401")
+            || lower.contains("not logged")
+            || lower.contains("bad credentials")
+            || lower.contains("not authenticated")
+        {
+            ForgeError::Auth(message)
+        } else if lower.contains("http 422") || lower.contains("validation failed") {
+            ForgeError::Validation(message)
+        } else {
+            ForgeError::Other(message)
+        }
+    }
+}
+
+impl fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for ForgeError {}
+
+impl From<anyhow::Error> for ForgeError {
+    fn from(err: anyhow::Error) -> Self {
+        ForgeError::from_cli_message(&err.to_string())
+    }
+}
+
+/// Extract a readable message from an API error body
+/// Understands `{"message": ..., "errors": [...]}` and `{"error": ...}` shapes
+fn error_message_from_body(body: &str) -> String {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        let trimmed = body.trim();
+        return if trimmed.is_empty() {
+            "Request failed".to_string()
+        } else {
+            trimmed.chars().take(200).collect()
+        };
+    };
+
+    let mut message = json["message"]
+        .as_str()
+        .or_else(|| json["error"].as_str())
+        .unwrap_or("Request failed")
+        .to_string();
+
+    // Validation errors carry the interesting details in an "errors" array
+    if let Some(errors) = json["errors"].as_array() {
+        let details: Vec<String> = errors
+            .iter()
+            .filter_map(|e| {
+                e.as_str()
+                    .map(|s| s.to_string())
+                    .or_else(|| e["message"].as_str().map(|s| s.to_string()))
+                    .or_else(|| e["code"].as_str().map(|s| s.to_string()))
+            })
+            .collect();
+        if !details.is_empty() {
+            message = format!("{}: {}", message, details.join(", "));
+        }
+    }
+
+    message
+}
+
+/// A code review host kensa can read PRs from and submit reviews to
+#[async_trait]
+pub trait ForgeBackend: Send + Sync {
+    /// Short name used in error messages and perf logs
+    fn name(&self) -> &'static str;
+
+    /// Web URL of a PR, for backends whose PRs can't be opened with `gh pr view --web`
+    fn web_url(&self, _pr: &PrInfo) -> Option<String> {
+        None
+    }
+
+    /// Verify that the backend is usable (tool installed, credentials valid)
+    async fn check_auth(&self) -> Result<(), ForgeError>;
+
+    /// Get the login of the authenticated user
+    async fn current_user(&self) -> Result<String, ForgeError>;
+
+    /// Fetch PRs where review is requested from or was given by the current user
+    async fn fetch_review_prs(&self) -> Result<PrList, ForgeError>;
+
+    /// Fetch open PRs authored by the current user
+    async fn fetch_my_prs(&self) -> Result<PrList, ForgeError>;
+
+    /// Fetch open PRs authored by a specific user
+    async fn fetch_prs_by_author(&self, username: &str) -> Result<PrList, ForgeError>;
+
+    /// Fetch open PRs matching a saved search query (in the host's search syntax)
+    async fn fetch_search_prs(&self, _query: &str) -> Result<PrList, ForgeError> {
+        Err(ForgeError::Unsupported(
+            "Saved searches are not supported for this host".to_string(),
+        ))
+    }
+
+    /// Fetch unread notifications about PRs, each PR tagged with its notification
+    async fn fetch_notifications(&self) -> Result<PrList, ForgeError> {
+        Err(notifications_unsupported())
+    }
+
+    /// Mark a notification thread as read, or as done (gone from the inbox) with `done`
+    async fn mark_notification(&self, _thread_id: &str, _done: bool) -> Result<(), ForgeError> {
+        Err(notifications_unsupported())
+    }
+
+    /// Fetch the unified diff for a PR
+    async fn fetch_pr_diff(&self, pr: &PrInfo) -> Result<String, ForgeError>;
+
+    /// Fetch the unified diff between two commits of a PR's repository, from their merge base to `head`
+    async fn fetch_compare_diff(
+        &self,
+        _pr: &PrInfo,
+        _base: &str,
+        _head: &str,
+    ) -> Result<String, ForgeError> {
+        Err(ForgeError::Unsupported(
+            "Comparing commits is not supported for this host".to_string(),
+        ))
+    }
+
+    /// Fetch full PR details including head SHA and body
+    async fn fetch_pr_details(&self, pr: &PrInfo) -> Result<ReviewPr, ForgeError>;
+
+    /// Fetch all comment threads (inline and general) for a PR
+    async fn fetch_comment_threads(&self, pr: &PrInfo) -> Result<Vec<CommentThread>, ForgeError>;
+
+    /// Fetch everything shown alongside the diff: details, threads, reviews and file stats
+    /// Backends that can't do this in one request combine details and threads
+    async fn fetch_review_state(&self, pr: &PrInfo) -> Result<ReviewState, ForgeError> {
+        let (details, threads) =
+            tokio::join!(self.fetch_pr_details(pr), self.fetch_comment_threads(pr));
+        Ok(ReviewState {
+            pr: details?,
+            base_sha: None,
+            threads: threads?,
+            reviews: Vec::new(),
+            file_stats: Vec::new(),
+            pending_review: None,
+            pr_node_id: None,
+            reactions: Vec::new(),
+        })
+    }
+
+    /// Submit pending comments without a review verdict
+    /// Returns the number of comments submitted
+    async fn submit_comments(
+        &self,
+        pr: &PrInfo,
+        comments: &[PendingComment],
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError>;
+
+    /// Reply to an existing comment thread
+    async fn submit_thread_reply(
+        &self,
+        pr: &PrInfo,
+        thread: &CommentThread,
+        body: &str,
+    ) -> Result<(), ForgeError>;
+
+    /// Mark a review thread as resolved or unresolved
+    async fn set_thread_resolved(
+        &self,
+        _pr: &PrInfo,
+        _thread: &CommentThread,
+        _resolved: bool,
+    ) -> Result<(), ForgeError> {
+        Err(ForgeError::Unsupported(
+            "Resolving threads is not supported for this host".to_string(),
+        ))
+    }
+
+    /// Replace the text of one of the user's submitted comments in a thread
+    async fn edit_comment(
+        &self,
+        _pr: &PrInfo,
+        _thread: &CommentThread,
+        _comment_id: u64,
+        _body: &str,
+    ) -> Result<(), ForgeError> {
+        Err(comment_changes_unsupported())
+    }
+
+    /// Delete one of the user's submitted comments in a thread
+    async fn delete_comment(
+        &self,
+        _pr: &PrInfo,
+        _thread: &CommentThread,
+        _comment_id: u64,
+    ) -> Result<(), ForgeError> {
+        Err(comment_changes_unsupported())
+    }
+
+    /// Add or remove the user's reaction on a comment or the PR, by node ID
+    async fn set_reaction(
+        &self,
+        _subject_id: &str,
+        _kind: ReactionKind,
+        _add: bool,
+    ) -> Result<(), ForgeError> {
+        Err(ForgeError::Unsupported(
+            "Reactions are not supported for this host".to_string(),
+        ))
+    }
+
+    /// Fetch the CI checks (check runs and commit statuses) of a PR's head commit
+    async fn fetch_checks(
+        &self,
+        _pr: &PrInfo,
+        _head_sha: &str,
+    ) -> Result<Vec<CheckRun>, ForgeError> {
+        Err(ForgeError::Unsupported(
+            "CI checks are not supported for this host".to_string(),
+        ))
+    }
+
+    /// Fetch what is needed to offer merging a PR: allowed methods, mergeability, default messages
+    async fn fetch_merge_info(&self, _pr: &PrInfo) -> Result<MergeInfo, ForgeError> {
+        Err(merging_unsupported())
+    }
+
+    /// Merge a PR (or enable auto-merge for it)
+    async fn merge_pr(&self, _pr: &PrInfo, _request: &MergeRequest) -> Result<(), ForgeError> {
+        Err(merging_unsupported())
+    }
+
+    /// Fetch a PR's requested reviewers, assignees and labels, with the candidates for each
+    async fn fetch_metadata(&self, _pr: &PrInfo) -> Result<PrMetadata, ForgeError> {
+        Err(metadata_changes_unsupported())
+    }
+
+    /// Apply the changes between a PR's `original` and `updated` reviewers, assignees and labels
+    async fn update_metadata(
+        &self,
+        _pr: &PrInfo,
+        _original: &PrMetadata,
+        _updated: &PrMetadata,
+    ) -> Result<(), ForgeError> {
+        Err(metadata_changes_unsupported())
+    }
+
+    /// Add an inline draft to the user's pending review on the host
+    /// Returns the id of the remote copy
+    async fn add_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        _comment: &PendingComment,
+    ) -> Result<String, ForgeError> {
+        Err(pending_reviews_unsupported())
+    }
+
+    /// Replace the text of a draft on the user's pending review
+    async fn update_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        _remote_id: &str,
+        _body: &str,
+    ) -> Result<(), ForgeError> {
+        Err(pending_reviews_unsupported())
+    }
+
+    /// Remove a draft from the user's pending review
+    async fn delete_pending_comment(
+        &self,
+        _pr: &PrInfo,
+        _remote_id: &str,
+    ) -> Result<(), ForgeError> {
+        Err(pending_reviews_unsupported())
+    }
+
+    /// Submit a review ("APPROVE", "REQUEST_CHANGES" or "COMMENT") with pending comments
+    /// Returns the number of comments submitted
+    async fn submit_review(
+        &self,
+        pr: &PrInfo,
+        event: &str,
+        body: Option<&str>,
+        pending_comments: Option<&[PendingComment]>,
+        head_sha: Option<&str>,
+    ) -> Result<usize, ForgeError>;
+}
+
+fn pending_reviews_unsupported() -> ForgeError {
+    ForgeError::Unsupported("Pending reviews are not supported for this host".to_string())
+}
+
+fn merging_unsupported() -> ForgeError {
+    ForgeError::Unsupported("Merging is not supported for this host".to_string())
+}
+
+fn metadata_changes_unsupported() -> ForgeError {
+    ForgeError::Unsupported(
+        "Editing reviewers, assignees and labels is not supported for this host".to_string(),
+    )
+}
+
+fn notifications_unsupported() -> ForgeError {
+    ForgeError::Unsupported("Notifications are not supported for this host".to_string())
+}
+
+fn comment_changes_unsupported() -> ForgeError {
+    ForgeError::Unsupported("Editing comments is not supported for this host".to_string())
+}
+
+/// Config the cached backends are selected from (loaded once)
+fn backend_config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(Config::load)
+}
+
+/// Backends resolved so far, keyed by lowercased host
+fn backend_cache() -> MutexGuard<'static, HashMap<String, Arc<dyn ForgeBackend>>> {
+    static BACKENDS: OnceLock<Mutex<HashMap<String, Arc<dyn ForgeBackend>>>> = OnceLock::new();
+    BACKENDS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Resolve the backends for these hosts concurrently, asking `gh auth token` where needed
+/// Call once at startup so backend_for never waits on gh
+pub async fn init_backends(hosts: &[String]) {
+    let config = backend_config();
+    let backends = join_all(hosts.iter().map(|host| select_backend(config, host))).await;
+
+    let mut cache = backend_cache();
+    for (host, backend) in hosts.iter().zip(backends) {
+        cache.entry(host.to_lowercase()).or_insert(backend);
+    }
+}
+
+/// Get the backend for a host, as selected by the user's config (resolved once per host)
+pub fn backend_for(host: &str) -> Arc<dyn ForgeBackend> {
+    let config = backend_config();
+    // Hosts missed by init_backends only use configured tokens, so callers never block on gh
+    backend_cache()
+        .entry(host.to_lowercase())
+        .or_insert_with(|| build_backend(config, host, configured_token(config, host)))
+        .clone()
+}
+
+/// Pick the backend for a host, resolving a GitHub host's token first
+async fn select_backend(config: &Config, host: &str) -> Arc<dyn ForgeBackend> {
+    let github_token = match config.host_kind(host) {
+        ForgeKind::Github if !config.is_gh_backend_forced() => resolve_token(config, host).await,
+        _ => None,
+    };
+    build_backend(config, host, github_token)
+}
+
+/// Build the backend for a host based on its forge kind
+fn build_backend(
+    config: &Config,
+    host: &str,
+    github_token: Option<String>,
+) -> Arc<dyn ForgeBackend> {
+    match config.host_kind(host) {
+        ForgeKind::Github => github_backend(config, host, github_token),
+        // Without a token, check_auth reports how to configure one
+        ForgeKind::Gitlab => {
+            let token = resolve_host_token(config, host, "GITLAB_TOKEN").unwrap_or_default();
+            Arc::new(
+                GitlabApi::new(host, &api_url_for_host(config, host), &token)
+                    .with_max_prs(config.pr_list.max_prs),
+            )
+        }
+        ForgeKind::Gitea => {
+            let token = resolve_host_token(config, host, "GITEA_TOKEN").unwrap_or_default();
+            Arc::new(
+                GiteaApi::new(host, &api_url_for_host(config, host), &token)
+                    .with_max_prs(config.pr_list.max_prs),
+            )
+        }
+    }
+}
+
+/// Pick a GitHub backend: native API when a token is available, gh otherwise
+fn github_backend(config: &Config, host: &str, token: Option<String>) -> Arc<dyn ForgeBackend> {
+    let max_prs = config.pr_list.max_prs;
+    if config.is_gh_backend_forced() {
+        return Arc::new(GhCli::new(host).with_max_prs(max_prs));
+    }
+
+    let api_url = api_url_for_host(config, host);
+    match token {
+        Some(token) => Arc::new(GithubApi::new(host, &api_url, &token).with_max_prs(max_prs)),
+        // Forcing the API without a token surfaces an auth error from check_auth
+        None if config.is_api_backend_forced() => {
+            Arc::new(GithubApi::new(host, &api_url, "").with_max_prs(max_prs))
+        }
+        None => Arc::new(GhCli::new(host).with_max_prs(max_prs)),
+    }
+}
+
+/// REST API base URL for a host
+/// GitHub Enterprise Server serves it under /api/v3, GitLab under /api/v4, Gitea under /api/v1
+fn api_url_for_host(config: &Config, host: &str) -> String {
+    if host == DEFAULT_HOST {
+        return GITHUB_API_URL.to_string();
+    }
+
+    config
+        .host_config(host)
+        .and_then(|h| h.api_url.clone())
+        .unwrap_or_else(|| match config.host_kind(host) {
+            ForgeKind::Github => format!("https://{}/api/v3", host),
+            ForgeKind::Gitlab => format!("https://{}/api/v4", host),
+            ForgeKind::Gitea => format!("https://{}/api/v1", host),
+        })
+}
+
+/// Resolve a token for a host
+/// github.com: GH_TOKEN, GITHUB_TOKEN, config, then `gh auth token`
+/// Other hosts: [[hosts]] token, GH_ENTERPRISE_TOKEN, GITHUB_ENTERPRISE_TOKEN, then `gh auth token --hostname`
+pub async fn resolve_token(config: &Config, host: &str) -> Option<String> {
+    match configured_token(config, host) {
+        Some(token) => Some(token),
+        None => gh_auth_token(host).await,
+    }
+}
+
+/// Token for a GitHub host from the environment or config, without asking gh
+fn configured_token(config: &Config, host: &str) -> Option<String> {
+    if host == DEFAULT_HOST {
+        non_empty(std::env::var("GH_TOKEN").ok())
+            .or_else(|| non_empty(std::env::var("GITHUB_TOKEN").ok()))
+            .or_else(|| non_empty(config.github.token.clone()))
+    } else {
+        non_empty(config.host_config(host).and_then(|h| h.token.clone()))
+            .or_else(|| non_empty(std::env::var("GH_ENTERPRISE_TOKEN").ok()))
+            .or_else(|| non_empty(std::env::var("GITHUB_ENTERPRISE_TOKEN").ok()))
+    }
+}
+
+/// Resolve a token for a GitLab or Gitea host: [[hosts]] token, then the forge's env var
+fn resolve_host_token(config: &Config, host: &str, env_var: &str) -> Option<String> {
+    non_empty(config.host_config(host).and_then(|h| h.token.clone()))
+        .or_else(|| non_empty(std::env::var(env_var).ok()))
+}
+
+/// Ask the gh CLI for its stored token (if gh is installed and logged in)
+async fn gh_auth_token(host: &str) -> Option<String> {
+    let output = tokio::process::Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    non_empty(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// All hosts from the config (github.com first)
+pub fn forge_hosts() -> Vec<String> {
+    Config::load().forge_hosts()
+}
+
+/// Run an operation against every host's backend concurrently
+/// Results are returned in host order
+async fn on_all_hosts<T, F, Fut>(hosts: &[String], op: F) -> Vec<Result<T, ForgeError>>
+where
+    T: Send + 'static,
+    F: Fn(Arc<dyn ForgeBackend>) -> Fut,
+    Fut: Future<Output = Result<T, ForgeError>> + Send + 'static,
+{
+    let handles: Vec<_> = hosts
+        .iter()
+        .map(|host| tokio::spawn(op(backend_for(host))))
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(
+            handle
+                .await
+                .unwrap_or_else(|e| Err(ForgeError::Other(format!("Task failed: {}", e)))),
+        );
+    }
+    results
+}
+
+/// Merge per-host PR lists (`results` in host order)
+/// A failing host (e.g. an unreachable GHE instance) doesn't hide the others, its error is
+/// kept in `host_errors`; the first error is only returned when every host failed
+fn merge_host_results(
+    hosts: &[String],
+    results: Vec<Result<PrList, ForgeError>>,
+) -> Result<PrList, ForgeError> {
+    let mut merged = PrList::default();
+    let mut first_error = None;
+    let mut any_ok = false;
+
+    for (host, result) in hosts.iter().zip(results) {
+        match result {
+            Ok(host_list) => {
+                any_ok = true;
+                merged.prs.extend(host_list.prs);
+                merged.truncated |= host_list.truncated;
+            }
+            Err(e) => {
+                // Hosts without the feature (e.g. notifications on GitLab) haven't failed
+                if !matches!(e, ForgeError::Unsupported(_)) {
+                    merged.host_errors.push(format!("{}: {}", host, e));
+                }
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) if !any_ok => Err(e),
+        _ => Ok(merged),
+    }
+}
+
+/// Check that at least one host is usable
+pub async fn check_auth_any(hosts: &[String]) -> Result<(), ForgeError> {
+    let results = on_all_hosts(hosts, |forge| async move { forge.check_auth().await }).await;
+    merge_host_results(
+        hosts,
+        results
+            .into_iter()
+            .map(|r| r.map(|_| PrList::default()))
+            .collect(),
+    )
+    .map(|_| ())
+}
+
+/// Fetch PRs awaiting review from every host
+pub async fn fetch_review_prs_all(hosts: &[String]) -> Result<PrList, ForgeError> {
+    merge_host_results(
+        hosts,
+        on_all_hosts(hosts, |forge| async move { forge.fetch_review_prs().await }).await,
+    )
+}
+
+/// Fetch the current user's PRs from every host
+pub async fn fetch_my_prs_all(hosts: &[String]) -> Result<PrList, ForgeError> {
+    merge_host_results(
+        hosts,
+        on_all_hosts(hosts, |forge| async move { forge.fetch_my_prs().await }).await,
+    )
+}
+
+/// Fetch PRs by an author from every host
+pub async fn fetch_prs_by_author_all(
+    hosts: &[String],
+    username: &str,
+) -> Result<PrList, ForgeError> {
+    let username = username.to_string();
+    merge_host_results(
+        hosts,
+        on_all_hosts(hosts, |forge| {
+            let username = username.clone();
+            async move { forge.fetch_prs_by_author(&username).await }
+        })
+        .await,
+    )
+}
+
+/// Fetch PR notifications from every host
+pub async fn fetch_notifications_all(hosts: &[String]) -> Result<PrList, ForgeError> {
+    merge_host_results(
+        hosts,
+        on_all_hosts(
+            hosts,
+            |forge| async move { forge.fetch_notifications().await },
+        )
+        .await,
+    )
+}
+
+/// Run each saved search query against every host, one result per query
+pub async fn fetch_search_prs_all(
+    hosts: &[String],
+    queries: &[String],
+) -> Vec<Result<PrList, ForgeError>> {
+    let mut results = Vec::with_capacity(queries.len());
+    for query in queries {
+        let query = query.clone();
+        results.push(merge_host_results(
+            hosts,
+            on_all_hosts(hosts, |forge| {
+                let query = query.clone();
+                async move { forge.fetch_search_prs(&query).await }
+            })
+            .await,
+        ));
+    }
+    results
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_http_status_mapping() {
+        assert!(matches!(
+            ForgeError::from_http(401, false, ""),
+            ForgeError::Auth(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_http(403, false, ""),
+            ForgeError::Auth(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_http(403, true, ""),
+            ForgeError::RateLimited(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_http(404, false, ""),
+            ForgeError::NotFound(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_http(422, false, ""),
+            ForgeError::Validation(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_http(429, false, ""),
+            ForgeError::RateLimited(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_http(500, false, ""),
+            ForgeError::Other(_)
+        ));
+    }
+
+    #[test]
+    fn test_from_http_rate_limit_in_body() {
+        let body = r#"{"message": "API rate limit exceeded for user ID 1."}"#;
+        let err = ForgeError::from_http(403, false, body);
+        assert!(matches!(err, ForgeError::RateLimited(_)));
+        assert!(err.message().contains("API rate limit exceeded"));
+    }
+
+    #[test]
+    fn test_from_http_validation_details() {
+        let body = r#"{"message": "Validation Failed", "errors": [{"message": "line must be part of the diff"}, "pull_request_review_thread.line"]}"#;
+        let err = ForgeError::from_http(422, false, body);
+        assert_eq!(
+            err.message(),
+            "Validation Failed: line must be part of the diff, pull_request_review_thread.line (HTTP 422)"
+        );
+    }
+
+    #[test]
+    fn test_from_http_non_json_body() {
+        let err = ForgeError::from_http(502, false, "Bad Gateway");
+        assert_eq!(err.message(), "Bad Gateway (HTTP 502)");
+
+        let err = ForgeError::from_http(500, false, "");
+        assert_eq!(err.message(), "Request failed (HTTP 500)");
+    }
+
+    #[test]
+    fn test_from_cli_message() {
+        assert!(matches!(
+            ForgeError::from_cli_message("Failed to fetch diff: gh: Not Found (HTTP 404)"),
+            ForgeError::NotFound(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_cli_message("Not authenticated with GitHub CLI. Run: gh auth login"),
+            ForgeError::Auth(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_cli_message("gh: API rate limit exceeded (HTTP 403)"),
+            ForgeError::RateLimited(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_cli_message(
+                "Failed to submit review: gh: Validation Failed (HTTP 422)"
+            ),
+            ForgeError::Validation(_)
+        ));
+        assert!(matches!(
+            ForgeError::from_cli_message("Failed to run 'gh' CLI"),
+            ForgeError::Other(_)
+        ));
+    }
+
+    #[test]
+    fn test_display_is_message() {
+        let err = ForgeError::NotFound("PR not found: o/r/pull/1".to_string());
+        assert_eq!(err.to_string(), "PR not found: o/r/pull/1");
+    }
+
+    #[tokio::test]
+    async fn test_select_backend_forced_gh() {
+        let mut config = Config::default();
+        config.github.backend = "gh".to_string();
+        config.github.token = Some("ghp_unused".to_string());
+        let backend = select_backend(&config, "github.example.com").await;
+        assert_eq!(backend.name(), "gh");
+    }
+
+    #[tokio::test]
+    async fn test_select_backend_enterprise_token_from_config() {
+        let mut config = Config::default();
+        config.hosts.push(crate::config::HostConfig {
+            host: "github.example.com".to_string(),
+            token: Some("ghe_token".to_string()),
+            ..Default::default()
+        });
+        let backend = select_backend(&config, "github.example.com").await;
+        assert_eq!(backend.name(), "api");
+    }
+
+    #[test]
+    fn test_build_backend_without_token_uses_gh() {
+        let config = Config::default();
+        assert_eq!(
+            build_backend(&config, "github.example.com", None).name(),
+            "gh"
+        );
+        assert_eq!(
+            build_backend(&config, "github.example.com", Some("ghp_token".to_string())).name(),
+            "api"
+        );
+    }
+
+    #[test]
+    fn test_api_url_for_host() {
+        let mut config = Config::default();
+        assert_eq!(api_url_for_host(&config, "github.com"), GITHUB_API_URL);
+        assert_eq!(
+            api_url_for_host(&config, "github.example.com"),
+            "https://github.example.com/api/v3"
+        );
+
+        config.hosts.push(crate::config::HostConfig {
+            host: "ghe.internal".to_string(),
+            api_url: Some("https://ghe.internal/custom".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            api_url_for_host(&config, "ghe.internal"),
+            "https://ghe.internal/custom"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_hosts_use_gitlab_backend() {
+        let mut config = Config::default();
+        assert_eq!(select_backend(&config, "gitlab.com").await.name(), "gitlab");
+        assert_eq!(
+            api_url_for_host(&config, "gitlab.com"),
+            "https://gitlab.com/api/v4"
+        );
+
+        config.hosts.push(crate::config::HostConfig {
+            host: "git.example.com".to_string(),
+            kind: ForgeKind::Gitlab,
+            token: Some("glpat-token".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            select_backend(&config, "git.example.com").await.name(),
+            "gitlab"
+        );
+        assert_eq!(
+            api_url_for_host(&config, "git.example.com"),
+            "https://git.example.com/api/v4"
+        );
+        assert_eq!(
+            resolve_host_token(&config, "git.example.com", "GITLAB_TOKEN").as_deref(),
+            Some("glpat-token")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gitea_hosts_use_gitea_backend() {
+        let mut config = Config::default();
+        config.hosts.push(crate::config::HostConfig {
+            host: "code.example.com".to_string(),
+            kind: ForgeKind::Gitea,
+            ..Default::default()
+        });
+        assert_eq!(
+            select_backend(&config, "code.example.com").await.name(),
+            "gitea"
+        );
+        assert_eq!(
+            api_url_for_host(&config, "code.example.com"),
+            "https://code.example.com/api/v1"
+        );
+    }
+
+    fn pr_on(host: &str, number: u32) -> ReviewPr {
+        ReviewPr {
+            host: host.to_string(),
+            number,
+            title: String::new(),
+            repo_owner: "o".to_string(),
+            repo_name: "r".to_string(),
+            author: "a".to_string(),
+            created_at: String::new(),
+            head_sha: None,
+            body: None,
+            is_draft: false,
+            review_decision: None,
+            ci_state: None,
+            size: None,
+            updated_at: None,
+            review_reasons: Vec::new(),
+            notification: None,
+        }
+    }
+
+    fn hosts() -> Vec<String> {
+        vec!["github.com".to_string(), "ghe.example.com".to_string()]
+    }
+
+    #[test]
+    fn test_merge_host_results_concatenates() {
+        let merged = merge_host_results(
+            &hosts(),
+            vec![
+                Ok(PrList {
+                    prs: vec![pr_on("github.com", 1)],
+                    ..Default::default()
+                }),
+                Ok(PrList {
+                    prs: vec![pr_on("ghe.example.com", 2), pr_on("ghe.example.com", 3)],
+                    truncated: true,
+                    ..Default::default()
+                }),
+            ],
+        )
+        .unwrap();
+        assert_eq!(merged.prs.len(), 3);
+        assert_eq!(merged.prs[1].host, "ghe.example.com");
+        assert!(merged.truncated);
+        assert!(merged.host_errors.is_empty());
+    }
+
+    #[test]
+    fn test_merge_host_results_tolerates_partial_failure() {
+        let merged = merge_host_results(
+            &hosts(),
+            vec![
+                Err(ForgeError::Auth("no token".to_string())),
+                Ok(PrList {
+                    prs: vec![pr_on("ghe.example.com", 2)],
+                    ..Default::default()
+                }),
+            ],
+        )
+        .unwrap();
+        assert_eq!(merged.prs.len(), 1);
+        assert!(!merged.truncated);
+        assert_eq!(merged.host_errors, vec!["github.com: no token".to_string()]);
+
+        let merged = merge_host_results(
+            &hosts(),
+            vec![Ok(PrList::default()), Err(notifications_unsupported())],
+        )
+        .unwrap();
+        assert!(merged.host_errors.is_empty());
+    }
+
+    #[test]
+    fn test_merge_host_results_all_failed() {
+        let err = merge_host_results(
+            &hosts(),
+            vec![
+                Err(ForgeError::Auth("first".to_string())),
+                Err(ForgeError::Other("second".to_string())),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err, ForgeError::Auth("first".to_string()));
+    }
+
+    #[test]
+    fn test_non_empty() {
+        assert_eq!(
+            non_empty(Some(" tok\n".to_string())),
+            Some("tok".to_string())
+        );
+        assert_eq!(non_empty(Some("  ".to_string())), None);
+        assert_eq!(non_empty(None), None);
+    }
+}
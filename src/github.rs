@@ -1,15 +1,17 @@
 use anyhow::{anyhow, Context, Result};
+use futures::future::join_all;
 use serde::Deserialize;
 use std::process::Stdio;
 use std::time::Instant;
 use tokio::process::Command;
 use url::Url;
 
-use crate::types::{CommentThread, IssueComment, PendingComment, PrInfo, ReviewComment, ReviewPr, ThreadComment};
+use crate::config::{Config, ForgeKind};
+use crate::types::{CommentThread, DiffSide, IssueComment, PendingComment, PrInfo, PrList, PrNotification, ReviewComment, ReviewPr, ReviewReason, ThreadComment, DEFAULT_HOST};
 
 /// Log performance timing to file if KENSA_DEBUG is set
 #[inline]
-fn perf_log(operation: &str, elapsed_ms: u128) {
+pub(crate) fn perf_log(operation: &str, elapsed_ms: u128) {
     if std::env::var("KENSA_DEBUG").is_ok() {
         use std::io::Write;
         if let Some(mut path) = dirs::config_dir() {
@@ -26,44 +28,102 @@ fn perf_log(operation: &str, elapsed_ms: u128) {
     }
 }
 
-/// Parse a GitHub PR URL into owner, repo, and PR number
+/// Parse a PR URL into host, owner, repo, and PR number
+/// Accepts github.com, gitlab.com merge requests, and any host listed under [[hosts]] in config.toml
 pub fn parse_pr_url(url_str: &str) -> Result<PrInfo> {
+    parse_pr_url_for_hosts(url_str, &Config::load().url_hosts())
+}
+
+/// Parse a PR URL, accepting only the given hosts
+pub fn parse_pr_url_for_hosts(url_str: &str, hosts: &[(String, ForgeKind)]) -> Result<PrInfo> {
     let url = Url::parse(url_str).context("Invalid URL")?;
 
-    if url.host_str() != Some("github.com") {
-        return Err(anyhow!("Only github.com URLs are supported"));
-    }
+    let host = url
+        .host_str()
+        .map(|h| h.to_lowercase())
+        .ok_or_else(|| anyhow!("Invalid URL: missing host"))?;
+
+    let kind = hosts
+        .iter()
+        .find(|(h, _)| h.eq_ignore_ascii_case(&host))
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unsupported host: {}. Add it to [[hosts]] in config.toml (kensa --edit-config)",
+                host
+            )
+        })?;
 
     let segments: Vec<_> = url
         .path_segments()
         .ok_or_else(|| anyhow!("Invalid PR URL path"))?
         .collect();
 
-    // Expected format: /owner/repo/pull/123
-    if segments.len() < 4 || segments[2] != "pull" {
-        return Err(anyhow!(
-            "Invalid PR URL format. Expected: https://github.com/owner/repo/pull/123"
-        ));
-    }
+    let (owner, repo, number) = match kind {
+        ForgeKind::Gitea => {
+            // Expected format: /owner/repo/pulls/123
+            if segments.len() < 4 || segments[2] != "pulls" {
+                return Err(anyhow!(
+                    "Invalid PR URL format. Expected: https://{}/owner/repo/pulls/123",
+                    host
+                ));
+            }
+            (segments[0].to_string(), segments[1].to_string(), segments[3])
+        }
+        ForgeKind::Github => {
+            // Expected format: /owner/repo/pull/123
+            if segments.len() < 4 || segments[2] != "pull" {
+                return Err(anyhow!(
+                    "Invalid PR URL format. Expected: https://{}/owner/repo/pull/123",
+                    host
+                ));
+            }
+            (segments[0].to_string(), segments[1].to_string(), segments[3])
+        }
+        ForgeKind::Gitlab => {
+            // Expected format: /group[/subgroup...]/project/-/merge_requests/123
+            let marker = segments.iter().position(|s| *s == "-");
+            match marker {
+                Some(i) if i >= 2 && segments.get(i + 1) == Some(&"merge_requests") && i + 2 < segments.len() => {
+                    (segments[..i - 1].join("/"), segments[i - 1].to_string(), segments[i + 2])
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Invalid merge request URL format. Expected: https://{}/group/project/-/merge_requests/123",
+                        host
+                    ));
+                }
+            }
+        }
+    };
 
-    let owner = segments[0].to_string();
-    let repo = segments[1].to_string();
-    let number: u32 = segments[3]
+    let number: u32 = number
         .parse()
         .context("PR number must be a valid integer")?;
 
     Ok(PrInfo {
+        host,
         owner,
         repo,
         number,
     })
 }
 
+/// Create a gh command targeting a specific host
+/// GH_HOST makes every gh subcommand (api, pr, search) talk to that host
+fn gh_command(host: &str) -> Command {
+    let mut command = Command::new("gh");
+    if host != DEFAULT_HOST {
+        command.env("GH_HOST", host);
+    }
+    command
+}
+
 /// Check if gh CLI is installed and authenticated
-pub async fn check_gh_cli() -> Result<()> {
+pub async fn check_gh_cli(host: &str) -> Result<()> {
     let start = Instant::now();
-    let output = Command::new("gh")
-        .args(["auth", "status"])
+    let output = gh_command(host)
+        .args(["auth", "status", "--hostname", host])
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
         .output()
@@ -76,7 +136,9 @@ pub async fn check_gh_cli() -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr);
         if stderr.contains("not logged") {
             return Err(anyhow!(
-                "Not authenticated with GitHub CLI. Run: gh auth login"
+                "Not authenticated with GitHub CLI for {}. Run: gh auth login --hostname {}",
+                host,
+                host
             ));
         }
         return Err(anyhow!("gh auth check failed: {}", stderr));
@@ -88,7 +150,7 @@ pub async fn check_gh_cli() -> Result<()> {
 /// Fetch the diff content for a PR using gh CLI
 pub async fn fetch_pr_diff(pr: &PrInfo) -> Result<String> {
     let start = Instant::now();
-    let output = Command::new("gh")
+    let output = gh_command(&pr.host)
         .args([
             "api",
             &format!("repos/{}/{}/pulls/{}", pr.owner, pr.repo, pr.number),
@@ -123,6 +185,33 @@ pub async fn fetch_pr_diff(pr: &PrInfo) -> Result<String> {
     Ok(diff)
 }
 
+/// Fetch the unified diff between two commits of a PR's repository, from their merge base to `head`
+pub async fn fetch_compare_diff(pr: &PrInfo, base: &str, head: &str) -> Result<String> {
+    let start = Instant::now();
+    let output = gh_command(&pr.host)
+        .args([
+            "api",
+            &format!("repos/{}/{}/compare/{}...{}", pr.owner, pr.repo, base, head),
+            "-H",
+            "Accept: application/vnd.github.v3.diff",
+        ])
+        .output()
+        .await
+        .context("Failed to fetch compare diff")?;
+
+    perf_log(&format!("fetch_compare_diff #{}", pr.number), start.elapsed().as_millis());
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("404") {
+            return Err(anyhow!("Commit {} not found in {}/{}", base, pr.owner, pr.repo));
+        }
+        return Err(anyhow!("Failed to fetch compare diff: {}", stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// JSON structure for gh search prs output
 #[derive(Debug, Deserialize)]
 struct GhSearchPrResult {
@@ -132,6 +221,10 @@ struct GhSearchPrResult {
     author: GhAuthor,
     #[serde(rename = "createdAt")]
     created_at: String,
+    #[serde(rename = "updatedAt", default)]
+    updated_at: Option<String>,
+    #[serde(rename = "isDraft", default)]
+    is_draft: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -146,23 +239,24 @@ struct GhAuthor {
     login: String,
 }
 
-/// Common helper for searching PRs with a specific filter
-async fn search_prs_with_filter(filter: &str) -> Result<Vec<ReviewPr>> {
+/// Common helper for searching PRs with specific filters (flags or `--` followed by query terms)
+/// Fetches up to `max_prs` results; one extra is requested to tell whether the cap cut the list short
+async fn search_prs_with_filter(host: &str, filters: &[&str], max_prs: usize) -> Result<PrList> {
     let start = Instant::now();
-    let output = Command::new("gh")
+    let output = gh_command(host)
         .args([
             "search",
             "prs",
-            filter,
             "--state=open",
-            "--json=number,title,repository,author,createdAt,url",
-            "--limit=100",
+            "--json=number,title,repository,author,createdAt,updatedAt,isDraft,url",
+            &format!("--limit={}", max_prs + 1),
         ])
+        .args(filters)
         .output()
         .await
         .context("Failed to fetch PRs. Is 'gh' CLI installed?")?;
 
-    perf_log(&format!("gh search prs {}", filter), start.elapsed().as_millis());
+    perf_log(&format!("gh search prs {}", filters.join(" ")), start.elapsed().as_millis());
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -173,8 +267,10 @@ async fn search_prs_with_filter(filter: &str) -> Result<Vec<ReviewPr>> {
     let results: Vec<GhSearchPrResult> =
         serde_json::from_str(&json_str).context("Failed to parse PR list JSON")?;
 
+    let truncated = results.len() > max_prs;
     let prs = results
         .into_iter()
+        .take(max_prs)
         .map(|r| {
             let (repo_owner, repo_name) = r
                 .repository
@@ -184,6 +280,7 @@ async fn search_prs_with_filter(filter: &str) -> Result<Vec<ReviewPr>> {
                 .unwrap_or((String::new(), r.repository.name));
 
             ReviewPr {
+                host: host.to_string(),
                 number: r.number,
                 title: r.title,
                 repo_owner,
@@ -192,17 +289,24 @@ async fn search_prs_with_filter(filter: &str) -> Result<Vec<ReviewPr>> {
                 created_at: r.created_at,
                 head_sha: None,
                 body: None, // Not fetched in search results
+                is_draft: r.is_draft,
+                review_decision: None,
+                ci_state: None,
+                size: None,
+                updated_at: r.updated_at,
+                review_reasons: Vec::new(),
+                notification: None,
             }
         })
         .collect();
 
-    Ok(prs)
+    Ok(PrList { prs, truncated, ..Default::default() })
 }
 
 /// Get the current authenticated GitHub username
-async fn get_current_user() -> Result<String> {
+pub async fn get_current_user(host: &str) -> Result<String> {
     let start = Instant::now();
-    let output = Command::new("gh")
+    let output = gh_command(host)
         .args(["api", "user", "--jq", ".login"])
         .output()
         .await
@@ -217,44 +321,271 @@ async fn get_current_user() -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-/// Fetch all PRs where review is requested or already reviewed by the current user
-pub async fn fetch_review_prs() -> Result<Vec<ReviewPr>> {
+/// Get the current user's teams, as "org/team"
+/// Listing teams needs the read:org scope
+pub async fn get_user_teams(host: &str) -> Result<Vec<String>> {
+    let start = Instant::now();
+    let output = gh_command(host)
+        .args([
+            "api",
+            "--paginate",
+            "user/teams",
+            "--jq",
+            r#".[] | "\(.organization.login)/\(.slug)""#,
+        ])
+        .output()
+        .await
+        .context("Failed to get teams")?;
+
+    perf_log("gh api user/teams", start.elapsed().as_millis());
+
+    if !output.status.success() {
+        return Err(anyhow!("Failed to get teams"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Fetch all PRs where review is requested from the current user or one of their teams,
+/// or that the user already reviewed
+pub async fn fetch_review_prs(host: &str, max_prs: usize) -> Result<PrList> {
     let start = Instant::now();
 
-    // Fetch current user, requested PRs, and reviewed PRs in parallel
-    let (current_user, requested, reviewed) = tokio::join!(
-        get_current_user(),
-        search_prs_with_filter("--review-requested=@me"),
-        search_prs_with_filter("--reviewed-by=@me")
+    // Fetch current user, teams, requested PRs, and reviewed PRs in parallel
+    let (current_user, teams, requested, direct, reviewed) = tokio::join!(
+        get_current_user(host),
+        get_user_teams(host),
+        search_prs_with_filter(host, &["--review-requested=@me"], max_prs),
+        search_prs_with_filter(host, &["--", "user-review-requested:@me"], max_prs),
+        search_prs_with_filter(host, &["--reviewed-by=@me"], max_prs)
     );
 
-    let current_user = current_user?;
-    let mut prs = requested?;
-    let reviewed_prs = reviewed?;
+    let (requested, reviewed) = (requested?, reviewed?);
+    let mut truncated = requested.truncated || reviewed.truncated;
+
+    // Team searches only tag requested PRs; failed searches leave their PRs untagged
+    let teams: Vec<String> = teams.unwrap_or_default().into_iter().take(MAX_TEAM_SEARCHES).collect();
+    let team_results = join_all(teams.iter().map(|team| {
+        let query = format!("team-review-requested:{}", team);
+        async move { search_prs_with_filter(host, &["--", &query], max_prs).await }
+    }))
+    .await;
+    let team_prs = teams
+        .into_iter()
+        .zip(team_results)
+        .filter_map(|(team, result)| result.ok().map(|list| (team, list)))
+        .map(|(team, list)| {
+            truncated |= list.truncated;
+            (team, list.prs)
+        })
+        .collect();
+    let direct = direct.ok().map(|list| {
+        truncated |= list.truncated;
+        list.prs
+    });
+
+    let mut lists = review_request_lists(requested.prs, direct, team_prs);
+    lists.push((ReviewReason::Reviewed, reviewed.prs));
+
+    let prs = PrList {
+        prs: merge_review_prs(&current_user?, lists),
+        truncated,
+        ..Default::default()
+    };
+
+    perf_log("fetch_review_prs (total)", start.elapsed().as_millis());
+    Ok(prs)
+}
 
-    // Add reviewed PRs that aren't already in the list (dedupe by repo+number)
-    // Also filter out PRs where the current user is the author
-    for pr in reviewed_prs {
-        // Skip PRs authored by the current user
-        if pr.author.eq_ignore_ascii_case(&current_user) {
-            continue;
+/// Teams searched for review requests, to stay within GitHub's search rate limit
+/// (30 requests a minute); requests to further teams are listed untagged
+pub(crate) const MAX_TEAM_SEARCHES: usize = 5;
+
+/// Split the PRs requesting the user's review by who was asked, the user (`direct`) and/or
+/// their teams; a PR asked of both is listed under each
+/// Without a `direct` result, PRs no team search found are taken as direct requests.
+/// Search results that aren't in `requested` are ignored
+pub(crate) fn review_request_lists(
+    requested: Vec<ReviewPr>,
+    direct: Option<Vec<ReviewPr>>,
+    teams: Vec<(String, Vec<ReviewPr>)>,
+) -> Vec<(ReviewReason, Vec<ReviewPr>)> {
+    let mut team_lists: Vec<(ReviewReason, Vec<ReviewPr>)> = teams
+        .iter()
+        .map(|(team, _)| (ReviewReason::Team(team.clone()), Vec::new()))
+        .collect();
+    let mut direct_list = Vec::new();
+
+    for pr in requested {
+        let mut by_team = false;
+        for ((_, team_prs), (_, list)) in teams.iter().zip(team_lists.iter_mut()) {
+            if team_prs.iter().any(|team_pr| is_same_pr(team_pr, &pr)) {
+                list.push(pr.clone());
+                by_team = true;
+            }
+        }
+        let is_direct = match &direct {
+            Some(direct) => direct.iter().any(|direct_pr| is_same_pr(direct_pr, &pr)),
+            None => !by_team,
+        };
+        if is_direct {
+            direct_list.push(pr);
         }
+    }
 
-        let exists = prs.iter().any(|p| {
-            p.number == pr.number && p.repo_owner == pr.repo_owner && p.repo_name == pr.repo_name
-        });
-        if !exists {
-            prs.push(pr);
+    let mut lists = vec![(ReviewReason::Direct, direct_list)];
+    lists.extend(team_lists);
+    lists
+}
+
+/// Whether two list entries are the same PR (same host, repo and number)
+fn is_same_pr(a: &ReviewPr, b: &ReviewPr) -> bool {
+    a.number == b.number && a.host == b.host && a.repo_owner == b.repo_owner && a.repo_name == b.repo_name
+}
+
+/// Merge the lists behind the "For Review" tab, tagging each PR with why it is listed
+/// A PR found in several lists is kept once with every reason (dedupe by repo+number)
+/// Already-reviewed PRs authored by the current user are skipped
+pub(crate) fn merge_review_prs(
+    current_user: &str,
+    lists: Vec<(ReviewReason, Vec<ReviewPr>)>,
+) -> Vec<ReviewPr> {
+    let mut prs: Vec<ReviewPr> = Vec::new();
+
+    for (reason, list) in lists {
+        for mut pr in list {
+            if reason == ReviewReason::Reviewed && pr.author.eq_ignore_ascii_case(current_user) {
+                continue;
+            }
+
+            let existing = prs.iter_mut().find(|p| is_same_pr(p, &pr));
+            match existing {
+                Some(existing) => {
+                    if !existing.review_reasons.contains(&reason) {
+                        existing.review_reasons.push(reason.clone());
+                    }
+                }
+                None => {
+                    pr.review_reasons = vec![reason.clone()];
+                    prs.push(pr);
+                }
+            }
         }
     }
 
-    perf_log("fetch_review_prs (total)", start.elapsed().as_millis());
-    Ok(prs)
+    prs
+}
+
+/// A notification thread from the REST API
+#[derive(Debug, Deserialize)]
+pub(crate) struct NotificationItem {
+    id: String,
+    unread: bool,
+    reason: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    subject: NotificationSubject,
+    repository: NotificationRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationSubject {
+    title: String,
+    /// API URL of the subject, ending in the PR number for pull requests
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationRepository {
+    name: String,
+    owner: GhAuthor,
+}
+
+/// Turn the notifications about pull requests into inbox entries
+/// Author and creation time aren't part of a notification; the list details lookup fills them in
+pub(crate) fn notification_prs(host: &str, items: Vec<NotificationItem>) -> Vec<ReviewPr> {
+    items
+        .into_iter()
+        .filter(|item| item.subject.kind == "PullRequest")
+        .filter_map(|item| {
+            let number = item.subject.url.as_deref()?.rsplit('/').next()?.parse().ok()?;
+            Some(ReviewPr {
+                host: host.to_string(),
+                number,
+                title: item.subject.title,
+                repo_owner: item.repository.owner.login,
+                repo_name: item.repository.name,
+                author: String::new(),
+                created_at: String::new(),
+                head_sha: None,
+                body: None,
+                is_draft: false,
+                review_decision: None,
+                ci_state: None,
+                size: None,
+                updated_at: item.updated_at,
+                review_reasons: Vec::new(),
+                notification: Some(PrNotification {
+                    thread_id: item.id,
+                    reason: item.reason,
+                    unread: item.unread,
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Fetch unread notifications about pull requests, up to `max_prs`
+pub async fn fetch_notifications(host: &str, max_prs: usize) -> Result<PrList> {
+    let start = Instant::now();
+    let output = gh_command(host)
+        .args(["api", "--paginate", "notifications"])
+        .output()
+        .await
+        .context("Failed to fetch notifications")?;
+
+    perf_log("gh api notifications", start.elapsed().as_millis());
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to fetch notifications: {}", stderr));
+    }
+
+    let json_str = String::from_utf8(output.stdout).context("Invalid UTF-8")?;
+    let items = parse_paginated_json(&json_str).context("Failed to parse notifications")?;
+    let mut prs = notification_prs(host, items);
+    let truncated = prs.len() > max_prs;
+    prs.truncate(max_prs);
+    Ok(PrList { prs, truncated, ..Default::default() })
+}
+
+/// Mark a notification thread as read, or as done with `done`
+pub async fn mark_notification(host: &str, thread_id: &str, done: bool) -> Result<()> {
+    let method = if done { "DELETE" } else { "PATCH" };
+    let output = gh_command(host)
+        .args(["api", "-X", method, &format!("notifications/threads/{}", thread_id)])
+        .output()
+        .await
+        .context("Failed to update notification")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to update notification: {}", stderr));
+    }
+
+    Ok(())
 }
 
 /// Fetch the head SHA for a PR (needed for inline comments)
 pub async fn fetch_pr_head_sha(pr: &PrInfo) -> Result<String> {
-    let output = Command::new("gh")
+    let output = gh_command(&pr.host)
         .args([
             "pr",
             "view",
@@ -288,7 +619,7 @@ pub async fn fetch_pr_head_sha(pr: &PrInfo) -> Result<String> {
 /// Returns a ReviewPr with all fields populated including head_sha and body
 pub async fn fetch_pr_details(pr: &PrInfo) -> Result<ReviewPr> {
     let start = Instant::now();
-    let output = Command::new("gh")
+    let output = gh_command(&pr.host)
         .args([
             "pr",
             "view",
@@ -325,6 +656,7 @@ pub async fn fetch_pr_details(pr: &PrInfo) -> Result<ReviewPr> {
     let details: PrDetails = serde_json::from_str(&json_str).context("Failed to parse PR details")?;
 
     Ok(ReviewPr {
+        host: pr.host.clone(),
         number: details.number,
         title: details.title,
         repo_owner: pr.owner.clone(),
@@ -333,17 +665,22 @@ pub async fn fetch_pr_details(pr: &PrInfo) -> Result<ReviewPr> {
         created_at: details.created_at,
         head_sha: Some(details.head_ref_oid),
         body: details.body,
+        is_draft: false,
+        review_decision: None,
+        ci_state: None,
+        size: None,
+        updated_at: None,
+        review_reasons: Vec::new(),
+        notification: None,
     })
 }
 
-/// Submit a comment to a PR (general or inline)
+/// Submit a comment to a PR (general, inline or file-level)
 pub async fn submit_pr_comment(pr: &PrInfo, comment: &PendingComment, head_sha: Option<&str>) -> Result<()> {
     let repo = format!("{}/{}", pr.owner, pr.repo);
 
-    if comment.is_inline() {
-        // Inline comment - use GitHub API
-        let file_path = comment.file_path.as_ref().unwrap();
-        let line = comment.line_number.unwrap();
+    if let Some(file_path) = &comment.file_path {
+        // Inline or file-level comment - use GitHub API
         let commit_id = head_sha.ok_or_else(|| anyhow!("Head SHA required for inline comments"))?;
 
         let mut args = vec![
@@ -351,20 +688,31 @@ pub async fn submit_pr_comment(pr: &PrInfo, comment: &PendingComment, head_sha:
             format!("repos/{}/pulls/{}/comments", repo, pr.number),
             "-f".to_string(), format!("body={}", comment.body),
             "-f".to_string(), format!("path={}", file_path),
-            "-F".to_string(), format!("line={}", line),
-            "-f".to_string(), "side=RIGHT".to_string(),
             "-f".to_string(), format!("commit_id={}", commit_id),
         ];
 
+        match comment.line_number {
+            Some(line) => {
+                args.push("-F".to_string());
+                args.push(format!("line={}", line));
+                args.push("-f".to_string());
+                args.push(format!("side={}", comment.side.as_str()));
+            }
+            None => {
+                args.push("-f".to_string());
+                args.push("subject_type=file".to_string());
+            }
+        }
+
         // Add start_line for multi-line comments
         if let Some(start_line) = comment.start_line {
             args.push("-F".to_string());
             args.push(format!("start_line={}", start_line));
             args.push("-f".to_string());
-            args.push("start_side=RIGHT".to_string());
+            args.push(format!("start_side={}", comment.start_side.as_str()));
         }
 
-        let output = Command::new("gh")
+        let output = gh_command(&pr.host)
             .args(&args)
             .output()
             .await
@@ -376,7 +724,7 @@ pub async fn submit_pr_comment(pr: &PrInfo, comment: &PendingComment, head_sha:
         }
     } else {
         // General PR comment
-        let output = Command::new("gh")
+        let output = gh_command(&pr.host)
             .args([
                 "pr",
                 "comment",
@@ -406,9 +754,9 @@ pub async fn submit_pr_comments(pr: &PrInfo, comments: &[PendingComment], head_s
         return Ok(0);
     }
 
-    // Separate inline and general comments
+    // Separate inline (and file-level) comments from general ones
     let (inline_comments, general_comments): (Vec<_>, Vec<_>) =
-        comments.iter().partition(|c| c.is_inline());
+        comments.iter().partition(|c| c.is_review_comment());
 
     let mut submitted = 0;
 
@@ -432,20 +780,28 @@ pub async fn submit_pr_comments(pr: &PrInfo, comments: &[PendingComment], head_s
 }
 
 /// Build review comments JSON array for the GitHub Review API
-fn build_review_comments_json(comments: &[&PendingComment]) -> Vec<serde_json::Value> {
+pub(crate) fn build_review_comments_json(comments: &[&PendingComment]) -> Vec<serde_json::Value> {
     comments
         .iter()
         .map(|c| {
+            if c.is_file_level() {
+                return serde_json::json!({
+                    "path": c.file_path.as_ref().unwrap(),
+                    "body": c.body,
+                    "subject_type": "file"
+                });
+            }
+
             let mut comment_obj = serde_json::json!({
                 "path": c.file_path.as_ref().unwrap(),
                 "line": c.line_number.unwrap(),
                 "body": c.body,
-                "side": "RIGHT"
+                "side": c.side.as_str()
             });
 
             if let Some(start_line) = c.start_line {
                 comment_obj["start_line"] = serde_json::json!(start_line);
-                comment_obj["start_side"] = serde_json::json!("RIGHT");
+                comment_obj["start_side"] = serde_json::json!(c.start_side.as_str());
             }
 
             comment_obj
@@ -473,7 +829,7 @@ async fn submit_inline_comments_batch(pr: &PrInfo, comments: &[&PendingComment],
         .context("Failed to serialize request body")?;
 
     // Use Review API with --input to send proper JSON body
-    let mut child = Command::new("gh")
+    let mut child = gh_command(&pr.host)
         .args([
             "api",
             &format!("repos/{}/pulls/{}/reviews", repo, pr.number),
@@ -504,13 +860,21 @@ async fn submit_inline_comments_batch(pr: &PrInfo, comments: &[&PendingComment],
 }
 
 /// Fetch PRs authored by the current user
-pub async fn fetch_my_prs() -> Result<Vec<ReviewPr>> {
-    search_prs_with_filter("--author=@me").await
+pub async fn fetch_my_prs(host: &str, max_prs: usize) -> Result<PrList> {
+    search_prs_with_filter(host, &["--author=@me"], max_prs).await
 }
 
 /// Fetch PRs authored by a specific user
-pub async fn fetch_prs_by_author(username: &str) -> Result<Vec<ReviewPr>> {
-    search_prs_with_filter(&format!("--author={}", username)).await
+pub async fn fetch_prs_by_author(host: &str, username: &str, max_prs: usize) -> Result<PrList> {
+    search_prs_with_filter(host, &[&format!("--author={}", username)], max_prs).await
+}
+
+/// Fetch open PRs matching a search query
+/// Terms are passed after `--` so exclusions like `-label:wip` aren't read as flags
+pub async fn search_prs(host: &str, query: &str, max_prs: usize) -> Result<PrList> {
+    let mut filters = vec!["--"];
+    filters.extend(query.split_whitespace());
+    search_prs_with_filter(host, &filters, max_prs).await
 }
 
 // ============================================================================
@@ -519,7 +883,7 @@ pub async fn fetch_prs_by_author(username: &str) -> Result<Vec<ReviewPr>> {
 
 /// Fetch review comments (inline on code) for a PR
 pub async fn fetch_pr_review_comments(pr: &PrInfo) -> Result<Vec<ReviewComment>> {
-    let output = Command::new("gh")
+    let output = gh_command(&pr.host)
         .args([
             "api",
             &format!("repos/{}/{}/pulls/{}/comments", pr.owner, pr.repo, pr.number),
@@ -535,21 +899,12 @@ pub async fn fetch_pr_review_comments(pr: &PrInfo) -> Result<Vec<ReviewComment>>
     }
 
     let json_str = String::from_utf8(output.stdout).context("Invalid UTF-8")?;
-
-    // Handle empty response
-    if json_str.trim().is_empty() || json_str.trim() == "[]" {
-        return Ok(Vec::new());
-    }
-
-    let comments: Vec<ReviewComment> = serde_json::from_str(&json_str)
-        .context("Failed to parse review comments")?;
-
-    Ok(comments)
+    parse_paginated_json(&json_str).context("Failed to parse review comments")
 }
 
 /// Fetch general PR comments (issue comments)
 pub async fn fetch_pr_issue_comments(pr: &PrInfo) -> Result<Vec<IssueComment>> {
-    let output = Command::new("gh")
+    let output = gh_command(&pr.host)
         .args([
             "api",
             &format!("repos/{}/{}/issues/{}/comments", pr.owner, pr.repo, pr.number),
@@ -565,16 +920,16 @@ pub async fn fetch_pr_issue_comments(pr: &PrInfo) -> Result<Vec<IssueComment>> {
     }
 
     let json_str = String::from_utf8(output.stdout).context("Invalid UTF-8")?;
+    parse_paginated_json(&json_str).context("Failed to parse PR comments")
+}
 
-    // Handle empty response
-    if json_str.trim().is_empty() || json_str.trim() == "[]" {
-        return Ok(Vec::new());
+/// Parse `gh api --paginate` output: one JSON array per page, printed back to back
+fn parse_paginated_json<T: serde::de::DeserializeOwned>(json_str: &str) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    for page in serde_json::Deserializer::from_str(json_str).into_iter::<Vec<T>>() {
+        items.extend(page?);
     }
-
-    let comments: Vec<IssueComment> = serde_json::from_str(&json_str)
-        .context("Failed to parse PR comments")?;
-
-    Ok(comments)
+    Ok(items)
 }
 
 /// Fetch all comment threads for a PR (combines review + issue comments)
@@ -585,9 +940,48 @@ pub async fn fetch_all_comment_threads(pr: &PrInfo) -> Result<Vec<CommentThread>
         fetch_pr_issue_comments(pr)
     );
 
-    let review_comments = review_result?;
-    let issue_comments = issue_result?;
+    Ok(build_comment_threads(review_result?, issue_result?))
+}
+
+/// Run a GraphQL request (`{"query": ..., "variables": ...}`) with `gh api graphql`
+pub async fn graphql(host: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
+    use tokio::io::AsyncWriteExt;
+
+    let start = Instant::now();
+    let mut child = gh_command(host)
+        .args(["api", "graphql", "--input", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run 'gh' CLI. Is it installed? (brew install gh)")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body.to_string().as_bytes())
+            .await
+            .context("Failed to send GraphQL query to gh")?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .context("Failed to run GraphQL query")?;
+
+    perf_log("gh api graphql", start.elapsed().as_millis());
 
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("GraphQL query failed: {}", stderr.trim()));
+    }
+
+    serde_json::from_slice(&output.stdout).context("Failed to parse GraphQL response")
+}
+
+/// Combine review and issue comments into a sorted list of threads
+pub(crate) fn build_comment_threads(
+    review_comments: Vec<ReviewComment>,
+    issue_comments: Vec<IssueComment>,
+) -> Vec<CommentThread> {
     let mut threads = Vec::new();
 
     // Group review comments by reply chain (in_reply_to_id)
@@ -597,18 +991,31 @@ pub async fn fetch_all_comment_threads(pr: &PrInfo) -> Result<Vec<CommentThread>
     for comment in issue_comments {
         threads.push(CommentThread {
             id: comment.id,
+            node_id: None,
             file_path: None,
             line: None,
+            side: DiffSide::Right,
+            start_line: None,
             comments: vec![ThreadComment {
+                id: comment.id,
+                node_id: None,
                 body: comment.body,
                 author: comment.user.login,
                 created_at: comment.created_at,
+                by_viewer: false,
+                reactions: Vec::new(),
             }],
             outdated: false, // Issue comments are never outdated
+            resolved: false,
         });
     }
 
-    // Sort threads: inline comments by file/line, then general comments
+    sort_comment_threads(&mut threads);
+    threads
+}
+
+/// Sort threads: inline comments by file/line, then general comments
+pub(crate) fn sort_comment_threads(threads: &mut [CommentThread]) {
     threads.sort_by(|a, b| {
         match (&a.file_path, &b.file_path) {
             (Some(pa), Some(pb)) => pa.cmp(pb)
@@ -618,8 +1025,6 @@ pub async fn fetch_all_comment_threads(pr: &PrInfo) -> Result<Vec<CommentThread>
             (None, None) => a.id.cmp(&b.id),
         }
     });
-
-    Ok(threads)
 }
 
 /// Group review comments into threads based on in_reply_to_id
@@ -657,9 +1062,13 @@ fn group_review_comments_into_threads(comments: Vec<ReviewComment>) -> Vec<Comme
         while let Some(id) = stack.pop() {
             if let Some(comment) = by_id.get(&id) {
                 thread_comments.push(ThreadComment {
+                    id: comment.id,
+                    node_id: None,
                     body: comment.body.clone(),
                     author: comment.user.login.clone(),
                     created_at: comment.created_at.clone(),
+                    by_viewer: false,
+                    reactions: Vec::new(),
                 });
 
                 // Add children (in reverse order to maintain chronological order when popping)
@@ -677,10 +1086,14 @@ fn group_review_comments_into_threads(comments: Vec<ReviewComment>) -> Vec<Comme
         if let Some(root) = by_id.get(&root_id) {
             threads.push(CommentThread {
                 id: root_id,
+                node_id: None,
                 file_path: Some(root.path.clone()),
                 line: root.line,
+                side: root.side,
+                start_line: root.start_line,
                 comments: thread_comments,
                 outdated: root.is_outdated(),
+                resolved: false,
             });
         }
     }
@@ -688,6 +1101,50 @@ fn group_review_comments_into_threads(comments: Vec<ReviewComment>) -> Vec<Comme
     threads
 }
 
+/// REST path of a submitted comment
+/// Comments in review threads are pull request comments, the rest are issue comments
+pub(crate) fn comment_path(pr: &PrInfo, thread: &CommentThread, comment_id: u64) -> String {
+    let kind = if thread.is_inline() { "pulls" } else { "issues" };
+    format!("repos/{}/{}/{}/comments/{}", pr.owner, pr.repo, kind, comment_id)
+}
+
+/// Replace the text of a submitted comment
+pub async fn edit_comment(pr: &PrInfo, thread: &CommentThread, comment_id: u64, body: &str) -> Result<()> {
+    let output = gh_command(&pr.host)
+        .args([
+            "api",
+            "-X", "PATCH",
+            &comment_path(pr, thread, comment_id),
+            "-f", &format!("body={}", body),
+        ])
+        .output()
+        .await
+        .context("Failed to edit comment")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to edit comment: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Delete a submitted comment
+pub async fn delete_comment(pr: &PrInfo, thread: &CommentThread, comment_id: u64) -> Result<()> {
+    let output = gh_command(&pr.host)
+        .args(["api", "-X", "DELETE", &comment_path(pr, thread, comment_id)])
+        .output()
+        .await
+        .context("Failed to delete comment")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to delete comment: {}", stderr));
+    }
+
+    Ok(())
+}
+
 /// Submit a reply to an existing comment thread
 pub async fn submit_thread_reply(
     pr: &PrInfo,
@@ -698,7 +1155,7 @@ pub async fn submit_thread_reply(
 
     if thread.is_inline() {
         // Reply to review comment using in_reply_to
-        let output = Command::new("gh")
+        let output = gh_command(&pr.host)
             .args([
                 "api",
                 &format!("repos/{}/pulls/{}/comments", repo, pr.number),
@@ -715,7 +1172,7 @@ pub async fn submit_thread_reply(
         }
     } else {
         // Reply to issue comment (just add a new issue comment)
-        let output = Command::new("gh")
+        let output = gh_command(&pr.host)
             .args([
                 "api",
                 &format!("repos/{}/issues/{}/comments", repo, pr.number),
@@ -749,9 +1206,9 @@ pub async fn submit_pr_review(
         return Err(anyhow!("Request changes requires a comment"));
     }
 
-    // Separate inline and general comments
+    // Separate inline (and file-level) comments from general ones
     let (inline_comments, general_comments): (Vec<_>, Vec<_>) = pending_comments
-        .map(|c| c.iter().partition(|c| c.is_inline()))
+        .map(|c| c.iter().partition(|c| c.is_review_comment()))
         .unwrap_or_default();
 
     // Get head SHA if we have inline comments
@@ -764,30 +1221,12 @@ pub async fn submit_pr_review(
         String::new()
     };
 
-    // Build the review comments array for inline comments
-    let review_comments = build_review_comments_json(&inline_comments);
-
-    // Build the request body
-    let mut request_body = serde_json::json!({
-        "event": event
-    });
-
-    // Add body if provided
-    if let Some(body_text) = body
-        && !body_text.is_empty() {
-            request_body["body"] = serde_json::json!(body_text);
-        }
-
-    // Add inline comments and commit_id if we have any
-    if !review_comments.is_empty() {
-        request_body["comments"] = serde_json::json!(review_comments);
-        request_body["commit_id"] = serde_json::json!(commit_id);
-    }
+    let request_body = build_review_request_body(event, body, &inline_comments, &commit_id);
 
     let body_json = serde_json::to_string(&request_body)
         .context("Failed to serialize request body")?;
 
-    let mut child = Command::new("gh")
+    let mut child = gh_command(&pr.host)
         .args([
             "api",
             &format!("repos/{}/pulls/{}/reviews", repo, pr.number),
@@ -825,6 +1264,35 @@ pub async fn submit_pr_review(
     Ok(submitted)
 }
 
+/// Build the request body for the Review API
+pub(crate) fn build_review_request_body(
+    event: &str,
+    body: Option<&str>,
+    inline_comments: &[&PendingComment],
+    commit_id: &str,
+) -> serde_json::Value {
+    // Build the review comments array for inline comments
+    let review_comments = build_review_comments_json(inline_comments);
+
+    let mut request_body = serde_json::json!({
+        "event": event
+    });
+
+    // Add body if provided
+    if let Some(body_text) = body
+        && !body_text.is_empty() {
+            request_body["body"] = serde_json::json!(body_text);
+        }
+
+    // Add inline comments and commit_id if we have any
+    if !review_comments.is_empty() {
+        request_body["comments"] = serde_json::json!(review_comments);
+        request_body["commit_id"] = serde_json::json!(commit_id);
+    }
+
+    request_body
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -948,8 +1416,86 @@ mod tests {
 
     #[test]
     fn test_parse_pr_url_github_enterprise_rejected() {
-        // Only github.com is supported
-        assert!(parse_pr_url("https://github.mycompany.com/owner/repo/pull/1").is_err());
+        // Enterprise hosts are rejected unless configured
+        let hosts = vec![(DEFAULT_HOST.to_string(), ForgeKind::Github)];
+        let err = parse_pr_url_for_hosts("https://github.mycompany.com/owner/repo/pull/1", &hosts)
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported host: github.mycompany.com"));
+    }
+
+    #[test]
+    fn test_parse_pr_url_github_enterprise_configured() {
+        let hosts = vec![
+            (DEFAULT_HOST.to_string(), ForgeKind::Github),
+            ("github.mycompany.com".to_string(), ForgeKind::Github),
+        ];
+        let pr = parse_pr_url_for_hosts("https://github.mycompany.com/owner/repo/pull/1", &hosts)
+            .unwrap();
+        assert_eq!(pr.host, "github.mycompany.com");
+        assert_eq!(pr.owner, "owner");
+        assert_eq!(pr.repo, "repo");
+        assert_eq!(pr.number, 1);
+    }
+
+    #[test]
+    fn test_parse_pr_url_host_case_insensitive() {
+        let hosts = vec![("github.mycompany.com".to_string(), ForgeKind::Github)];
+        let pr = parse_pr_url_for_hosts("https://GitHub.MyCompany.com/owner/repo/pull/9", &hosts)
+            .unwrap();
+        assert_eq!(pr.host, "github.mycompany.com");
+    }
+
+    #[test]
+    fn test_parse_pr_url_gitlab_merge_request() {
+        let pr = parse_pr_url("https://gitlab.com/group/project/-/merge_requests/42").unwrap();
+        assert_eq!(pr.host, "gitlab.com");
+        assert_eq!(pr.owner, "group");
+        assert_eq!(pr.repo, "project");
+        assert_eq!(pr.number, 42);
+    }
+
+    #[test]
+    fn test_parse_pr_url_gitlab_nested_namespace() {
+        let pr = parse_pr_url("https://gitlab.com/org/team/sub/project/-/merge_requests/7/diffs")
+            .unwrap();
+        assert_eq!(pr.owner, "org/team/sub");
+        assert_eq!(pr.repo, "project");
+        assert_eq!(pr.number, 7);
+    }
+
+    #[test]
+    fn test_parse_pr_url_gitlab_self_hosted() {
+        let hosts = vec![("git.example.com".to_string(), ForgeKind::Gitlab)];
+        let pr = parse_pr_url_for_hosts("https://git.example.com/a/b/-/merge_requests/3", &hosts)
+            .unwrap();
+        assert_eq!(pr.host, "git.example.com");
+        assert_eq!(pr.owner, "a");
+        assert_eq!(pr.repo, "b");
+
+        // GitHub-style paths are rejected on GitLab hosts
+        let err = parse_pr_url_for_hosts("https://git.example.com/a/b/pull/3", &hosts).unwrap_err();
+        assert!(err.to_string().contains("merge_requests"));
+    }
+
+    #[test]
+    fn test_parse_pr_url_gitea() {
+        let hosts = vec![("code.example.com".to_string(), ForgeKind::Gitea)];
+        let pr = parse_pr_url_for_hosts("https://code.example.com/tools/ci/pulls/12/files", &hosts)
+            .unwrap();
+        assert_eq!(pr.host, "code.example.com");
+        assert_eq!(pr.owner, "tools");
+        assert_eq!(pr.repo, "ci");
+        assert_eq!(pr.number, 12);
+
+        let err = parse_pr_url_for_hosts("https://code.example.com/tools/ci/pull/12", &hosts)
+            .unwrap_err();
+        assert!(err.to_string().contains("/pulls/123"));
+    }
+
+    #[test]
+    fn test_parse_pr_url_sets_default_host() {
+        let pr = parse_pr_url("https://github.com/owner/repo/pull/1").unwrap();
+        assert_eq!(pr.host, DEFAULT_HOST);
     }
 
     #[test]
@@ -979,6 +1525,8 @@ mod tests {
             },
             path: path.to_string(),
             line,
+            side: DiffSide::Right,
+            start_line: None,
             created_at: format!("2024-01-15T10:{:02}:00Z", id % 60),
             in_reply_to_id: in_reply_to,
             commit_id: Some("abc123".to_string()),
@@ -1129,6 +1677,15 @@ mod tests {
         assert_eq!(threads[0].line, Some(42));
     }
 
+    #[test]
+    fn test_group_preserves_side() {
+        let mut comment = create_review_comment(1, "Why remove?", "src/main.rs", Some(7), None);
+        comment.side = DiffSide::Left;
+        let threads = group_review_comments_into_threads(vec![comment]);
+
+        assert_eq!(threads[0].side, DiffSide::Left);
+    }
+
     #[test]
     fn test_group_comment_with_line() {
         let comment = create_review_comment(1, "Comment", "src/main.rs", Some(50), None);
@@ -1236,4 +1793,58 @@ mod tests {
         assert_eq!(threads.len(), 1);
         assert_eq!(threads[0].comment_count(), 4);
     }
+
+    #[test]
+    fn test_parse_paginated_json() {
+        // gh api --paginate prints each page's array back to back
+        let pages: Vec<u32> = parse_paginated_json("[1,2]\n[3][]").unwrap();
+        assert_eq!(pages, vec![1, 2, 3]);
+
+        let empty: Vec<u32> = parse_paginated_json("").unwrap();
+        assert!(empty.is_empty());
+
+        assert!(parse_paginated_json::<u32>("[1,").is_err());
+    }
+
+    #[test]
+    fn test_review_request_lists() {
+        let pr = |number: u32| -> ReviewPr {
+            serde_json::from_value(serde_json::json!({
+                "number": number, "title": "PR", "repo_owner": "org", "repo_name": "api",
+                "author": "bob", "created_at": "2024-01-01T00:00:00Z", "head_sha": null, "body": null
+            }))
+            .unwrap()
+        };
+        let numbers = |prs: &[ReviewPr]| prs.iter().map(|pr| pr.number).collect::<Vec<_>>();
+
+        // #2 is requested from the user and a team, #3 only from a team, and #4 is found by a
+        // team search but isn't requested from the user any more
+        let lists = review_request_lists(
+            vec![pr(1), pr(2), pr(3)],
+            Some(vec![pr(1), pr(2)]),
+            vec![("org/backend".to_string(), vec![pr(2), pr(3), pr(4)])],
+        );
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[0].0, ReviewReason::Direct);
+        assert_eq!(numbers(&lists[0].1), vec![1, 2]);
+        assert_eq!(lists[1].0, ReviewReason::Team("org/backend".to_string()));
+        assert_eq!(numbers(&lists[1].1), vec![2, 3]);
+
+        // A PR whose team wasn't searched gets no tag
+        let lists = review_request_lists(vec![pr(1), pr(3)], Some(vec![pr(1)]), Vec::new());
+        assert_eq!(numbers(&lists[0].1), vec![1]);
+
+        // Without the direct search, requests no team search found are direct
+        let lists = review_request_lists(
+            vec![pr(1), pr(2)],
+            None,
+            vec![("org/backend".to_string(), vec![pr(2)])],
+        );
+        assert_eq!(numbers(&lists[0].1), vec![1]);
+        assert_eq!(numbers(&lists[1].1), vec![2]);
+
+        let lists = review_request_lists(vec![pr(1), pr(2)], None, Vec::new());
+        assert_eq!(lists.len(), 1);
+        assert_eq!(numbers(&lists[0].1), vec![1, 2]);
+    }
 }
@@ -0,0 +1,311 @@
+//! Local review mode: diffs from the current git repository, with notes kept on disk.
+
+use anyhow::{Context, Result, anyhow};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::process::Command;
+
+use crate::github::perf_log;
+use crate::types::PendingComment;
+
+/// A review that lives only on this machine (no forge to submit to)
+#[derive(Debug, Clone)]
+pub struct LocalReview {
+    /// Shown in the UI and in exported notes, e.g. "kensa main..feature"
+    pub title: String,
+    /// Stable key used to store notes, safe for use in a file name
+    pub key: String,
+}
+
+impl LocalReview {
+    /// Review of `git diff <range>` (or the working tree when no range is given)
+    pub fn for_range(repo_root: &Path, range: Option<&str>) -> Self {
+        let repo_name = repo_root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "repo".to_string());
+        let target = range.unwrap_or("working tree");
+
+        Self {
+            title: format!("{} {}", repo_name, target),
+            // The root path hash keeps same-named checkouts apart
+            key: format!(
+                "{}_{:016x}_{}",
+                sanitize(&repo_name),
+                fnv1a(repo_root.to_string_lossy().as_bytes()),
+                sanitize(target)
+            ),
+        }
+    }
+
+    /// Review of a patch file (or stdin); notes are keyed by the patch content
+    pub fn for_patch(name: &str, content: &str) -> Self {
+        Self {
+            title: name.to_string(),
+            key: format!("patch_{:016x}", fnv1a(content.as_bytes())),
+        }
+    }
+}
+
+/// Read a unified diff from a file, or from stdin when the source is "-"
+/// Returns the review and the diff with git headers added where missing
+pub fn read_patch(source: &str) -> Result<(LocalReview, String)> {
+    let (name, content) = if source == "-" {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+            .context("Failed to read diff from stdin")?;
+        ("stdin".to_string(), content)
+    } else {
+        let content = std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read patch file: {}", source))?;
+        let name = Path::new(source)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| source.to_string());
+        (name, content)
+    };
+
+    Ok((
+        LocalReview::for_patch(&name, &content),
+        with_git_headers(&content),
+    ))
+}
+
+/// Add `diff --git` headers to plain `diff -u` output so the parser can find each file
+/// Diffs that already have git headers (git diff, format-patch emails) are returned as is
+pub fn with_git_headers(diff: &str) -> String {
+    if diff.lines().any(|l| l.starts_with("diff --git ")) {
+        return diff.to_string();
+    }
+
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut out = String::with_capacity(diff.len());
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("--- ")
+            && let Some(next) = lines.get(i + 1)
+            && next.starts_with("+++ ")
+        {
+            let old = plain_diff_path(&line[4..], "a/");
+            let new = plain_diff_path(&next[4..], "b/");
+            let path = if new == "/dev/null" { &old } else { &new };
+
+            out.push_str(&format!("diff --git a/{} b/{}\n", path, path));
+            if old == "/dev/null" {
+                out.push_str("new file mode 100644\n");
+            } else if new == "/dev/null" {
+                out.push_str("deleted file mode 100644\n");
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Path from a `---`/`+++` line: drop the timestamp and the a/ or b/ prefix
+fn plain_diff_path(raw: &str, prefix: &str) -> String {
+    let path = raw.split('\t').next().unwrap_or(raw).trim_end();
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
+
+/// Run `git diff` in the current repository
+/// Returns the review and the raw unified diff
+pub async fn diff_range(range: Option<&str>) -> Result<(LocalReview, String)> {
+    let start = Instant::now();
+    let root_output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .await
+        .context("Failed to run 'git'. Is it installed?")?;
+
+    if !root_output.status.success() {
+        return Err(anyhow!(
+            "Not inside a git repository. Run kensa --local from a repository checkout"
+        ));
+    }
+    let repo_root = PathBuf::from(String::from_utf8_lossy(&root_output.stdout).trim());
+
+    let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+    if let Some(range) = range {
+        args.push(range);
+    }
+    // Everything before "--" is a revision, never a path
+    args.push("--");
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(&repo_root)
+        .output()
+        .await
+        .context("Failed to run git diff")?;
+
+    perf_log(
+        &format!("git diff {}", range.unwrap_or("")),
+        start.elapsed().as_millis(),
+    );
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git diff failed: {}", stderr.trim()));
+    }
+
+    Ok((
+        LocalReview::for_range(&repo_root, range),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+    ))
+}
+
+/// Render notes as Markdown, one section per note
+pub fn export_markdown(title: &str, comments: &[PendingComment]) -> String {
+    let mut out = format!("# Review notes: {}\n", title);
+
+    for comment in comments {
+        let heading = match (&comment.file_path, comment.line_number, comment.start_line) {
+            (Some(path), Some(end), Some(start)) if start != end => {
+                format!("{}:{}-{}", path, start, end)
+            }
+            (Some(path), Some(line), _) => format!("{}:{}", path, line),
+            (Some(path), None, _) => path.clone(),
+            (None, _, _) => "General".to_string(),
+        };
+        out.push_str(&format!(
+            "\n## {}\n\n{}\n",
+            heading,
+            comment.body.trim_end()
+        ));
+    }
+
+    out
+}
+
+/// Write notes as Markdown to ~/.config/kensa/notes/<key>.md
+pub fn export_notes(review: &LocalReview, comments: &[PendingComment]) -> Result<PathBuf, String> {
+    let dir = dirs::config_dir()
+        .map(|p| p.join("kensa").join("notes"))
+        .ok_or("Could not determine config directory")?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let path = dir.join(format!("{}.md", review.key));
+    std::fs::write(&path, export_markdown(&review.title, comments))
+        .map_err(|e| format!("Failed to write notes: {}", e))?;
+    Ok(path)
+}
+
+/// Keep only characters that are safe in a file name
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 64-bit FNV-1a hash (stable across builds, unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_range_title_and_key() {
+        let review =
+            LocalReview::for_range(Path::new("/home/me/src/kensa"), Some("main..feature/x"));
+        assert_eq!(review.title, "kensa main..feature/x");
+        assert!(review.key.starts_with("kensa_"));
+        assert!(review.key.ends_with("_main..feature_x"));
+        assert!(!review.key.contains('/'));
+
+        let working = LocalReview::for_range(Path::new("/home/me/src/kensa"), None);
+        assert_eq!(working.title, "kensa working tree");
+    }
+
+    #[test]
+    fn test_for_range_key_depends_on_repo_path() {
+        let a = LocalReview::for_range(Path::new("/a/kensa"), Some("HEAD~1"));
+        let b = LocalReview::for_range(Path::new("/b/kensa"), Some("HEAD~1"));
+        assert_ne!(a.key, b.key);
+        assert_eq!(
+            a.key,
+            LocalReview::for_range(Path::new("/a/kensa"), Some("HEAD~1")).key
+        );
+    }
+
+    #[test]
+    fn test_for_patch_key_is_content_hash() {
+        let a = LocalReview::for_patch("fix.diff", "diff --git a/x b/x\n");
+        let b = LocalReview::for_patch("other-name.diff", "diff --git a/x b/x\n");
+        let c = LocalReview::for_patch("fix.diff", "diff --git a/y b/y\n");
+        assert_eq!(a.title, "fix.diff");
+        assert_eq!(a.key, b.key);
+        assert_ne!(a.key, c.key);
+        assert!(a.key.starts_with("patch_"));
+    }
+
+    #[test]
+    fn test_with_git_headers_keeps_git_diffs() {
+        let diff = "From abc Mon Sep 17 00:00:00 2001\nSubject: fix\n\ndiff --git a/x b/x\n--- a/x\n+++ b/x\n";
+        assert_eq!(with_git_headers(diff), diff);
+    }
+
+    #[test]
+    fn test_with_git_headers_plain_diff() {
+        let diff = "\
+--- a/src/lib.rs\t2024-01-01 00:00:00
++++ b/src/lib.rs\t2024-01-02 00:00:00
+@@ -1 +1 @@
+-old
++new
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1 @@
++hello
+";
+        let files = crate::parser::parse_diff(&with_git_headers(diff));
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 1);
+        assert_eq!(files[1].path, "notes.txt");
+        assert_eq!(files[1].status, crate::types::FileStatus::Added);
+    }
+
+    #[test]
+    fn test_fnv1a_known_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_export_markdown() {
+        let comments = vec![
+            PendingComment::new_inline("Rename this".to_string(), "src/a.rs".to_string(), 4),
+            PendingComment::new_multiline(
+                "Extract a helper".to_string(),
+                "src/b.rs".to_string(),
+                10,
+                14,
+            ),
+            PendingComment::new_general("Overall fine\n".to_string()),
+        ];
+
+        let markdown = export_markdown("kensa main..dev", &comments);
+        assert_eq!(
+            markdown,
+            "# Review notes: kensa main..dev\n\
+             \n## src/a.rs:4\n\nRename this\n\
+             \n## src/b.rs:10-14\n\nExtract a helper\n\
+             \n## General\n\nOverall fine\n"
+        );
+    }
+}
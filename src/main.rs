@@ -1,7 +1,10 @@
 mod cache;
 mod config;
+mod difftool;
 mod drafts;
+mod forge;
 mod github;
+mod local;
 mod parser;
 mod syntax;
 mod types;
@@ -10,11 +13,14 @@ mod update;
 
 use anyhow::Result;
 use clap::Parser;
+use std::io::{IsTerminal, Read};
 use std::time::Instant;
 
 use crate::config::Config;
-use crate::github::{check_gh_cli, fetch_my_prs, fetch_pr_details, fetch_pr_diff, fetch_prs_by_author, fetch_review_prs, parse_pr_url};
+use crate::github::parse_pr_url;
+use crate::local::LocalReview;
 use crate::parser::parse_diff;
+use crate::types::{DiffFile, PrList};
 use crate::ui::App;
 use crate::update::check_for_update;
 
@@ -42,6 +48,7 @@ fn perf_log_start() {
 
 /// Log performance timing to file if KENSA_DEBUG is set
 #[inline]
+#[allow(clippy::collapsible_if)]
 fn perf_log(operation: &str, elapsed_ms: u128) {
     if std::env::var("KENSA_DEBUG").is_ok() {
         use std::io::Write;
@@ -70,7 +77,21 @@ const LOGO: &str = r#"
 EXAMPLES:
     kensa                                         List PRs awaiting your review
     kensa https://github.com/owner/repo/pull/123  Open a specific PR
+    kensa https://ghe.example.com/org/repo/pull/7 Open a PR on a configured [[hosts]] entry
+    kensa https://gitlab.com/group/project/-/merge_requests/42
+                                                  Open a GitLab merge request
+    kensa https://code.example.com/org/repo/pulls/7
+                                                  Open a Gitea/Forgejo PR ([[hosts]] kind = \"gitea\")
     kensa --user <username>                       List PRs by a GitHub user
+    kensa --local main..feature                   Review a local git range (notes stay local)
+    kensa --local                                 Review uncommitted changes in the working tree
+    kensa --local main..feature --export-notes    Print saved notes as Markdown
+    kensa --patch fix.diff                        Review a unified diff from a file
+    some-cmd | kensa -                            Review a unified diff from stdin
+    git config core.pager 'kensa --pager'         Open git diff/show/log -p in kensa
+    git config diff.external 'kensa --difftool'   Same, via git's external diff hook
+    git config difftool.kensa.cmd 'kensa --difftool \"$LOCAL\" \"$REMOTE\" \"$MERGED\"'
+                                                  Use with git difftool -t kensa
     kensa --upgrade                               Check for updates
     kensa --init-config                           Generate default config file
     kensa --edit-config                           Open config in editor
@@ -102,17 +123,40 @@ KEY BINDINGS:
         Esc         Cancel
 
 REQUIREMENTS:
-    GitHub CLI (gh) must be installed and authenticated.
+    A GitHub token (GH_TOKEN or github.token in config.toml), or the
+    GitHub CLI (gh) installed and authenticated.
     Install: https://cli.github.com/
+    GitLab merge requests need a token (GITLAB_TOKEN or [[hosts]] token);
+    Gitea/Forgejo PRs likewise (GITEA_TOKEN or [[hosts]] token).
 ")]
 struct Args {
-    /// GitHub PR URL (e.g., https://github.com/owner/repo/pull/123)
+    /// PR or merge request URL (github.com, gitlab.com or a host listed in config.toml)
     pr_url: Option<String>,
 
     /// Show PRs by a specific GitHub user
     #[arg(long, short)]
     user: Option<String>,
 
+    /// Review a local git range (e.g. main..feature); without a range, the working tree
+    #[arg(long, value_name = "RANGE", num_args = 0..=1, default_missing_value = "")]
+    local: Option<String>,
+
+    /// Review a unified diff from a file ("-" reads stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "local")]
+    patch: Option<String>,
+
+    /// Print the notes saved for --local or --patch as Markdown and exit
+    #[arg(long)]
+    export_notes: bool,
+
+    /// Act as a git pager: diffs on stdin open in the viewer, other text is paged
+    #[arg(long)]
+    pager: bool,
+
+    /// Act as a git external diff or difftool (takes the arguments git passes)
+    #[arg(long, value_name = "ARGS", num_args = 1..=9, allow_hyphen_values = true)]
+    difftool: Option<Vec<String>>,
+
     /// Check for updates and exit
     #[arg(long)]
     upgrade: bool,
@@ -131,6 +175,7 @@ struct Args {
 }
 
 #[tokio::main]
+#[allow(clippy::collapsible_if)]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
@@ -241,6 +286,47 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Git integration runs quietly: git may call us once per file or for non-diff output
+    if args.pager {
+        // Decide from the start of the input, so long non-diff output (git log) streams into less
+        let mut stdin = std::io::stdin().lock();
+        let mut input = difftool::read_prefix(&mut stdin, difftool::PAGER_SNIFF_BYTES)?;
+        let looks_like_diff = std::io::stdout().is_terminal() && {
+            let prefix = difftool::strip_ansi(&String::from_utf8_lossy(&input));
+            !parse_diff(&local::with_git_headers(&prefix)).is_empty()
+        };
+        if !looks_like_diff {
+            return difftool::page(&input, &mut stdin);
+        }
+
+        stdin.read_to_end(&mut input)?;
+        let input = String::from_utf8_lossy(&input);
+        let plain = difftool::strip_ansi(&input);
+        let files = parse_diff(&local::with_git_headers(&plain));
+        if files.is_empty() {
+            return difftool::page(input.as_bytes(), &mut std::io::empty());
+        }
+        return run_local_review(LocalReview::for_patch("git pager", &plain), files);
+    }
+
+    if let Some(tool_args) = &args.difftool {
+        let file_diff = difftool::external_diff(tool_args).await?;
+        // Piped (e.g. into a pager): hand the labelled diff on instead of opening the viewer
+        if !std::io::stdout().is_terminal() {
+            print!("{}", file_diff);
+            return Ok(());
+        }
+        let Some(session) = difftool::collect(&file_diff)? else {
+            return Ok(());
+        };
+        let files = parse_diff(&session);
+        if files.is_empty() {
+            eprintln!("No changes");
+            return Ok(());
+        }
+        return run_local_review(LocalReview::for_patch("git difftool", &session), files);
+    }
+
     // Show logo
     eprintln!("{}", LOGO);
 
@@ -253,19 +339,57 @@ async fn main() -> Result<()> {
     }
 
     let startup_start = Instant::now();
+    let hosts = forge::forge_hosts();
+
+    // "kensa -" reads the diff from stdin, same as "--patch -"
+    let patch = args.patch.or_else(|| args.pr_url.clone().filter(|url| url == "-"));
+    let local_source = if let Some(source) = &patch {
+        Some(local::read_patch(source)?)
+    } else if let Some(range) = &args.local {
+        let range = (!range.is_empty()).then_some(range.as_str());
+        Some(local::diff_range(range).await?)
+    } else {
+        None
+    };
 
-    if let Some(username) = args.user {
+    if args.export_notes && local_source.is_none() {
+        anyhow::bail!("--export-notes needs --local or --patch");
+    }
+
+    if let Some((review, diff_content)) = local_source {
+        // Local mode - review a git range or patch, comments are kept as local notes
+        if args.export_notes {
+            let notes = drafts::load_local_notes(&review.key);
+            print!("{}", local::export_markdown(&review.title, &notes));
+            return Ok(());
+        }
+
+        let parse_start = Instant::now();
+        let files = parse_diff(&diff_content);
+        perf_log("parse_diff", parse_start.elapsed().as_millis());
+
+        if files.is_empty() {
+            eprintln!("No changes in {}", review.title);
+            return Ok(());
+        }
+
+        perf_log("startup (total)", startup_start.elapsed().as_millis());
+        eprintln!("Found {} files. Starting viewer...", files.len());
+
+        run_local_review(review, files)?;
+    } else if let Some(username) = args.user {
         // User mode - show PRs by that user
         eprintln!("Fetching PRs by @{}...", username);
+        forge::init_backends(&hosts).await;
 
         // Run auth check in parallel with fetch
         let fetch_start = Instant::now();
         let (auth_result, prs_result) = tokio::join!(
-            check_gh_cli(),
-            fetch_prs_by_author(&username)
+            forge::check_auth_any(&hosts),
+            forge::fetch_prs_by_author_all(&hosts, &username)
         );
         auth_result?;
-        let prs = prs_result?;
+        let PrList { prs, truncated, host_errors } = prs_result?;
         perf_log("fetch_prs_by_author", fetch_start.elapsed().as_millis());
 
         if prs.is_empty() {
@@ -281,6 +405,8 @@ async fn main() -> Result<()> {
         );
 
         let mut app = App::new_with_author_prs(username, prs);
+        app.set_truncated(false, truncated);
+        app.set_host_errors(host_errors);
         app.run()?;
     } else if let Some(url) = args.pr_url {
         // Direct PR URL mode
@@ -290,18 +416,25 @@ async fn main() -> Result<()> {
             pr_info.number, pr_info.owner, pr_info.repo
         );
 
+        forge::init_backends(std::slice::from_ref(&pr_info.host)).await;
+        let forge = forge::backend_for(&pr_info.host);
+        perf_log(
+            &format!("select backend ({} for {})", forge.name(), pr_info.host),
+            startup_start.elapsed().as_millis(),
+        );
+
         // Fetch auth, diff and PR details concurrently
         let fetch_start = Instant::now();
-        let (auth_result, diff_result, details_result) = tokio::join!(
-            check_gh_cli(),
-            fetch_pr_diff(&pr_info),
-            fetch_pr_details(&pr_info)
+        let (auth_result, diff_result, state_result) = tokio::join!(
+            forge.check_auth(),
+            forge.fetch_pr_diff(&pr_info),
+            forge.fetch_review_state(&pr_info)
         );
         auth_result?;
-        perf_log("fetch PR (diff + details)", fetch_start.elapsed().as_millis());
+        perf_log("fetch PR (diff + review state)", fetch_start.elapsed().as_millis());
 
         let diff_content = diff_result?;
-        let pr = details_result?;
+        let state = state_result?;
 
         let parse_start = Instant::now();
         let files = parse_diff(&diff_content);
@@ -315,10 +448,12 @@ async fn main() -> Result<()> {
         perf_log("startup (total)", startup_start.elapsed().as_millis());
         eprintln!("Found {} files. Starting viewer...", files.len());
 
-        let mut app = App::new_with_pr(files, pr);
+        let mut app = App::new_with_pr(files, state);
         app.run()?;
     } else {
         // PR list mode - show PRs awaiting review and my PRs
+        forge::init_backends(&hosts).await;
+
         // Try to load from cache for instant startup
         if let Some(cached) = cache::load_cache() {
             perf_log("loaded from cache", startup_start.elapsed().as_millis());
@@ -332,6 +467,8 @@ async fn main() -> Result<()> {
 
             // Start app with cached data, it will refresh in background
             let mut app = App::new_with_prs(cached.review_prs, cached.my_prs);
+            app.set_cached_searches(&cached.searches);
+            app.set_inbox(Ok(PrList { prs: cached.inbox, ..Default::default() }));
             app.set_cache_age(cache_age);
             app.trigger_background_refresh();
             app.run()?;
@@ -340,22 +477,37 @@ async fn main() -> Result<()> {
             eprintln!("Fetching PRs...");
 
             let fetch_start = Instant::now();
-            let (auth_result, review_prs, my_prs) = tokio::join!(
-                check_gh_cli(),
-                fetch_review_prs(),
-                fetch_my_prs()
+            let queries: Vec<String> = Config::load().tabs.into_iter().map(|tab| tab.query).collect();
+            let (auth_result, review_prs, my_prs, searches, inbox) = tokio::join!(
+                forge::check_auth_any(&hosts),
+                forge::fetch_review_prs_all(&hosts),
+                forge::fetch_my_prs_all(&hosts),
+                forge::fetch_search_prs_all(&hosts, &queries),
+                forge::fetch_notifications_all(&hosts)
             );
             auth_result?;
             perf_log("fetch all PRs (parallel)", fetch_start.elapsed().as_millis());
 
-            let review_prs = review_prs?;
-            let my_prs = my_prs?;
+            let (review_prs, my_prs) = (review_prs?, my_prs?);
+            let host_errors = PrList::host_errors_of(
+                [&review_prs, &my_prs]
+                    .into_iter()
+                    .chain(searches.iter().flatten())
+                    .chain(inbox.as_ref().ok()),
+            );
+            let PrList { prs: review_prs, truncated: review_truncated, .. } = review_prs;
+            let PrList { prs: my_prs, truncated: my_truncated, .. } = my_prs;
 
             // Save to cache for next time
-            cache::save_cache(&review_prs, &my_prs);
+            let inbox_prs = inbox.as_ref().map(|list| list.prs.as_slice()).unwrap_or_default();
+            cache::save_cache(&review_prs, &my_prs, cache::search_results(&queries, &searches), inbox_prs);
 
-            let total = review_prs.len() + my_prs.len();
+            let search_count: usize = searches.iter().flatten().map(|list| list.prs.len()).sum();
+            let total = review_prs.len() + my_prs.len() + search_count + inbox_prs.len();
             if total == 0 {
+                for error in &host_errors {
+                    eprintln!("Failed to fetch PRs from {}", error);
+                }
                 eprintln!("No open PRs found.");
                 return Ok(());
             }
@@ -368,9 +520,19 @@ async fn main() -> Result<()> {
             );
 
             let mut app = App::new_with_prs(review_prs, my_prs);
+            app.set_truncated(review_truncated, my_truncated);
+            app.set_search_results(searches.into_iter().map(|r| r.map_err(|e| e.to_string())).collect());
+            app.set_inbox(inbox.map_err(|e| e.to_string()));
+            app.set_host_errors(host_errors);
             app.run()?;
         }
     }
 
     Ok(())
 }
+
+/// Open the diff viewer for a review whose comments are kept as local notes
+fn run_local_review(review: LocalReview, files: Vec<DiffFile>) -> Result<()> {
+    let mut app = App::new_with_local(files, review);
+    app.run()
+}
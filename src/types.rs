@@ -66,9 +66,20 @@ impl DiffFile {
     }
 }
 
+/// Host used when none is configured or recorded
+pub const DEFAULT_HOST: &str = "github.com";
+
+/// Public GitLab host, recognized without configuration
+pub const GITLAB_HOST: &str = "gitlab.com";
+
+fn default_host() -> String {
+    DEFAULT_HOST.to_string()
+}
+
 /// Parsed PR information from URL
 #[derive(Debug, Clone)]
 pub struct PrInfo {
+    pub host: String,
     pub owner: String,
     pub repo: String,
     pub number: u32,
@@ -77,6 +88,8 @@ pub struct PrInfo {
 /// A PR awaiting review
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ReviewPr {
+    #[serde(default = "default_host")]
+    pub host: String,
     pub number: u32,
     pub title: String,
     pub repo_owner: String,
@@ -85,15 +98,600 @@ pub struct ReviewPr {
     pub created_at: String,
     pub head_sha: Option<String>,  // For inline comments
     pub body: Option<String>,      // PR description (fetched on demand)
+    // List columns; missing from caches written before they existed
+    #[serde(default)]
+    pub is_draft: bool,
+    #[serde(default)]
+    pub review_decision: Option<ReviewDecision>,
+    /// Combined state of the head commit's checks
+    #[serde(default)]
+    pub ci_state: Option<CheckState>,
+    #[serde(default)]
+    pub size: Option<PrSize>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Why the PR is in the "For Review" list (empty in other lists)
+    #[serde(default)]
+    pub review_reasons: Vec<ReviewReason>,
+    /// The notification the PR is listed for in the inbox
+    #[serde(default)]
+    pub notification: Option<PrNotification>,
+}
+
+/// A notification thread about a PR (GitHub)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PrNotification {
+    pub thread_id: String,
+    /// Why the user was notified, e.g. "review_requested", "mention", "comment"
+    pub reason: String,
+    pub unread: bool,
+}
+
+impl PrNotification {
+    /// Short tag shown in the inbox
+    pub fn label(&self) -> String {
+        match self.reason.as_str() {
+            "review_requested" => "review".to_string(),
+            "ci_activity" => "ci".to_string(),
+            reason => reason.replace('_', " "),
+        }
+    }
+}
+
+/// Why a PR is awaiting the user's review
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewReason {
+    /// Review requested from the user
+    Direct,
+    /// Review requested from one of the user's teams, as "org/team"
+    Team(String),
+    /// The user reviewed it before
+    Reviewed,
+}
+
+impl ReviewReason {
+    /// Short tag shown in the PR list
+    pub fn label(&self) -> String {
+        match self {
+            ReviewReason::Direct => "direct".to_string(),
+            ReviewReason::Team(team) => format!("@{}", team.rsplit('/').next().unwrap_or(team)),
+            ReviewReason::Reviewed => "reviewed".to_string(),
+        }
+    }
+}
+
+/// Whether a PR has the reviews branch protection asks for (GitHub)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+}
+
+impl ReviewDecision {
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewDecision::Approved => "approved",
+            ReviewDecision::ChangesRequested => "changes",
+            ReviewDecision::ReviewRequired => "review",
+        }
+    }
+}
+
+/// Lines added and removed, and files changed, across a whole PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PrSize {
+    pub additions: u32,
+    pub deletions: u32,
+    pub changed_files: u32,
+}
+
+/// PRs returned by a list query
+#[derive(Debug, Clone, Default)]
+pub struct PrList {
+    pub prs: Vec<ReviewPr>,
+    /// More PRs matched than the configured cap allowed
+    pub truncated: bool,
+    /// Hosts that failed while others answered, as "host: error"
+    pub host_errors: Vec<String>,
+}
+
+impl PrList {
+    /// Failed hosts reported by any of the lists, each listed once
+    pub fn host_errors_of<'a>(lists: impl IntoIterator<Item = &'a PrList>) -> Vec<String> {
+        let mut errors: Vec<String> = Vec::new();
+        for error in lists.into_iter().flat_map(|list| &list.host_errors) {
+            if !errors.contains(error) {
+                errors.push(error.clone());
+            }
+        }
+        errors
+    }
+}
+
+/// Everything needed to review a PR apart from its diff
+#[derive(Debug, Clone)]
+pub struct ReviewState {
+    /// Full PR details including head SHA and body
+    pub pr: ReviewPr,
+    pub base_sha: Option<String>,
+    pub threads: Vec<CommentThread>,
+    pub reviews: Vec<PrReview>,
+    /// Per-file line counts as reported by the host (empty when not available)
+    pub file_stats: Vec<FileStat>,
+    /// The current user's unsubmitted review on the host, if any
+    pub pending_review: Option<PendingReview>,
+    /// Host-specific PR ID, for reacting to the PR (GitHub node ID)
+    pub pr_node_id: Option<String>,
+    /// Reactions on the PR itself
+    pub reactions: Vec<Reaction>,
+}
+
+/// A submitted (or pending) review on a PR
+#[derive(Debug, Clone)]
+pub struct PrReview {
+    pub author: String,
+    /// "APPROVED", "CHANGES_REQUESTED", "COMMENTED", "DISMISSED" or "PENDING"
+    pub state: String,
+    /// Written by the current user
+    pub by_viewer: bool,
+    /// Head commit the review was submitted on, when the host reports it
+    pub commit_id: Option<String>,
+}
+
+/// Lines added and removed in one file of a PR
+#[derive(Debug, Clone)]
+pub struct FileStat {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+/// A review the current user started but has not submitted (GitHub pending reviews)
+#[derive(Debug, Clone)]
+pub struct PendingReview {
+    /// Inline drafts on the review, with `remote_id` set
+    pub comments: Vec<PendingComment>,
+}
+
+/// Outcome of a CI check, folding GitHub check-run conclusions and commit status states together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CheckState {
+    Success,
+    Failure,
+    /// Queued or still running
+    Pending,
+    /// Finished without a verdict (neutral, skipped, stale)
+    Neutral,
+}
+
+impl CheckState {
+    pub fn icon(self) -> &'static str {
+        match self {
+            CheckState::Success => "✓",
+            CheckState::Failure => "✗",
+            CheckState::Pending => "●",
+            CheckState::Neutral => "-",
+        }
+    }
+}
+
+/// A check run or commit status on a PR's head commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckRun {
+    pub name: String,
+    pub state: CheckState,
+    /// Required by branch protection for the PR to merge
+    pub required: bool,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub url: Option<String>,
+    /// Messages the check left on lines of the PR's files
+    pub annotations: Vec<CheckAnnotation>,
+}
+
+/// Severity of a check annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Failure,
+}
+
+/// A message a check left on lines of a file (a lint error, a failing test)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckAnnotation {
+    pub path: String,
+    /// Lines in the new version of the file
+    pub start_line: u32,
+    pub end_line: u32,
+    pub level: AnnotationLevel,
+    pub title: Option<String>,
+    pub message: String,
+}
+
+impl CheckRun {
+    /// How long the check ran (None while it is running or when the host didn't say)
+    pub fn duration(&self) -> Option<chrono::TimeDelta> {
+        let parse = |time: &Option<String>| chrono::DateTime::parse_from_rfc3339(time.as_deref()?).ok();
+        let elapsed = parse(&self.completed_at)? - parse(&self.started_at)?;
+        (elapsed >= chrono::TimeDelta::zero()).then_some(elapsed)
+    }
+
+    /// A required check that failed or hasn't finished, which blocks merging
+    pub fn is_blocking(&self) -> bool {
+        self.required && matches!(self.state, CheckState::Failure | CheckState::Pending)
+    }
+}
+
+/// How a PR's commits land on the base branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub const ALL: [MergeMethod; 3] = [MergeMethod::Merge, MergeMethod::Squash, MergeMethod::Rebase];
+
+    /// Name used by the GitHub GraphQL API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "MERGE",
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Rebase => "REBASE",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "Create a merge commit",
+            MergeMethod::Squash => "Squash and merge",
+            MergeMethod::Rebase => "Rebase and merge",
+        }
+    }
+
+    /// Rebasing keeps the PR's commits, so there is no commit message to write
+    pub fn has_commit_message(self) -> bool {
+        self != MergeMethod::Rebase
+    }
+}
+
+/// Whether a PR can be merged, as reported by the host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStatus {
+    /// Mergeable, with all requirements met
+    Clean,
+    /// Mergeable, but non-required checks failed
+    Unstable,
+    /// Conflicts with the base branch
+    Conflicting,
+    /// Requirements (reviews, required checks) not met yet
+    Blocked,
+    /// The head branch must be updated with the base branch first
+    Behind,
+    Draft,
+    /// Still being computed by the host
+    Unknown,
+}
+
+impl MergeStatus {
+    /// Whether merging right away is possible
+    pub fn can_merge(self) -> bool {
+        matches!(self, MergeStatus::Clean | MergeStatus::Unstable)
+    }
+
+    /// Whether auto-merge can be enabled to merge once requirements are met
+    pub fn can_auto_merge(self) -> bool {
+        matches!(self, MergeStatus::Blocked | MergeStatus::Behind | MergeStatus::Unstable | MergeStatus::Unknown)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            MergeStatus::Clean => "Ready to merge",
+            MergeStatus::Unstable => "Mergeable, but some checks failed",
+            MergeStatus::Conflicting => "Conflicts with the base branch",
+            MergeStatus::Blocked => "Blocked by required reviews or checks",
+            MergeStatus::Behind => "Head branch is behind the base branch",
+            MergeStatus::Draft => "Draft PRs can't be merged",
+            MergeStatus::Unknown => "Mergeability is still being checked",
+        }
+    }
+}
+
+/// Default commit title and message for one merge method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeMessage {
+    pub method: MergeMethod,
+    pub title: String,
+    pub body: String,
+}
+
+/// What is needed to offer merging a PR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeInfo {
+    /// Merge methods the repository allows, in `MergeMethod::ALL` order
+    pub methods: Vec<MergeMethod>,
+    pub status: MergeStatus,
+    /// Default commit messages, for the methods that have one
+    pub messages: Vec<MergeMessage>,
+    pub auto_merge_allowed: bool,
+    /// Auto-merge is already enabled for the PR
+    pub auto_merge_enabled: bool,
+    pub head_ref: String,
+    /// The user may delete the head branch
+    pub can_delete_branch: bool,
+    /// The repository deletes head branches after merging anyway
+    pub deletes_branch_on_merge: bool,
+}
+
+impl MergeInfo {
+    /// Default commit title and message for a method (empty for rebasing)
+    pub fn message(&self, method: MergeMethod) -> (String, String) {
+        self.messages
+            .iter()
+            .find(|m| m.method == method)
+            .map(|m| (m.title.clone(), m.body.clone()))
+            .unwrap_or_default()
+    }
+}
+
+/// A merge (or auto-merge) as chosen in the merge dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeRequest {
+    pub method: MergeMethod,
+    pub title: String,
+    pub body: String,
+    pub delete_branch: bool,
+    /// Enable auto-merge instead of merging now
+    pub auto_merge: bool,
+    /// Head commit that was reviewed; the merge fails if the branch moved since
+    pub head_sha: Option<String>,
+}
+
+/// The lists of a PR's metadata that can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataField {
+    Reviewers,
+    Assignees,
+    Labels,
+}
+
+impl MetadataField {
+    pub const ALL: [MetadataField; 3] = [MetadataField::Reviewers, MetadataField::Assignees, MetadataField::Labels];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MetadataField::Reviewers => "Reviewers",
+            MetadataField::Assignees => "Assignees",
+            MetadataField::Labels => "Labels",
+        }
+    }
+
+    /// Noun for a single entry, as in "Add reviewer"
+    pub fn entry_name(self) -> &'static str {
+        match self {
+            MetadataField::Reviewers => "reviewer",
+            MetadataField::Assignees => "assignee",
+            MetadataField::Labels => "label",
+        }
+    }
+}
+
+/// A requested reviewer, assignee or label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataEntry {
+    /// Node ID, used to apply changes
+    pub id: String,
+    /// Login, `org/team` slug or label name
+    pub name: String,
+    pub is_team: bool,
+}
+
+/// The requested reviewers, assignees and labels of a PR, with what the repository offers for each
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrMetadata {
+    /// Users and teams whose review is requested
+    pub reviewers: Vec<MetadataEntry>,
+    pub assignees: Vec<MetadataEntry>,
+    pub labels: Vec<MetadataEntry>,
+    /// Collaborators who can be assigned or asked for a review
+    pub users: Vec<MetadataEntry>,
+    /// Teams of the owning organization (empty when they can't be read)
+    pub teams: Vec<MetadataEntry>,
+    /// Labels defined in the repository
+    pub repo_labels: Vec<MetadataEntry>,
+}
+
+impl PrMetadata {
+    pub fn entries(&self, field: MetadataField) -> &Vec<MetadataEntry> {
+        match field {
+            MetadataField::Reviewers => &self.reviewers,
+            MetadataField::Assignees => &self.assignees,
+            MetadataField::Labels => &self.labels,
+        }
+    }
+
+    pub fn entries_mut(&mut self, field: MetadataField) -> &mut Vec<MetadataEntry> {
+        match field {
+            MetadataField::Reviewers => &mut self.reviewers,
+            MetadataField::Assignees => &mut self.assignees,
+            MetadataField::Labels => &mut self.labels,
+        }
+    }
+
+    /// Candidates for a field whose name contains `query` (case-insensitively), minus those
+    /// already on the PR and the `excluded` login (a PR's author can't review it)
+    /// Names starting with the query come first
+    pub fn completions(&self, field: MetadataField, query: &str, excluded: &str) -> Vec<&MetadataEntry> {
+        let candidates: Vec<&MetadataEntry> = match field {
+            MetadataField::Reviewers => self.users.iter().filter(|u| u.name != excluded).chain(&self.teams).collect(),
+            MetadataField::Assignees => self.users.iter().collect(),
+            MetadataField::Labels => self.repo_labels.iter().collect(),
+        };
+        let query = query.to_lowercase();
+        let current = self.entries(field);
+        let mut matches: Vec<&MetadataEntry> = candidates
+            .into_iter()
+            .filter(|c| c.name.to_lowercase().contains(&query) && !current.iter().any(|e| e.id == c.id))
+            .collect();
+        matches.sort_by_key(|c| !c.name.to_lowercase().starts_with(&query));
+        matches
+    }
+
+    /// Whether any of the PR's lists differ from `other`'s, ignoring order
+    pub fn differs_from(&self, other: &PrMetadata) -> bool {
+        MetadataField::ALL.into_iter().any(|field| {
+            let ids = |metadata: &PrMetadata| {
+                let mut ids: Vec<String> = metadata.entries(field).iter().map(|e| e.id.clone()).collect();
+                ids.sort();
+                ids
+            };
+            ids(self) != ids(other)
+        })
+    }
+}
+
+/// Side of a diff an inline comment is anchored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DiffSide {
+    /// The old version of the file (deleted lines, numbered by `old_ln`)
+    Left,
+    /// The new version of the file (added and context lines, numbered by `new_ln`)
+    #[default]
+    Right,
+}
+
+impl DiffSide {
+    /// Name used by the GitHub API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiffSide::Left => "LEFT",
+            DiffSide::Right => "RIGHT",
+        }
+    }
+}
+
+/// GitHub's reaction types, in the order GitHub shows them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionKind {
+    ThumbsUp,
+    ThumbsDown,
+    Laugh,
+    Hooray,
+    Confused,
+    Heart,
+    Rocket,
+    Eyes,
+}
+
+impl ReactionKind {
+    pub const ALL: [ReactionKind; 8] = [
+        ReactionKind::ThumbsUp,
+        ReactionKind::ThumbsDown,
+        ReactionKind::Laugh,
+        ReactionKind::Hooray,
+        ReactionKind::Confused,
+        ReactionKind::Heart,
+        ReactionKind::Rocket,
+        ReactionKind::Eyes,
+    ];
+
+    /// Name used by the GitHub GraphQL API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReactionKind::ThumbsUp => "THUMBS_UP",
+            ReactionKind::ThumbsDown => "THUMBS_DOWN",
+            ReactionKind::Laugh => "LAUGH",
+            ReactionKind::Hooray => "HOORAY",
+            ReactionKind::Confused => "CONFUSED",
+            ReactionKind::Heart => "HEART",
+            ReactionKind::Rocket => "ROCKET",
+            ReactionKind::Eyes => "EYES",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            ReactionKind::ThumbsUp => "👍",
+            ReactionKind::ThumbsDown => "👎",
+            ReactionKind::Laugh => "😄",
+            ReactionKind::Hooray => "🎉",
+            ReactionKind::Confused => "😕",
+            ReactionKind::Heart => "❤️",
+            ReactionKind::Rocket => "🚀",
+            ReactionKind::Eyes => "👀",
+        }
+    }
+}
+
+/// How many people left one kind of reaction on a comment or PR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reaction {
+    pub kind: ReactionKind,
+    pub count: u32,
+    /// Whether the authenticated user is one of them
+    pub by_viewer: bool,
+}
+
+impl Reaction {
+    /// Record the user adding or removing a reaction, keeping `ReactionKind::ALL` order
+    pub fn apply(reactions: &mut Vec<Reaction>, kind: ReactionKind, add: bool) {
+        match reactions.iter().position(|r| r.kind == kind) {
+            Some(i) if reactions[i].by_viewer != add => {
+                let reaction = &mut reactions[i];
+                reaction.by_viewer = add;
+                if add {
+                    reaction.count += 1;
+                } else {
+                    reaction.count = reaction.count.saturating_sub(1);
+                }
+                if reaction.count == 0 {
+                    reactions.remove(i);
+                }
+            }
+            Some(_) => {}
+            None if add => {
+                let at = reactions
+                    .iter()
+                    .position(|r| Self::rank(r.kind) > Self::rank(kind))
+                    .unwrap_or(reactions.len());
+                reactions.insert(at, Reaction { kind, count: 1, by_viewer: true });
+            }
+            None => {}
+        }
+    }
+
+    fn rank(kind: ReactionKind) -> usize {
+        ReactionKind::ALL.iter().position(|&k| k == kind).unwrap_or(0)
+    }
 }
 
 /// A pending comment to be submitted later
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PendingComment {
     pub body: String,
-    pub file_path: Option<String>,  // For inline comments
+    pub file_path: Option<String>,  // For inline and file-level comments
     pub line_number: Option<u32>,   // For inline comments (end line for multi-line)
     pub start_line: Option<u32>,    // For multi-line comments
+    #[serde(default)]
+    pub side: DiffSide,             // Side of `line_number` (drafts from older versions are RIGHT)
+    #[serde(default)]
+    pub start_side: DiffSide,       // Side of `start_line`
+    /// Id of the copy on the host's pending review, once synced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_id: Option<String>,
+    /// Text of that copy when last synced, to tell local edits from ones made on the host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced_body: Option<String>,
 }
 
 impl PendingComment {
@@ -103,6 +701,10 @@ impl PendingComment {
             file_path: None,
             line_number: None,
             start_line: None,
+            side: DiffSide::Right,
+            start_side: DiffSide::Right,
+            remote_id: None,
+            synced_body: None,
         }
     }
 
@@ -112,6 +714,10 @@ impl PendingComment {
             file_path: Some(file_path),
             line_number: Some(line_number),
             start_line: None,
+            side: DiffSide::Right,
+            start_side: DiffSide::Right,
+            remote_id: None,
+            synced_body: None,
         }
     }
 
@@ -121,23 +727,77 @@ impl PendingComment {
             file_path: Some(file_path),
             line_number: Some(end_line),
             start_line: Some(start_line),
+            side: DiffSide::Right,
+            start_side: DiffSide::Right,
+            remote_id: None,
+            synced_body: None,
         }
     }
 
+    /// A comment on a whole file rather than on some of its lines
+    pub fn new_file(body: String, file_path: String) -> Self {
+        Self {
+            body,
+            file_path: Some(file_path),
+            line_number: None,
+            start_line: None,
+            side: DiffSide::Right,
+            start_side: DiffSide::Right,
+            remote_id: None,
+            synced_body: None,
+        }
+    }
+
+    /// Anchor the (last) line to a side of the diff
+    pub fn with_side(mut self, side: DiffSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Anchor the first line of a multi-line comment to a side of the diff
+    pub fn with_start_side(mut self, start_side: DiffSide) -> Self {
+        self.start_side = start_side;
+        self
+    }
+
     pub fn is_inline(&self) -> bool {
         self.file_path.is_some() && self.line_number.is_some()
     }
+
+    pub fn is_file_level(&self) -> bool {
+        self.file_path.is_some() && self.line_number.is_none()
+    }
+
+    /// Inline and file-level comments are submitted on a review, general ones as PR comments
+    pub fn is_review_comment(&self) -> bool {
+        self.file_path.is_some()
+    }
+
+    /// Same text at the same place: file, lines and their sides (the remote id is ignored)
+    pub fn same_draft(&self, other: &PendingComment) -> bool {
+        self.body == other.body
+            && self.file_path == other.file_path
+            && self.line_number == other.line_number
+            && self.start_line == other.start_line
+            && self.side == other.side
+            && self.start_side == other.start_side
+    }
 }
 
 impl ReviewPr {
-    /// Full repository name (owner/repo)
+    /// Full repository name (owner/repo, prefixed with the host when not github.com)
     pub fn repo_full_name(&self) -> String {
-        format!("{}/{}", self.repo_owner, self.repo_name)
+        if self.host == DEFAULT_HOST {
+            format!("{}/{}", self.repo_owner, self.repo_name)
+        } else {
+            format!("{}/{}/{}", self.host, self.repo_owner, self.repo_name)
+        }
     }
 
     /// Convert to PrInfo for fetching diff
     pub fn to_pr_info(&self) -> PrInfo {
         PrInfo {
+            host: self.host.clone(),
             owner: self.repo_owner.clone(),
             repo: self.repo_name.clone(),
             number: self.number,
@@ -146,27 +806,37 @@ impl ReviewPr {
 
     /// Format the age of the PR (e.g., "2d", "3h", "5m")
     pub fn age(&self) -> String {
-        use std::time::SystemTime;
+        relative_age(&self.created_at)
+    }
 
-        // Parse ISO 8601 date
-        let created = chrono::DateTime::parse_from_rfc3339(&self.created_at)
-            .map(|dt| dt.timestamp())
-            .unwrap_or(0);
+    /// Time since the PR was last updated, in the format of `age`
+    pub fn updated_age(&self) -> Option<String> {
+        self.updated_at.as_deref().map(relative_age)
+    }
+}
 
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
+/// Time elapsed since an RFC 3339 timestamp (e.g., "2d", "3h", "5m")
+fn relative_age(timestamp: &str) -> String {
+    use std::time::SystemTime;
 
-        let diff_secs = now - created;
+    // Parse ISO 8601 date
+    let then = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|dt| dt.timestamp())
+        .unwrap_or(0);
 
-        if diff_secs < 3600 {
-            format!("{}m", diff_secs / 60)
-        } else if diff_secs < 86400 {
-            format!("{}h", diff_secs / 3600)
-        } else {
-            format!("{}d", diff_secs / 86400)
-        }
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    let diff_secs = now - then;
+
+    if diff_secs < 3600 {
+        format!("{}m", diff_secs / 60)
+    } else if diff_secs < 86400 {
+        format!("{}h", diff_secs / 3600)
+    } else {
+        format!("{}d", diff_secs / 86400)
     }
 }
 
@@ -183,9 +853,13 @@ pub struct CommentUser {
 /// A single comment in a thread (used for both review and issue comments)
 #[derive(Debug, Clone)]
 pub struct ThreadComment {
+    pub id: u64,                      // REST id, for editing and deleting (0 if unknown)
+    pub node_id: Option<String>,      // GraphQL id, for reacting (GitHub only)
     pub body: String,
     pub author: String,
     pub created_at: String,
+    pub by_viewer: bool,              // Written by the authenticated user (GitHub only)
+    pub reactions: Vec<Reaction>,
 }
 
 /// A review comment (inline on code) from GitHub API
@@ -196,6 +870,12 @@ pub struct ReviewComment {
     pub user: CommentUser,
     pub path: String,
     pub line: Option<u32>,
+    /// Side of `line` (LEFT for comments on removed lines)
+    #[serde(default)]
+    pub side: DiffSide,
+    /// First line of a multi-line comment
+    #[serde(default)]
+    pub start_line: Option<u32>,
     #[serde(rename = "created_at")]
     pub created_at: String,
     #[serde(default)]
@@ -232,10 +912,14 @@ pub struct IssueComment {
 #[derive(Debug, Clone)]
 pub struct CommentThread {
     pub id: u64,                      // ID of root comment
+    pub node_id: Option<String>,      // Host-specific thread ID (e.g. GitLab discussion ID)
     pub file_path: Option<String>,    // None for general PR comments
     pub line: Option<u32>,            // Line number for inline comments
+    pub side: DiffSide,               // Side of `line` (LEFT numbers the old version)
+    pub start_line: Option<u32>,      // First line of a multi-line comment
     pub comments: Vec<ThreadComment>, // All comments in thread (root + replies)
     pub outdated: bool,               // Whether this thread is on outdated code
+    pub resolved: bool,               // Whether the thread was marked resolved
 }
 
 impl CommentThread {
@@ -244,6 +928,12 @@ impl CommentThread {
         self.file_path.is_some()
     }
 
+    /// Check if this thread is on a whole file
+    /// Threads on lines that left the diff have no line either, but are outdated
+    pub fn is_file_level(&self) -> bool {
+        self.file_path.is_some() && self.line.is_none() && !self.outdated
+    }
+
     /// Get the total number of comments in thread
     pub fn comment_count(&self) -> usize {
         self.comments.len()
@@ -300,6 +990,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_file_status_clone() {
         let status = FileStatus::Added;
         let cloned = status.clone();
@@ -420,8 +1111,26 @@ mod tests {
             file_path: Some("file.rs".to_string()),
             line_number: None,
             start_line: None,
+            side: DiffSide::Right,
+            start_side: DiffSide::Right,
+            remote_id: None,
+            synced_body: None,
         };
         assert!(!comment.is_inline());
+        assert!(comment.is_file_level());
+    }
+
+    #[test]
+    fn test_pending_comment_file_level() {
+        let comment = PendingComment::new_file("Split this file".to_string(), "src/ui.rs".to_string());
+        assert!(comment.is_file_level());
+        assert!(comment.is_review_comment());
+        assert!(!comment.is_inline());
+
+        let general = PendingComment::new_general("test".to_string());
+        assert!(!general.is_file_level());
+        assert!(!general.is_review_comment());
+        assert!(PendingComment::new_inline("test".to_string(), "a.rs".to_string(), 1).is_review_comment());
     }
 
     #[test]
@@ -455,12 +1164,42 @@ mod tests {
         assert_eq!(deserialized.line_number, Some(15));
     }
 
+    #[test]
+    fn test_pending_comment_sides() {
+        let comment = PendingComment::new_multiline("Mixed".to_string(), "file.rs".to_string(), 5, 7)
+            .with_start_side(DiffSide::Left);
+        let json = serde_json::to_value(&comment).unwrap();
+        assert_eq!(json["start_side"], "LEFT");
+        assert_eq!(json["side"], "RIGHT");
+
+        // Drafts saved before sides existed are on the new version
+        let old: PendingComment = serde_json::from_str(
+            r#"{"body": "Old", "file_path": "file.rs", "line_number": 3, "start_line": null}"#,
+        )
+        .unwrap();
+        assert_eq!(old.side, DiffSide::Right);
+        assert_eq!(old.start_side, DiffSide::Right);
+    }
+
+    #[test]
+    fn test_pending_comment_same_draft() {
+        let new_line = PendingComment::new_inline("Same".to_string(), "file.rs".to_string(), 10);
+        let old_line = new_line.clone().with_side(DiffSide::Left);
+        let mut synced = new_line.clone();
+        synced.remote_id = Some("PRRC_1".to_string());
+
+        assert!(new_line.same_draft(&synced));
+        assert!(!new_line.same_draft(&old_line));
+        assert!(!new_line.same_draft(&PendingComment::new_inline("Other".to_string(), "file.rs".to_string(), 10)));
+    }
+
     // ========================================================================
     // ReviewPr tests
     // ========================================================================
 
     fn create_test_review_pr() -> ReviewPr {
         ReviewPr {
+            host: DEFAULT_HOST.to_string(),
             number: 123,
             title: "Test PR".to_string(),
             repo_owner: "testowner".to_string(),
@@ -469,6 +1208,13 @@ mod tests {
             created_at: "2024-01-15T10:30:00Z".to_string(),
             head_sha: Some("abc123".to_string()),
             body: Some("Test PR description".to_string()),
+            is_draft: false,
+            review_decision: None,
+            ci_state: None,
+            size: None,
+            updated_at: None,
+            review_reasons: Vec::new(),
+            notification: None,
         }
     }
 
@@ -478,11 +1224,51 @@ mod tests {
         assert_eq!(pr.repo_full_name(), "testowner/testrepo");
     }
 
+    #[test]
+    fn test_review_pr_repo_full_name_enterprise_host() {
+        let mut pr = create_test_review_pr();
+        pr.host = "github.example.com".to_string();
+        assert_eq!(pr.repo_full_name(), "github.example.com/testowner/testrepo");
+    }
+
+    #[test]
+    fn test_review_pr_deserialize_without_host() {
+        // Caches written before host support have no host field
+        let json = r#"{"number": 1, "title": "t", "repo_owner": "o", "repo_name": "r",
+            "author": "a", "created_at": "2024-01-15T10:30:00Z", "head_sha": null, "body": null}"#;
+        let pr: ReviewPr = serde_json::from_str(json).unwrap();
+        assert_eq!(pr.host, DEFAULT_HOST);
+        // ...nor the list columns
+        assert!(!pr.is_draft);
+        assert_eq!(pr.review_decision, None);
+        assert_eq!(pr.updated_age(), None);
+    }
+
+    #[test]
+    fn test_review_pr_list_columns_roundtrip() {
+        let mut pr = create_test_review_pr();
+        pr.is_draft = true;
+        pr.review_decision = Some(ReviewDecision::ChangesRequested);
+        pr.ci_state = Some(CheckState::Failure);
+        pr.size = Some(PrSize { additions: 120, deletions: 4, changed_files: 3 });
+        pr.updated_at = Some("2024-01-16T08:00:00Z".to_string());
+
+        let json = serde_json::to_string(&pr).unwrap();
+        assert!(json.contains(r#""review_decision":"CHANGES_REQUESTED""#));
+        let cached: ReviewPr = serde_json::from_str(&json).unwrap();
+        assert!(cached.is_draft);
+        assert_eq!(cached.review_decision, Some(ReviewDecision::ChangesRequested));
+        assert_eq!(cached.ci_state, Some(CheckState::Failure));
+        assert_eq!(cached.size, pr.size);
+        assert!(cached.updated_age().unwrap().ends_with('d'));
+    }
+
     #[test]
     fn test_review_pr_to_pr_info() {
         let pr = create_test_review_pr();
         let info = pr.to_pr_info();
 
+        assert_eq!(info.host, DEFAULT_HOST);
         assert_eq!(info.owner, "testowner");
         assert_eq!(info.repo, "testrepo");
         assert_eq!(info.number, 123);
@@ -525,6 +1311,7 @@ mod tests {
     #[test]
     fn test_pr_info_creation() {
         let info = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "owner".to_string(),
             repo: "repo".to_string(),
             number: 42,
@@ -538,6 +1325,7 @@ mod tests {
     #[test]
     fn test_pr_info_clone() {
         let info = PrInfo {
+            host: DEFAULT_HOST.to_string(),
             owner: "owner".to_string(),
             repo: "repo".to_string(),
             number: 42,
@@ -555,26 +1343,34 @@ mod tests {
 
     fn create_test_thread_comment(body: &str, author: &str) -> ThreadComment {
         ThreadComment {
+            id: 1,
+            node_id: None,
             body: body.to_string(),
             author: author.to_string(),
             created_at: "2024-01-15T10:30:00Z".to_string(),
+            by_viewer: false,
+            reactions: Vec::new(),
         }
     }
 
     fn create_test_comment_thread(is_inline: bool) -> CommentThread {
         CommentThread {
             id: 1,
+            node_id: None,
             file_path: if is_inline {
                 Some("src/main.rs".to_string())
             } else {
                 None
             },
             line: if is_inline { Some(42) } else { None },
+            side: DiffSide::Right,
+            start_line: None,
             comments: vec![
                 create_test_thread_comment("First comment", "user1"),
                 create_test_thread_comment("Second comment", "user2"),
             ],
             outdated: false,
+            resolved: false,
         }
     }
 
@@ -590,6 +1386,19 @@ mod tests {
         assert!(!thread.is_inline());
     }
 
+    #[test]
+    fn test_comment_thread_is_file_level() {
+        let mut thread = create_test_comment_thread(true);
+        assert!(!thread.is_file_level());
+
+        thread.line = None;
+        assert!(thread.is_file_level());
+
+        thread.outdated = true;
+        assert!(!thread.is_file_level());
+        assert!(!create_test_comment_thread(false).is_file_level());
+    }
+
     #[test]
     fn test_comment_thread_comment_count() {
         let thread = create_test_comment_thread(true);
@@ -600,10 +1409,14 @@ mod tests {
     fn test_comment_thread_comment_count_empty() {
         let thread = CommentThread {
             id: 1,
+            node_id: None,
             file_path: None,
             line: None,
+            side: DiffSide::Right,
+            start_line: None,
             comments: vec![],
             outdated: false,
+            resolved: false,
         };
         assert_eq!(thread.comment_count(), 0);
     }
@@ -629,10 +1442,14 @@ mod tests {
     fn test_comment_thread_preview_empty() {
         let thread = CommentThread {
             id: 1,
+            node_id: None,
             file_path: None,
             line: None,
+            side: DiffSide::Right,
+            start_line: None,
             comments: vec![],
             outdated: false,
+            resolved: false,
         };
         assert_eq!(thread.preview(100), "");
     }
@@ -656,10 +1473,14 @@ mod tests {
     fn test_comment_thread_author_empty() {
         let thread = CommentThread {
             id: 1,
+            node_id: None,
             file_path: None,
             line: None,
+            side: DiffSide::Right,
+            start_line: None,
             comments: vec![],
             outdated: false,
+            resolved: false,
         };
         assert_eq!(thread.author(), "unknown");
     }
@@ -683,6 +1504,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_line_kind_clone() {
         let kind = LineKind::Add;
         let cloned = kind.clone();
@@ -793,9 +1615,13 @@ mod tests {
     #[test]
     fn test_thread_comment_creation() {
         let comment = ThreadComment {
+            id: 7,
+            node_id: None,
             body: "Test comment body".to_string(),
             author: "testuser".to_string(),
             created_at: "2024-01-15T10:30:00Z".to_string(),
+            by_viewer: false,
+            reactions: Vec::new(),
         };
 
         assert_eq!(comment.body, "Test comment body");
@@ -868,4 +1694,125 @@ mod tests {
         assert_eq!(comment.body, "General PR comment");
         assert_eq!(comment.user.login, "commenter");
     }
+
+    // ========================================================================
+    // Reaction tests
+    // ========================================================================
+
+    #[test]
+    fn test_reaction_kind_names() {
+        for kind in ReactionKind::ALL {
+            assert_eq!(ReactionKind::from_name(kind.as_str()), Some(kind));
+        }
+        assert_eq!(ReactionKind::from_name("THUMBS_UP"), Some(ReactionKind::ThumbsUp));
+        assert_eq!(ReactionKind::from_name("UNKNOWN"), None);
+    }
+
+    #[test]
+    fn test_reaction_apply() {
+        let mut reactions = vec![Reaction { kind: ReactionKind::Heart, count: 2, by_viewer: false }];
+
+        // New kinds are inserted in picker order
+        Reaction::apply(&mut reactions, ReactionKind::ThumbsUp, true);
+        let kinds: Vec<ReactionKind> = reactions.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, vec![ReactionKind::ThumbsUp, ReactionKind::Heart]);
+        assert!(reactions[0].by_viewer);
+
+        // Reacting to an existing group bumps its count; repeating it does nothing
+        Reaction::apply(&mut reactions, ReactionKind::Heart, true);
+        Reaction::apply(&mut reactions, ReactionKind::Heart, true);
+        assert_eq!(reactions[1].count, 3);
+        assert!(reactions[1].by_viewer);
+
+        // Removing the last reaction of a kind drops its group
+        Reaction::apply(&mut reactions, ReactionKind::ThumbsUp, false);
+        Reaction::apply(&mut reactions, ReactionKind::Heart, false);
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].count, 2);
+        assert!(!reactions[0].by_viewer);
+    }
+
+    // ========================================================================
+    // CheckRun tests
+    // ========================================================================
+
+    fn check(state: CheckState, required: bool) -> CheckRun {
+        CheckRun {
+            name: "ci".to_string(),
+            state,
+            required,
+            started_at: Some("2024-01-01T10:00:00Z".to_string()),
+            completed_at: Some("2024-01-01T10:01:30Z".to_string()),
+            url: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_run_duration() {
+        let mut run = check(CheckState::Success, false);
+        assert_eq!(run.duration(), Some(chrono::TimeDelta::seconds(90)));
+
+        run.completed_at = None;
+        assert_eq!(run.duration(), None);
+    }
+
+    #[test]
+    fn test_check_run_is_blocking() {
+        assert!(check(CheckState::Failure, true).is_blocking());
+        assert!(check(CheckState::Pending, true).is_blocking());
+        assert!(!check(CheckState::Failure, false).is_blocking());
+        assert!(!check(CheckState::Success, true).is_blocking());
+        assert!(!check(CheckState::Neutral, true).is_blocking());
+    }
+
+    fn entry(id: &str, name: &str) -> MetadataEntry {
+        MetadataEntry { id: id.to_string(), name: name.to_string(), is_team: false }
+    }
+
+    #[test]
+    fn test_metadata_completions() {
+        let metadata = PrMetadata {
+            reviewers: vec![entry("U1", "alice")],
+            users: vec![entry("U1", "alice"), entry("U2", "bob"), entry("U3", "abigail"), entry("U4", "carla")],
+            teams: vec![MetadataEntry { is_team: true, ..entry("T1", "org/backend") }],
+            repo_labels: vec![entry("L1", "bug"), entry("L2", "debug")],
+            ..Default::default()
+        };
+
+        let names = |entries: Vec<&MetadataEntry>| entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        // Already requested and the author are left out, teams are offered for reviews only
+        assert_eq!(names(metadata.completions(MetadataField::Reviewers, "", "carla")), vec!["bob", "abigail", "org/backend"]);
+        assert_eq!(names(metadata.completions(MetadataField::Assignees, "A", "carla")), vec!["alice", "abigail", "carla"]);
+        // Prefix matches first
+        assert_eq!(names(metadata.completions(MetadataField::Labels, "bug", "")), vec!["bug", "debug"]);
+        assert_eq!(names(metadata.completions(MetadataField::Labels, "de", "")), vec!["debug"]);
+    }
+
+    #[test]
+    fn test_metadata_differs_from() {
+        let original = PrMetadata {
+            labels: vec![entry("L1", "bug"), entry("L2", "debug")],
+            ..Default::default()
+        };
+        let mut edited = original.clone();
+        edited.labels.reverse();
+        assert!(!edited.differs_from(&original));
+
+        edited.labels.pop();
+        assert!(edited.differs_from(&original));
+    }
+
+    #[test]
+    fn test_merge_status() {
+        assert!(MergeStatus::Clean.can_merge());
+        assert!(MergeStatus::Unstable.can_merge());
+        assert!(!MergeStatus::Blocked.can_merge());
+        assert!(MergeStatus::Blocked.can_auto_merge());
+        assert!(!MergeStatus::Clean.can_auto_merge());
+        assert!(!MergeStatus::Conflicting.can_auto_merge());
+        assert!(!MergeStatus::Draft.can_auto_merge());
+        assert!(!MergeMethod::Rebase.has_commit_message());
+        assert_eq!(MergeMethod::Squash.as_str(), "SQUASH");
+    }
 }
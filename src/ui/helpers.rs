@@ -13,6 +13,9 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
 use ratatui::Terminal;
 
+use super::types::InlineTarget;
+use crate::types::{CheckRun, CheckState, DiffFile, DiffSide, LineKind, PrReview, Reaction};
+
 /// Fill an entire area with a background color
 pub fn fill_area(buf: &mut Buffer, area: Rect, color: Color) {
     let style = Style::default().bg(color);
@@ -52,6 +55,20 @@ pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Re
     Ok(())
 }
 
+/// Build a command that opens a URL in the system browser
+pub fn open_url_command(url: &str) -> std::process::Command {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut command = std::process::Command::new(opener);
+    command.arg(url);
+    command
+}
+
 /// Format time as relative (e.g., "2h ago", "3d ago")
 pub fn format_relative_time(iso_time: &str) -> String {
     chrono::DateTime::parse_from_rfc3339(iso_time)
@@ -131,9 +148,221 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
         .collect()
 }
 
+/// Count shown in a list header, with "+" when the list was cut short by the PR cap
+pub fn list_count(count: usize, truncated: bool) -> String {
+    if truncated {
+        format!("{}+", count)
+    } else {
+        count.to_string()
+    }
+}
+
+/// One-line summary of each reviewer's latest verdict, e.g. "@bob approved, @carol commented"
+/// Pending reviews are skipped; a comment doesn't replace an earlier approval or change request
+pub fn review_summary(reviews: &[PrReview]) -> Option<String> {
+    let mut latest: Vec<(&str, &str)> = Vec::new();
+
+    for review in reviews.iter().filter(|r| r.state != "PENDING") {
+        match latest.iter_mut().find(|(author, _)| *author == review.author) {
+            Some(entry) if review.state != "COMMENTED" => entry.1 = &review.state,
+            Some(_) => {}
+            None => latest.push((&review.author, &review.state)),
+        }
+    }
+
+    if latest.is_empty() {
+        return None;
+    }
+
+    let parts: Vec<String> = latest
+        .iter()
+        .map(|(author, state)| {
+            let verdict = match *state {
+                "APPROVED" => "approved",
+                "CHANGES_REQUESTED" => "requested changes",
+                "DISMISSED" => "dismissed",
+                _ => "commented",
+            };
+            format!("@{} {}", author, verdict)
+        })
+        .collect();
+    Some(parts.join(", "))
+}
+
+/// Head commit of the current user's latest submitted review, if the host reported it
+pub fn last_reviewed_commit(reviews: &[PrReview]) -> Option<&str> {
+    reviews
+        .iter()
+        .rev()
+        .filter(|review| review.by_viewer && review.state != "PENDING")
+        .find_map(|review| review.commit_id.as_deref())
+}
+
+/// Why a selection without new-side lines can't hold a suggestion
+pub const NO_NEW_LINES: &str = "Suggestions need lines from the new version";
+
+/// New-side lines of the display rows `sel_start..=sel_end` of a file (hunk headers count as rows)
+/// Returns (start_line, end_line, contents); deleted lines are skipped. The lines must come
+/// from one hunk and be contiguous, or the suggestion would replace lines the diff doesn't show
+pub fn selection_new_lines(
+    file: &DiffFile,
+    sel_start: usize,
+    sel_end: usize,
+) -> Result<(u32, u32, Vec<String>), &'static str> {
+    let mut selected: Vec<(usize, u32, String)> = Vec::new();
+    let mut line_idx = 0;
+    for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+        line_idx += 1; // Skip hunk header
+
+        for diff_line in &hunk.lines {
+            if line_idx >= sel_start
+                && line_idx <= sel_end
+                && let Some(new_ln) = diff_line.new_ln
+            {
+                selected.push((hunk_idx, new_ln, diff_line.content.clone()));
+            }
+            line_idx += 1;
+        }
+    }
+
+    let (first, last) = (selected.first().ok_or(NO_NEW_LINES)?, selected.last().ok_or(NO_NEW_LINES)?);
+    let contiguous = selected.windows(2).all(|pair| pair[1].1 == pair[0].1 + 1);
+    if first.0 != last.0 || !contiguous {
+        return Err("Suggestions need a selection inside one hunk");
+    }
+
+    let (start, end) = (first.1, last.1);
+    let lines = selected.into_iter().map(|(_, _, content)| content).collect();
+    Ok((start, end, lines))
+}
+
+/// Whether every line of a comment target is a new-side line of `files` (a file-level target only needs the file)
+/// Old-side lines never match: they can't be placed in a diff against another base
+pub fn head_target_in_diff(files: &[DiffFile], target: &InlineTarget) -> bool {
+    let Some(file) = files.iter().find(|file| file.path == target.path) else {
+        return false;
+    };
+    [target.line, target.start].into_iter().flatten().all(|(line, side)| {
+        side == DiffSide::Right
+            && file
+                .hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .any(|diff_line| diff_line.kind != LineKind::Del && diff_line.new_ln == Some(line))
+    })
+}
+
+/// Reaction counts, e.g. "👍 2  [🎉 1]"; the user's own reactions are bracketed
+pub fn reactions_label(reactions: &[Reaction]) -> String {
+    reactions
+        .iter()
+        .map(|r| {
+            let label = format!("{} {}", r.kind.emoji(), r.count);
+            if r.by_viewer { format!("[{}]", label) } else { label }
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Counts of checks by outcome, e.g. "3 passed, 1 failed, 2 pending"
+pub fn checks_summary(checks: &[CheckRun]) -> String {
+    let labels = [
+        (CheckState::Success, "passed"),
+        (CheckState::Failure, "failed"),
+        (CheckState::Pending, "pending"),
+        (CheckState::Neutral, "skipped"),
+    ];
+    let parts: Vec<String> = labels
+        .iter()
+        .filter_map(|&(state, label)| {
+            let count = checks.iter().filter(|c| c.state == state).count();
+            (count > 0).then(|| format!("{} {}", count, label))
+        })
+        .collect();
+    if parts.is_empty() {
+        "no checks".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Short duration, e.g. "45s", "3m 12s", "1h 5m"
+pub fn format_duration(duration: chrono::TimeDelta) -> String {
+    let secs = duration.num_seconds();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Count shortened to fit a narrow column, e.g. "950", "1.2k", "34k", "1.5M"
+pub fn compact_count(n: u32) -> String {
+    match n {
+        0..1_000 => n.to_string(),
+        1_000..10_000 => format!("{:.1}k", n as f64 / 1_000.0),
+        10_000..1_000_000 => format!("{}k", n / 1_000),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Fence that opens a GitHub suggested change
+const SUGGESTION_FENCE: &str = "```suggestion";
+
+/// Comment body proposing `lines` as the replacement for the commented lines
+pub fn suggestion_body(lines: &[String]) -> String {
+    format!("{}\n{}\n```\n", SUGGESTION_FENCE, lines.join("\n"))
+}
+
+/// Split a comment body around its first suggestion block
+/// Returns (text before, suggested lines, text after); an unclosed block runs to the end
+pub fn split_suggestion(body: &str) -> Option<(String, Vec<String>, String)> {
+    let lines: Vec<&str> = body.split('\n').collect();
+    let open = lines.iter().position(|l| l.trim() == SUGGESTION_FENCE)?;
+    let close = lines[open + 1..]
+        .iter()
+        .position(|l| l.trim() == "```")
+        .map(|i| open + 1 + i)
+        .unwrap_or(lines.len());
+
+    let suggested = lines[open + 1..close].iter().map(|l| l.to_string()).collect();
+    let after = lines.get(close + 1..).unwrap_or_default().join("\n");
+    Some((lines[..open].join("\n"), suggested, after))
+}
+
+/// Mini-diff of a suggestion against the lines it replaces, as (marker, line) rows
+/// Lines shared at the start and end are shown once as context; without the original
+/// lines every suggested line is an addition
+pub fn suggestion_diff(original: Option<&[String]>, suggested: &[String]) -> Vec<(char, String)> {
+    let Some(original) = original else {
+        return suggested.iter().map(|l| ('+', l.clone())).collect();
+    };
+
+    let prefix = original
+        .iter()
+        .zip(suggested)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = original[prefix..]
+        .iter()
+        .rev()
+        .zip(suggested[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut rows: Vec<(char, String)> = original[..prefix].iter().map(|l| (' ', l.clone())).collect();
+    rows.extend(original[prefix..original.len() - suffix].iter().map(|l| ('-', l.clone())));
+    rows.extend(suggested[prefix..suggested.len() - suffix].iter().map(|l| ('+', l.clone())));
+    rows.extend(original[original.len() - suffix..].iter().map(|l| (' ', l.clone())));
+    rows
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::ReactionKind;
 
     #[test]
     fn test_wrap_text_with_code_preserves_whitespace() {
@@ -194,4 +423,177 @@ mod tests {
     fn test_truncate_or_pad_exact() {
         assert_eq!(truncate_or_pad("hello", 5), "hello");
     }
+
+    #[test]
+    fn test_list_count() {
+        assert_eq!(list_count(12, false), "12");
+        assert_eq!(list_count(300, true), "300+");
+    }
+
+    #[test]
+    fn test_review_summary() {
+        let review = |author: &str, state: &str| PrReview {
+            author: author.to_string(),
+            state: state.to_string(),
+            by_viewer: false,
+            commit_id: None,
+        };
+        assert_eq!(review_summary(&[]), None);
+        assert_eq!(
+            review_summary(&[
+                review("bob", "CHANGES_REQUESTED"),
+                review("carol", "COMMENTED"),
+                review("bob", "APPROVED"),
+                review("bob", "COMMENTED"),
+                review("dave", "PENDING"),
+            ])
+            .as_deref(),
+            Some("@bob approved, @carol commented")
+        );
+    }
+
+    #[test]
+    fn test_last_reviewed_commit() {
+        let review = |by_viewer, state: &str, commit: Option<&str>| PrReview {
+            author: "me".to_string(),
+            state: state.to_string(),
+            by_viewer,
+            commit_id: commit.map(str::to_string),
+        };
+        assert_eq!(last_reviewed_commit(&[]), None);
+        assert_eq!(
+            last_reviewed_commit(&[
+                review(true, "COMMENTED", Some("aaa")),
+                review(true, "APPROVED", Some("bbb")),
+                review(false, "APPROVED", Some("ccc")),
+                review(true, "PENDING", Some("ddd")),
+            ]),
+            Some("bbb")
+        );
+    }
+
+    #[test]
+    fn test_selection_new_lines() {
+        // Rows: 0 header, 1 a, 2 -b, 3 +c, 4 d, 5 header, 6 x, 7 +y
+        let files = crate::parser::parse_diff(
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,3 +1,3 @@\n a\n-b\n+c\n d\n@@ -20,1 +20,2 @@\n x\n+y\n",
+        );
+        let file = &files[0];
+
+        assert_eq!(
+            selection_new_lines(file, 1, 4),
+            Ok((1, 3, vec!["a".to_string(), "c".to_string(), "d".to_string()]))
+        );
+        assert_eq!(selection_new_lines(file, 6, 7), Ok((20, 21, vec!["x".to_string(), "y".to_string()])));
+        assert_eq!(selection_new_lines(file, 2, 2), Err(NO_NEW_LINES));
+        // Lines 4..19 aren't in the diff
+        assert_eq!(
+            selection_new_lines(file, 4, 6),
+            Err("Suggestions need a selection inside one hunk")
+        );
+    }
+
+    #[test]
+    fn test_head_target_in_diff() {
+        let files = crate::parser::parse_diff(
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,3 +1,3 @@\n fn a() {}\n-fn b() {}\n+fn c() {}\n fn d() {}\n",
+        );
+        let target = |path: &str, line, start| InlineTarget { path: path.to_string(), line, start };
+
+        assert!(head_target_in_diff(&files, &target("src/lib.rs", Some((2, DiffSide::Right)), None)));
+        assert!(head_target_in_diff(
+            &files,
+            &target("src/lib.rs", Some((3, DiffSide::Right)), Some((1, DiffSide::Right)))
+        ));
+        assert!(head_target_in_diff(&files, &InlineTarget::file("src/lib.rs".to_string())));
+        // Outside the hunks, removed lines and other files
+        assert!(!head_target_in_diff(&files, &target("src/lib.rs", Some((9, DiffSide::Right)), None)));
+        assert!(!head_target_in_diff(&files, &target("src/lib.rs", Some((2, DiffSide::Left)), None)));
+        assert!(!head_target_in_diff(&files, &InlineTarget::file("src/main.rs".to_string())));
+    }
+
+    #[test]
+    fn test_checks_summary() {
+        let check = |state| CheckRun {
+            name: "ci".to_string(),
+            state,
+            required: false,
+            started_at: None,
+            completed_at: None,
+            url: None,
+            annotations: Vec::new(),
+        };
+        assert_eq!(checks_summary(&[]), "no checks");
+        assert_eq!(
+            checks_summary(&[
+                check(CheckState::Pending),
+                check(CheckState::Success),
+                check(CheckState::Success),
+                check(CheckState::Failure),
+            ]),
+            "2 passed, 1 failed, 1 pending"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(45)), "45s");
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(192)), "3m 12s");
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(3900)), "1h 5m");
+    }
+
+    #[test]
+    fn test_compact_count() {
+        assert_eq!(compact_count(950), "950");
+        assert_eq!(compact_count(1_234), "1.2k");
+        assert_eq!(compact_count(34_567), "34k");
+        assert_eq!(compact_count(1_500_000), "1.5M");
+    }
+
+    #[test]
+    fn test_reactions_label() {
+        assert_eq!(reactions_label(&[]), "");
+        let reactions = [
+            Reaction { kind: ReactionKind::ThumbsUp, count: 2, by_viewer: false },
+            Reaction { kind: ReactionKind::Hooray, count: 1, by_viewer: true },
+        ];
+        assert_eq!(reactions_label(&reactions), "👍 2  [🎉 1]");
+    }
+
+    #[test]
+    fn test_split_suggestion() {
+        let lines = vec!["let x = 1;".to_string(), "".to_string()];
+        let body = format!("Use a constant\n{}Thanks", suggestion_body(&lines));
+        let (before, suggested, after) = split_suggestion(&body).unwrap();
+        assert_eq!(before, "Use a constant");
+        assert_eq!(suggested, lines);
+        assert_eq!(after, "Thanks");
+
+        let (_, suggested, after) = split_suggestion("```suggestion\nfoo").unwrap();
+        assert_eq!(suggested, vec!["foo".to_string()]);
+        assert_eq!(after, "");
+
+        assert!(split_suggestion("```rust\nfoo\n```").is_none());
+    }
+
+    #[test]
+    fn test_suggestion_diff() {
+        let lines = |ls: &[&str]| ls.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let original = lines(&["fn a() {", "    old();", "}"]);
+        let suggested = lines(&["fn a() {", "    new();", "    more();", "}"]);
+
+        let rows = suggestion_diff(Some(&original), &suggested);
+        let markers: String = rows.iter().map(|(m, _)| *m).collect();
+        assert_eq!(markers, " -++ ");
+        assert_eq!(rows[1].1, "    old();");
+
+        // Deleting every line
+        let rows = suggestion_diff(Some(&original), &[]);
+        assert_eq!(rows.iter().map(|(m, _)| *m).collect::<String>(), "---");
+
+        let rows = suggestion_diff(None, &suggested);
+        assert!(rows.iter().all(|(m, _)| *m == '+'));
+    }
 }
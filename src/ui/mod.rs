@@ -21,20 +21,47 @@ use ratatui::widgets::{
 };
 use ratatui::Terminal;
 
-use crate::config::Config;
+use crate::config::{Config, ForgeKind, PrColumn};
+use crate::drafts::PendingReviewSync;
+use crate::local::LocalReview;
 use crate::syntax::Highlighter;
-use crate::types::{CommentThread, DiffFile, LineKind, PendingComment, ReviewPr};
+use crate::types::{
+    AnnotationLevel, CheckAnnotation, CheckRun, CheckState, CommentThread, DiffFile, DiffLine, DiffSide, FileStat,
+    LineKind, MergeInfo, MergeRequest, MetadataField, PendingComment, PrList, PrMetadata, PrReview, Reaction,
+    ReactionKind, ReviewDecision, ReviewPr, ReviewReason, ReviewState, ThreadComment,
+};
 
 // Re-export public types
 pub use types::{CommentMode, HelpMode, LoadingState, PrListTab, Screen, ViewMode};
 
 // Internal type imports
-use types::{Focus, TreeItem, TreeNode};
+use types::{
+    Focus, InlineTarget, MergeField, MetadataEditor, PrListRefresh, PrListState, ReactionPicker, ReactionTarget, SearchTab,
+    TreeItem, TreeNode,
+};
 
 // Type aliases to reduce complexity warnings
-type DiffResultReceiver =
-    mpsc::Receiver<Result<(Vec<DiffFile>, Option<String>, Option<String>), String>>;
-type PrListReceiver = mpsc::Receiver<Result<(Vec<ReviewPr>, Vec<ReviewPr>), String>>;
+type DiffResultReceiver = mpsc::Receiver<Result<(Vec<DiffFile>, Option<ReviewState>), String>>;
+type PrListReceiver = mpsc::Receiver<Result<PrListRefresh, String>>;
+/// Pending review edits that went through, and the error that stopped the rest
+type PendingSyncReceiver = mpsc::Receiver<(PendingReviewSync, Option<String>)>;
+/// (thread_index, comment_index, new body, or None once deleted) on success
+type CommentChangeReceiver = mpsc::Receiver<Result<(usize, usize, Option<String>), String>>;
+/// (target, reaction, added) on success
+type ReactionReceiver = mpsc::Receiver<Result<(ReactionTarget, ReactionKind, bool), String>>;
+/// (thread id, done) on success
+type NotificationReceiver = mpsc::Receiver<Result<(String, bool), String>>;
+type CompareReceiver = mpsc::Receiver<Result<(String, Vec<DiffFile>), String>>; // (base commit, files)
+
+/// Line number and side a comment on a diff line is anchored to
+/// Added and context lines use the new version, deleted lines the old one
+fn line_anchor(line: &DiffLine) -> Option<(u32, DiffSide)> {
+    match (line.new_ln, line.old_ln) {
+        (Some(new_ln), _) => Some((new_ln, DiffSide::Right)),
+        (None, Some(old_ln)) => Some((old_ln, DiffSide::Left)),
+        (None, None) => None,
+    }
+}
 
 /// Application state
 pub struct App {
@@ -57,12 +84,20 @@ pub struct App {
     my_pr_scroll: usize,
     filtered_my_pr_indices: Vec<usize>,
 
+    // Notifications about PRs
+    inbox: PrListState,
+    notification_receiver: Option<NotificationReceiver>,
+
+    // Saved searches from the [[tabs]] config
+    search_tabs: Vec<SearchTab>,
+
     // Shared PR list state
     pr_search_mode: bool,
     pr_search_query: String,
     repo_filter: Option<String>, // None = all repos
     available_repos: Vec<String>,
     repo_filter_index: usize, // 0 = all, 1+ = specific repo
+    reason_filter: Option<ReviewReason>, // "For Review" tab only; None = any reason
     author_filter: Option<String>, // When viewing PRs by a specific user
 
     // Diff view state
@@ -86,14 +121,20 @@ pub struct App {
     tree_collapsed: bool, // Hide the file tree panel entirely
 
     // For async diff loading
-    diff_receiver: Option<DiffResultReceiver>, // (files, head_sha, body)
+    diff_receiver: Option<DiffResultReceiver>, // (files, review state)
+    compare_receiver: Option<CompareReceiver>,
+    full_files: Option<Vec<DiffFile>>, // The PR's whole diff, kept while the changes since the last review are shown
+    since_review: Option<String>,      // Commit the shown changes start from
     current_pr: Option<ReviewPr>,
+    local_review: Option<LocalReview>, // Set when reviewing a local git range (notes only)
 
     // Comment drafting
     pending_comments: Vec<PendingComment>,
     comment_mode: CommentMode,
     selected_pending_comment: usize,
     editing_comment_index: Option<usize>, // Index of comment being edited (None = new comment)
+    synced_drafts: HashMap<String, String>, // Remote id -> text last sent to the GitHub pending review
+    pending_sync_dirty: bool,               // Drafts changed while a pending review sync was running
 
     // Cursor position for inline comments (line index in current file's flattened diff)
     diff_cursor: usize,
@@ -111,14 +152,38 @@ pub struct App {
 
     // Comment threads (existing comments from GitHub)
     comment_threads: Vec<CommentThread>,
-    line_to_threads: HashMap<(String, u32), Vec<usize>>, // Quick lookup: (file_path, line) -> thread indices
+    line_to_threads: HashMap<(String, u32, DiffSide), Vec<usize>>, // Quick lookup: (file_path, line, side) -> thread indices
+    line_to_annotations: HashMap<(String, u32), Vec<(usize, usize)>>, // (file_path, new line) -> (check, annotation)
+    expand_annotations: bool, // Show full annotation messages for the cursor line
+
+    // Review state loaded with the threads (empty for hosts that don't report it)
+    pr_reviews: Vec<PrReview>,
+    pr_node_id: Option<String>,
+    pr_reactions: Vec<Reaction>,
+    reaction_picker: Option<ReactionPicker>,
+    metadata_editor: Option<MetadataEditor>,
+    checks: Option<Result<Vec<CheckRun>, String>>, // CI checks of the head commit, once loaded
+    show_checks: bool,
+    selected_check: usize,
+    pr_base_sha: Option<String>,
+    file_stats: Vec<FileStat>,
+    show_resolved_threads: bool, // Resolved threads are hidden from the threads list by default
 
     // Async receivers for non-blocking operations
-    comment_threads_receiver: Option<mpsc::Receiver<Result<Vec<CommentThread>, String>>>,
+    review_state_receiver: Option<mpsc::Receiver<Result<ReviewState, String>>>,
     pr_list_receiver: Option<PrListReceiver>,
     comment_submit_receiver: Option<mpsc::Receiver<Result<usize, String>>>,
     reply_submit_receiver: Option<mpsc::Receiver<Result<usize, String>>>, // thread_index on success
+    resolve_receiver: Option<mpsc::Receiver<Result<(usize, bool), String>>>, // (thread_index, resolved) on success
+    comment_change_receiver: Option<CommentChangeReceiver>,
+    reaction_receiver: Option<ReactionReceiver>,
+    checks_receiver: Option<mpsc::Receiver<Result<Vec<CheckRun>, String>>>,
+    merge_info_receiver: Option<mpsc::Receiver<Result<MergeInfo, String>>>,
+    merge_receiver: Option<mpsc::Receiver<Result<bool, String>>>, // auto-merge enabled (rather than merged) on success
+    metadata_receiver: Option<mpsc::Receiver<Result<PrMetadata, String>>>,
+    metadata_update_receiver: Option<mpsc::Receiver<Result<(), String>>>,
     review_submit_receiver: Option<mpsc::Receiver<Result<(String, usize), String>>>, // (review action, comments count) on success
+    pending_sync_receiver: Option<PendingSyncReceiver>,
 
     // Cached tree structure to avoid rebuilding on every navigation
     cached_tree: Option<Vec<TreeNode>>,
@@ -127,6 +192,11 @@ pub struct App {
     // Background refresh state
     background_refreshing: bool,
     cache_age: Option<String>,
+
+    // Lists cut short by the pr_list.max_prs cap
+    review_truncated: bool,
+    my_truncated: bool,
+    host_errors: Vec<String>, // Hosts that failed in the last PR list fetch, as "host: error"
 }
 
 impl App {
@@ -177,9 +247,13 @@ impl App {
             selected_my_pr: 0,
             my_pr_scroll: 0,
             filtered_my_pr_indices: Vec::new(),
+            inbox: PrListState::default(),
+            notification_receiver: None,
+            search_tabs: Vec::new(),
             pr_search_mode: false,
             pr_search_query: String::new(),
             repo_filter: None,
+            reason_filter: None,
             available_repos: Vec::new(),
             repo_filter_index: 0,
             author_filter: None,
@@ -205,12 +279,18 @@ impl App {
             tree_collapsed: false,
 
             diff_receiver: None,
+            compare_receiver: None,
+            full_files: None,
+            since_review: None,
             current_pr: None,
+            local_review: None,
 
             pending_comments: Vec::new(),
             comment_mode: CommentMode::None,
             selected_pending_comment: 0,
             editing_comment_index: None,
+            synced_drafts: HashMap::new(),
+            pending_sync_dirty: false,
 
             diff_cursor: 0,
             visual_mode: false,
@@ -223,27 +303,62 @@ impl App {
 
             comment_threads: Vec::new(),
             line_to_threads: HashMap::new(),
-
-            comment_threads_receiver: None,
+            line_to_annotations: HashMap::new(),
+            expand_annotations: false,
+
+            pr_reviews: Vec::new(),
+            pr_node_id: None,
+            pr_reactions: Vec::new(),
+            reaction_picker: None,
+            metadata_editor: None,
+            checks: None,
+            show_checks: false,
+            selected_check: 0,
+            pr_base_sha: None,
+            file_stats: Vec::new(),
+            show_resolved_threads: false,
+
+            review_state_receiver: None,
             pr_list_receiver: None,
             comment_submit_receiver: None,
             reply_submit_receiver: None,
+            resolve_receiver: None,
+            comment_change_receiver: None,
+            reaction_receiver: None,
+            checks_receiver: None,
+            merge_info_receiver: None,
+            merge_receiver: None,
+            metadata_receiver: None,
+            metadata_update_receiver: None,
             review_submit_receiver: None,
+            pending_sync_receiver: None,
 
             cached_tree: None,
             cached_flat_items: None,
 
             background_refreshing: false,
             cache_age: None,
+
+            review_truncated: false,
+            my_truncated: false,
+            host_errors: Vec::new(),
         }
     }
 
     /// Create app in diff view mode with PR context (for direct PR URL with review support)
-    pub fn new_with_pr(files: Vec<DiffFile>, pr: ReviewPr) -> Self {
+    pub fn new_with_pr(files: Vec<DiffFile>, state: ReviewState) -> Self {
         let mut app = Self::new(files);
-        app.current_pr = Some(pr);
+        app.current_pr = Some(state.pr.clone());
         app.load_current_drafts(); // Load any saved drafts for this PR
-        app.load_comment_threads(); // Load existing comments from GitHub
+        app.apply_review_state(state);
+        app
+    }
+
+    /// Create app in diff view mode for a local review (comments are kept as local notes)
+    pub fn new_with_local(files: Vec<DiffFile>, review: LocalReview) -> Self {
+        let mut app = Self::new(files);
+        app.local_review = Some(review);
+        app.load_current_drafts(); // Load any saved notes for this review
         app
     }
 
@@ -275,6 +390,7 @@ impl App {
         repos.sort();
 
         let config = Config::load();
+        let search_tabs = config.tabs.iter().map(SearchTab::new).collect();
         let view_mode = if config.is_split_view_default() {
             ViewMode::Split
         } else {
@@ -296,9 +412,13 @@ impl App {
             selected_my_pr: 0,
             my_pr_scroll: 0,
             filtered_my_pr_indices: (0..my_count).collect(),
+            inbox: PrListState::default(),
+            notification_receiver: None,
+            search_tabs,
             pr_search_mode: false,
             pr_search_query: String::new(),
             repo_filter: None,
+            reason_filter: None,
             available_repos: repos,
             repo_filter_index: 0,
             author_filter: None,
@@ -324,12 +444,18 @@ impl App {
             tree_collapsed: false,
 
             diff_receiver: None,
+            compare_receiver: None,
+            full_files: None,
+            since_review: None,
             current_pr: None,
+            local_review: None,
 
             pending_comments: Vec::new(),
             comment_mode: CommentMode::None,
             selected_pending_comment: 0,
             editing_comment_index: None,
+            synced_drafts: HashMap::new(),
+            pending_sync_dirty: false,
 
             diff_cursor: 0,
             visual_mode: false,
@@ -342,18 +468,45 @@ impl App {
 
             comment_threads: Vec::new(),
             line_to_threads: HashMap::new(),
-
-            comment_threads_receiver: None,
+            line_to_annotations: HashMap::new(),
+            expand_annotations: false,
+
+            pr_reviews: Vec::new(),
+            pr_node_id: None,
+            pr_reactions: Vec::new(),
+            reaction_picker: None,
+            metadata_editor: None,
+            checks: None,
+            show_checks: false,
+            selected_check: 0,
+            pr_base_sha: None,
+            file_stats: Vec::new(),
+            show_resolved_threads: false,
+
+            review_state_receiver: None,
             pr_list_receiver: None,
             comment_submit_receiver: None,
             reply_submit_receiver: None,
+            resolve_receiver: None,
+            comment_change_receiver: None,
+            reaction_receiver: None,
+            checks_receiver: None,
+            merge_info_receiver: None,
+            merge_receiver: None,
+            metadata_receiver: None,
+            metadata_update_receiver: None,
             review_submit_receiver: None,
+            pending_sync_receiver: None,
 
             cached_tree: None,
             cached_flat_items: None,
 
             background_refreshing: false,
             cache_age: None,
+
+            review_truncated: false,
+            my_truncated: false,
+            host_errors: Vec::new(),
         }
     }
 
@@ -446,25 +599,21 @@ impl App {
             if let Some(ref receiver) = self.diff_receiver
                 && let Ok(result) = receiver.try_recv() {
                     match result {
-                        Ok((files, head_sha, body)) => {
-                            let file_count = files.len();
-                            self.files = files;
-                            self.filtered_indices = (0..file_count).collect();
-                            self.selected_file = 0;
-                            self.scroll_offset = 0;
-                            self.diff_cursor = 0;
-                            self.collapsed.clear();
-                            self.init_collapsed_folders();
-                            self.invalidate_tree_cache(); // Cache invalidated when files change
+                        Ok((files, state)) => {
+                            self.show_diff_files(files);
+                            self.full_files = None;
+                            self.since_review = None;
+                            self.compare_receiver = None;
                             self.screen = Screen::DiffView;
                             self.loading = LoadingState::Idle;
-                            // Store head SHA and body for the PR
-                            if let Some(ref mut pr) = self.current_pr {
-                                pr.head_sha = head_sha;
-                                pr.body = body;
+                            match state {
+                                Some(state) => self.apply_review_state(state),
+                                // Show the diff now and retry the rest in the background
+                                None => {
+                                    self.clear_review_state();
+                                    self.load_review_state();
+                                }
                             }
-                            // Load existing comment threads from GitHub (async)
-                            self.load_comment_threads();
                         }
                         Err(e) => {
                             self.loading = LoadingState::Error(e);
@@ -473,29 +622,113 @@ impl App {
                     self.diff_receiver = None;
                 }
 
-            // Check for async comment threads loading completion
-            if let Some(ref receiver) = self.comment_threads_receiver
+            // Check for async compare diff (changes since the last review) completion
+            if let Some(ref receiver) = self.compare_receiver
                 && let Ok(result) = receiver.try_recv() {
                     match result {
-                        Ok(threads) => {
-                            self.build_line_to_threads_map(&threads);
-                            self.comment_threads = threads;
+                        Ok((_, files)) if files.is_empty() => {
+                            self.loading =
+                                LoadingState::Success("No file changes since your last review".to_string());
                         }
+                        Ok((base, files)) => {
+                            self.loading = LoadingState::Idle;
+                            self.full_files = Some(self.replace_diff_files(files));
+                            self.since_review = Some(base);
+                        }
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!(
+                                "Failed to load changes since your last review: {}",
+                                e
+                            ));
+                        }
+                    }
+                    self.compare_receiver = None;
+                }
+
+            // Check for async review state (comment threads) loading completion
+            if let Some(ref receiver) = self.review_state_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    match result {
+                        Ok(state) => self.apply_review_state(state),
                         Err(e) => {
                             eprintln!("Warning: Failed to load comment threads: {}", e);
-                            self.comment_threads = Vec::new();
-                            self.line_to_threads.clear();
-                            self.line_to_threads.shrink_to_fit();
+                            self.clear_review_state();
+                        }
+                    }
+                    self.review_state_receiver = None;
+                }
+
+            // Check for async CI checks loading completion
+            if let Some(ref receiver) = self.checks_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    self.checks = Some(result);
+                    self.checks_receiver = None;
+                    self.build_line_to_annotations_map();
+                }
+
+            // Check for async mergeability check completion
+            if let Some(ref receiver) = self.merge_info_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    match result {
+                        Ok(info) => self.open_merge_dialog(info),
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!("Failed to check mergeability: {}", e));
+                        }
+                    }
+                    self.merge_info_receiver = None;
+                }
+
+            // Check for async merge completion
+            if let Some(ref receiver) = self.merge_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    self.loading = match result {
+                        Ok(true) => LoadingState::Success("Auto-merge enabled".to_string()),
+                        Ok(false) => LoadingState::Success("PR merged!".to_string()),
+                        Err(e) => LoadingState::Error(format!("Failed to merge: {}", e)),
+                    };
+                    self.merge_receiver = None;
+                }
+
+            // Check for async reviewers/assignees/labels load completion
+            if let Some(ref receiver) = self.metadata_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    match result {
+                        Ok(metadata) => {
+                            self.loading = LoadingState::Idle;
+                            self.metadata_editor = Some(MetadataEditor::new(metadata));
+                        }
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!("Failed to load reviewers and labels: {}", e));
                         }
                     }
-                    self.comment_threads_receiver = None;
+                    self.metadata_receiver = None;
+                }
+
+            // Check for async reviewers/assignees/labels update completion
+            if let Some(ref receiver) = self.metadata_update_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    self.loading = match result {
+                        Ok(()) => LoadingState::Success("Reviewers, assignees and labels updated".to_string()),
+                        Err(e) => LoadingState::Error(format!("Failed to update reviewers and labels: {}", e)),
+                    };
+                    self.metadata_update_receiver = None;
                 }
 
             // Check for async PR list refresh completion
             if let Some(ref receiver) = self.pr_list_receiver
                 && let Ok(result) = receiver.try_recv() {
                     match result {
-                        Ok((mut review_prs, mut my_prs)) => {
+                        Ok(PrListRefresh { review: review_list, my: my_list, searches: search_results, inbox }) => {
+                            self.host_errors = PrList::host_errors_of(
+                                [&review_list, &my_list]
+                                    .into_iter()
+                                    .chain(search_results.iter().flatten())
+                                    .chain(inbox.as_ref().ok()),
+                            );
+                            let (mut review_prs, mut my_prs) = (review_list.prs, my_list.prs);
+                            self.review_truncated = review_list.truncated;
+                            self.my_truncated = my_list.truncated;
+
                             // Sort by repo for proper grouping
                             review_prs.sort_by(|a, b| {
                                 a.repo_full_name()
@@ -511,15 +744,6 @@ impl App {
                             let review_count = review_prs.len();
                             let my_count = my_prs.len();
 
-                            let mut repos: Vec<String> = review_prs
-                                .iter()
-                                .chain(my_prs.iter())
-                                .map(|pr| pr.repo_full_name())
-                                .collect::<HashSet<_>>()
-                                .into_iter()
-                                .collect();
-                            repos.sort();
-
                             // Remember currently selected PRs to restore position
                             let selected_review = self.filtered_review_pr_indices
                                 .get(self.selected_review_pr)
@@ -532,7 +756,8 @@ impl App {
 
                             self.review_prs = review_prs;
                             self.my_prs = my_prs;
-                            self.available_repos = repos;
+                            self.inbox.apply(inbox);
+                            self.set_search_results(search_results);
                             self.filtered_review_pr_indices = (0..review_count).collect();
                             self.filtered_my_pr_indices = (0..my_count).collect();
 
@@ -585,6 +810,8 @@ impl App {
                 && let Ok(result) = receiver.try_recv() {
                     match result {
                         Ok(submitted) => {
+                            // Submitting consumed the pending review, if there was one
+                            self.synced_drafts.clear();
                             self.pending_comments.clear();
                             self.selected_pending_comment = 0;
                             self.save_current_drafts();
@@ -607,7 +834,7 @@ impl App {
                         Ok(thread_index) => {
                             self.loading = LoadingState::Success("Reply submitted!".to_string());
                             // Refresh threads to show new reply
-                            self.load_comment_threads();
+                            self.load_review_state();
                             // Go back to threads list
                             self.comment_mode = CommentMode::ViewingThreads {
                                 selected: thread_index,
@@ -621,6 +848,87 @@ impl App {
                     self.reply_submit_receiver = None;
                 }
 
+            // Check for async thread resolve/unresolve completion
+            if let Some(ref receiver) = self.resolve_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    match result {
+                        Ok((thread_index, resolved)) => {
+                            if let Some(thread) = self.comment_threads.get_mut(thread_index) {
+                                thread.resolved = resolved;
+                            }
+                            let msg = if resolved { "Thread resolved" } else { "Thread unresolved" };
+                            self.loading = LoadingState::Success(msg.to_string());
+                        }
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!("Failed: {}", e));
+                        }
+                    }
+                    self.resolve_receiver = None;
+                }
+
+            // Check for async comment edit/delete completion
+            if let Some(ref receiver) = self.comment_change_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    self.comment_change_receiver = None;
+                    match result {
+                        Ok((thread_index, comment_index, body)) => {
+                            self.apply_comment_change(thread_index, comment_index, body);
+                        }
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!("Failed: {}", e));
+                        }
+                    }
+                }
+
+            // Check for async reaction completion
+            if let Some(ref receiver) = self.reaction_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    self.reaction_receiver = None;
+                    match result {
+                        Ok((target, kind, add)) => {
+                            if let Some(reactions) = self.reactions_mut(target) {
+                                Reaction::apply(reactions, kind, add);
+                            }
+                            let msg = if add { "Reaction added" } else { "Reaction removed" };
+                            self.loading = LoadingState::Success(msg.to_string());
+                        }
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!("Failed: {}", e));
+                        }
+                    }
+                }
+
+            // Check for async pending review sync completion
+            if let Some(ref receiver) = self.pending_sync_receiver
+                && let Ok((done, error)) = receiver.try_recv() {
+                    self.pending_sync_receiver = None;
+                    self.apply_pending_sync(done);
+                    if let Some(e) = error {
+                        self.loading = LoadingState::Error(format!("Failed to sync pending review: {}", e));
+                    } else if self.pending_sync_dirty {
+                        self.sync_pending_review();
+                    }
+                }
+
+            // Check for notification read/done completion
+            if let Some(ref receiver) = self.notification_receiver
+                && let Ok(result) = receiver.try_recv() {
+                    match result {
+                        Ok((thread_id, done)) => {
+                            self.apply_notification_update(&thread_id, done);
+                            // After a review, keep its own message
+                            if self.screen == Screen::PrList {
+                                let msg = if done { "Notification marked as done" } else { "Notification marked as read" };
+                                self.loading = LoadingState::Success(msg.to_string());
+                            }
+                        }
+                        Err(e) => {
+                            self.loading = LoadingState::Error(format!("Failed to update notification: {}", e));
+                        }
+                    }
+                    self.notification_receiver = None;
+                }
+
             // Check for async review submission completion
             if let Some(ref receiver) = self.review_submit_receiver
                 && let Ok(result) = receiver.try_recv() {
@@ -638,8 +946,10 @@ impl App {
                             };
                             self.loading = LoadingState::Success(msg);
                             // Clear pending comments after successful submission
+                            self.synced_drafts.clear();
                             self.pending_comments.clear();
                             self.save_current_drafts();
+                            self.mark_current_pr_notification_done();
                         }
                         Err(e) => {
                             self.loading = LoadingState::Error(format!("Failed: {}", e));
@@ -706,6 +1016,7 @@ impl App {
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     fn handle_key_pr_list(&mut self, key: KeyEvent) {
         // Handle help mode
         if self.help_mode != HelpMode::None {
@@ -740,10 +1051,11 @@ impl App {
         match key.code {
             KeyCode::Char('q') => self.request_quit(),
             KeyCode::Esc => {
-                if self.repo_filter.is_some() {
-                    // Clear repo filter
+                if self.repo_filter.is_some() || self.reason_filter.is_some() {
+                    // Clear repo and reason filters
                     self.repo_filter = None;
                     self.repo_filter_index = 0;
+                    self.reason_filter = None;
                     self.update_filtered_pr_indices();
                 } else if !self.pr_search_query.is_empty() {
                     // Clear search
@@ -771,6 +1083,22 @@ impl App {
                     self.update_filtered_pr_indices();
                 }
             }
+            KeyCode::Char('3') => {
+                // Ignore in author mode (no tabs)
+                if self.author_filter.is_none() {
+                    self.pr_tab = PrListTab::Inbox;
+                    self.update_filtered_pr_indices();
+                }
+            }
+            KeyCode::Char(c @ '4'..='9') => {
+                // Saved search tabs follow the built-in ones
+                if self.author_filter.is_none()
+                    && (c as usize - '4' as usize) < self.search_tabs.len()
+                {
+                    self.pr_tab = PrListTab::Search(c as usize - '4' as usize);
+                    self.update_filtered_pr_indices();
+                }
+            }
             KeyCode::Tab => {
                 // Ignore in author mode (no tabs)
                 if self.author_filter.is_none() {
@@ -780,6 +1108,9 @@ impl App {
             KeyCode::Char('j') | KeyCode::Down => self.move_pr_down(),
             KeyCode::Char('k') | KeyCode::Up => self.move_pr_up(),
             KeyCode::Char('f') => self.cycle_repo_filter(),
+            KeyCode::Char('t') if self.pr_tab == PrListTab::ForReview => self.cycle_reason_filter(),
+            KeyCode::Char('m') if self.pr_tab == PrListTab::Inbox => self.mark_selected_notification(false),
+            KeyCode::Char('d') if self.pr_tab == PrListTab::Inbox => self.mark_selected_notification(true),
             KeyCode::Enter => self.select_pr(),
             KeyCode::Char('o') => self.open_selected_pr_in_browser(),
             KeyCode::Char('R') => self.refresh_pr_list(),
@@ -791,12 +1122,16 @@ impl App {
     fn toggle_pr_tab(&mut self) {
         self.pr_tab = match self.pr_tab {
             PrListTab::ForReview => PrListTab::MyPrs,
-            PrListTab::MyPrs => PrListTab::ForReview,
+            PrListTab::MyPrs => PrListTab::Inbox,
+            PrListTab::Inbox if !self.search_tabs.is_empty() => PrListTab::Search(0),
+            PrListTab::Search(i) if i + 1 < self.search_tabs.len() => PrListTab::Search(i + 1),
+            PrListTab::Inbox | PrListTab::Search(_) => PrListTab::ForReview,
         };
         self.pr_search_query.clear();
         self.update_filtered_pr_indices();
     }
 
+    #[allow(clippy::collapsible_match, clippy::collapsible_if)]
     fn handle_key_diff_view(&mut self, key: KeyEvent) {
         // Handle help mode
         if self.help_mode != HelpMode::None {
@@ -804,9 +1139,32 @@ impl App {
             return;
         }
 
+        // Handle the reaction picker, shown over the description or a thread
+        if let Some(ref mut picker) = self.reaction_picker {
+            let count = ReactionKind::ALL.len();
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.reaction_picker = None,
+                KeyCode::Char('h') | KeyCode::Left => picker.selected = (picker.selected + count - 1) % count,
+                KeyCode::Char('l') | KeyCode::Right => picker.selected = (picker.selected + 1) % count,
+                KeyCode::Char(c @ '1'..='8') => {
+                    let picker = ReactionPicker { selected: c as usize - '1' as usize, ..*picker };
+                    self.toggle_reaction(picker);
+                }
+                KeyCode::Enter | KeyCode::Char(' ') => {
+                    let picker = *picker;
+                    self.toggle_reaction(picker);
+                }
+                _ => {}
+            }
+            return;
+        }
+
         // Handle PR description view
         if self.show_pr_description {
             match key.code {
+                KeyCode::Char('+') if self.pr_node_id.is_some() => {
+                    self.reaction_picker = Some(ReactionPicker { target: ReactionTarget::Pr, selected: 0 });
+                }
                 KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => {
                     self.show_pr_description = false;
                 }
@@ -834,6 +1192,120 @@ impl App {
             return;
         }
 
+        // Handle reviewers/assignees/labels editor
+        if let Some(ref mut editor) = self.metadata_editor {
+            let author = self.current_pr.as_ref().map(|pr| pr.author.as_str()).unwrap_or_default();
+
+            // Typing the name of an entry to add
+            if let Some(ref mut query) = editor.query {
+                let count = editor.edited.completions(editor.field, query, author).len();
+                match key.code {
+                    KeyCode::Esc => editor.query = None,
+                    KeyCode::Enter => {
+                        let chosen = editor
+                            .edited
+                            .completions(editor.field, query, author)
+                            .get(editor.completion)
+                            .map(|&entry| entry.clone());
+                        if let Some(entry) = chosen {
+                            let entries = editor.edited.entries_mut(editor.field);
+                            entries.push(entry);
+                            editor.selected = entries.len() - 1;
+                            editor.query = None;
+                        }
+                    }
+                    KeyCode::Down | KeyCode::Tab if count > 0 => {
+                        editor.completion = (editor.completion + 1) % count;
+                    }
+                    KeyCode::Up | KeyCode::BackTab if count > 0 => {
+                        editor.completion = (editor.completion + count - 1) % count;
+                    }
+                    KeyCode::Backspace => {
+                        query.pop();
+                        editor.completion = 0;
+                    }
+                    KeyCode::Char(c) => {
+                        query.push(c);
+                        editor.completion = 0;
+                    }
+                    _ => {}
+                }
+                return;
+            }
+
+            let count = editor.edited.entries(editor.field).len();
+            let fields = MetadataField::ALL.len();
+            let field_index = MetadataField::ALL.iter().position(|&f| f == editor.field).unwrap_or(0);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.metadata_editor = None,
+                KeyCode::Tab | KeyCode::Char('l') | KeyCode::Right => {
+                    editor.field = MetadataField::ALL[(field_index + 1) % fields];
+                    editor.selected = 0;
+                }
+                KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Left => {
+                    editor.field = MetadataField::ALL[(field_index + fields - 1) % fields];
+                    editor.selected = 0;
+                }
+                KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                    editor.selected = (editor.selected + 1).min(count - 1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    editor.selected = editor.selected.saturating_sub(1);
+                }
+                KeyCode::Char('a') | KeyCode::Char('/') => {
+                    editor.query = Some(String::new());
+                    editor.completion = 0;
+                }
+                KeyCode::Char('d') | KeyCode::Char('x') | KeyCode::Delete if count > 0 => {
+                    editor.edited.entries_mut(editor.field).remove(editor.selected);
+                    editor.selected = editor.selected.min(count.saturating_sub(2));
+                }
+                KeyCode::Enter => {
+                    // Apply only when something changed
+                    if let Some(editor) = self.metadata_editor.take()
+                        && editor.edited.differs_from(&editor.original)
+                    {
+                        self.update_metadata(editor.original, editor.edited);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        // Handle CI checks panel
+        if self.show_checks {
+            let count = match self.checks {
+                Some(Ok(ref checks)) => checks.len(),
+                _ => 0,
+            };
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('K') => {
+                    self.show_checks = false;
+                }
+                KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                    self.selected_check = (self.selected_check + 1).min(count - 1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.selected_check = self.selected_check.saturating_sub(1);
+                }
+                KeyCode::Char('r') => self.load_checks(),
+                KeyCode::Enter | KeyCode::Char('o') => {
+                    if let Some(Ok(ref checks)) = self.checks
+                        && let Some(url) = checks.get(self.selected_check).and_then(|c| c.url.as_deref())
+                    {
+                        let _ = helpers::open_url_command(url)
+                            .stdin(std::process::Stdio::null())
+                            .stdout(std::process::Stdio::null())
+                            .stderr(std::process::Stdio::null())
+                            .spawn();
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
         // Handle comment editing mode input
         if let CommentMode::Editing {
             ref mut text,
@@ -852,23 +1324,22 @@ impl App {
                 // Submit the comment
                 if !text.is_empty() {
                     let comment = match inline_context.clone() {
-                        Some((path, end_line, Some(start_line))) => {
-                            PendingComment::new_multiline(text.clone(), path, start_line, end_line)
-                        }
-                        Some((path, line, None)) => {
-                            PendingComment::new_inline(text.clone(), path, line)
-                        }
+                        Some(target) => target.into_comment(text.clone()),
                         None => PendingComment::new_general(text.clone()),
                     };
                     // If editing an existing comment, replace it; otherwise add new
                     if let Some(idx) = self.editing_comment_index {
                         if idx < self.pending_comments.len() {
+                            // Keep the link to the copy on the pending review
+                            let mut comment = comment;
+                            comment.remote_id = self.pending_comments[idx].remote_id.take();
+                            comment.synced_body = self.pending_comments[idx].synced_body.take();
                             self.pending_comments[idx] = comment;
                         }
                     } else {
                         self.pending_comments.push(comment);
                     }
-                    self.save_current_drafts();
+                    self.drafts_changed();
                 }
                 self.comment_mode = CommentMode::None;
                 self.editing_comment_index = None;
@@ -923,7 +1394,7 @@ impl App {
                             self.selected_pending_comment =
                                 self.pending_comments.len().saturating_sub(1);
                         }
-                        self.save_current_drafts();
+                        self.drafts_changed();
                         if self.pending_comments.is_empty() {
                             self.comment_mode = CommentMode::None;
                         }
@@ -937,13 +1408,7 @@ impl App {
                     // Edit selected comment
                     if !self.pending_comments.is_empty() {
                         let comment = &self.pending_comments[self.selected_pending_comment];
-                        let inline_context = if let (Some(path), Some(line)) =
-                            (comment.file_path.clone(), comment.line_number)
-                        {
-                            Some((path, line, comment.start_line))
-                        } else {
-                            None
-                        };
+                        let inline_context = InlineTarget::of(comment);
                         self.editing_comment_index = Some(self.selected_pending_comment);
                         self.comment_mode = CommentMode::Editing {
                             text: comment.body.clone(),
@@ -969,6 +1434,14 @@ impl App {
                 KeyCode::Esc | KeyCode::Char('q') => {
                     self.comment_mode = CommentMode::None;
                 }
+                KeyCode::Char('a') => {
+                    // Toggle between unresolved threads and all threads
+                    self.show_resolved_threads = !self.show_resolved_threads;
+                    self.comment_mode = CommentMode::ViewingThreads {
+                        selected: 0,
+                        scroll: 0,
+                    };
+                }
                 KeyCode::Char('j') | KeyCode::Down => {
                     if visual_count > 0 && current_selected < visual_count - 1 {
                         self.comment_mode = CommentMode::ViewingThreads {
@@ -988,11 +1461,7 @@ impl App {
                 KeyCode::Enter => {
                     // Open thread detail view - convert visual index to original index
                     if let Some(idx) = thread_idx {
-                        self.comment_mode = CommentMode::ViewingThread {
-                            index: idx,
-                            selected: current_selected, // Store visual index for returning
-                            scroll: 0,
-                        };
+                        self.comment_mode = self.thread_view(idx);
                     }
                 }
                 KeyCode::Char('r') => {
@@ -1001,6 +1470,7 @@ impl App {
                         self.comment_mode = CommentMode::ReplyingToThread {
                             index: idx,
                             text: String::new(),
+                            editing: None,
                         };
                     }
                 }
@@ -1036,8 +1506,19 @@ impl App {
             index,
             selected: visual_idx,
             ref mut scroll,
+            ref mut comment,
+            ref mut deleting,
         } = self.comment_mode
         {
+            // Confirming deletion of the highlighted comment: y deletes, anything else cancels
+            if *deleting {
+                *deleting = false;
+                if let (KeyCode::Char('y'), Some(comment_index)) = (key.code, *comment) {
+                    self.delete_thread_comment(index, comment_index);
+                }
+                return;
+            }
+
             // Calculate max scroll (total lines - visible height)
             // Use approximate visible height of 20 lines for popup
             let max_scroll = self.comment_threads.get(index).map(|thread| {
@@ -1054,11 +1535,17 @@ impl App {
             match key.code {
                 KeyCode::Esc | KeyCode::Char('q') => {
                     // Go back to thread list - use stored visual index
+                    // (clamped: resolving the thread may have hidden it)
+                    let visual_count = self.thread_visual_order().len();
                     self.comment_mode = CommentMode::ViewingThreads {
-                        selected: visual_idx,
+                        selected: visual_idx.min(visual_count.saturating_sub(1)),
                         scroll: 0,
                     };
                 }
+                KeyCode::Char('x') => {
+                    let idx = index;
+                    self.toggle_thread_resolved(idx);
+                }
                 KeyCode::Char('j') | KeyCode::Down => {
                     *scroll = scroll.saturating_add(1).min(max_scroll);
                 }
@@ -1076,8 +1563,51 @@ impl App {
                     self.comment_mode = CommentMode::ReplyingToThread {
                         index: idx,
                         text: String::new(),
+                        editing: None,
                     };
                 }
+                KeyCode::Tab => {
+                    // Highlight the next comment
+                    if let (Some(current), Some(thread)) = (*comment, self.comment_threads.get(index)) {
+                        *comment = Some((current + 1) % thread.comments.len().max(1));
+                    }
+                }
+                KeyCode::Char('e') => {
+                    if let Some(comment_index) = *comment
+                        && let Some(existing) = self
+                            .comment_threads
+                            .get(index)
+                            .and_then(|t| t.comments.get(comment_index))
+                            .filter(|c| Self::is_own(c))
+                    {
+                        self.comment_mode = CommentMode::ReplyingToThread {
+                            index,
+                            text: existing.body.clone(),
+                            editing: Some(comment_index),
+                        };
+                    }
+                }
+                KeyCode::Char('D')
+                    if comment
+                        .and_then(|c| self.comment_threads.get(index)?.comments.get(c))
+                        .is_some_and(Self::is_own) =>
+                {
+                    *deleting = true;
+                }
+                KeyCode::Char('+') => {
+                    if let Some(comment_index) = *comment
+                        && self
+                            .comment_threads
+                            .get(index)
+                            .and_then(|t| t.comments.get(comment_index))
+                            .is_some_and(|c| c.node_id.is_some())
+                    {
+                        self.reaction_picker = Some(ReactionPicker {
+                            target: ReactionTarget::Comment { thread: index, comment: comment_index },
+                            selected: 0,
+                        });
+                    }
+                }
                 _ => {}
             }
             return;
@@ -1087,6 +1617,7 @@ impl App {
         if let CommentMode::ReplyingToThread {
             index,
             ref mut text,
+            editing,
         } = self.comment_mode
         {
             // Check for save shortcuts: Ctrl+Enter, Ctrl+S, or Alt+Enter
@@ -1100,18 +1631,16 @@ impl App {
             if is_save && !text.is_empty() {
                 let idx = index;
                 let reply_text = text.clone();
-                self.submit_thread_reply(idx, &reply_text);
+                match editing {
+                    Some(comment_index) => self.edit_thread_comment(idx, comment_index, reply_text),
+                    None => self.submit_thread_reply(idx, &reply_text),
+                }
                 return;
             }
 
             match key.code {
                 KeyCode::Esc => {
-                    let idx = index;
-                    self.comment_mode = CommentMode::ViewingThread {
-                        index: idx,
-                        selected: 0,
-                        scroll: 0,
-                    };
+                    self.comment_mode = self.thread_view(index);
                 }
                 KeyCode::Enter => {
                     text.push('\n');
@@ -1169,7 +1698,7 @@ impl App {
                     match key.code {
                         KeyCode::Esc => {
                             *editing_draft = false;
-                            self.save_current_drafts();
+                            self.drafts_changed();
                         }
                         KeyCode::Enter => {
                             self.pending_comments[draft_idx].body.push('\n');
@@ -1221,7 +1750,7 @@ impl App {
                             {
                                 *selected_draft = self.pending_comments.len() - 1;
                             }
-                            self.save_current_drafts();
+                            self.drafts_changed();
                         }
                     }
                     _ => {}
@@ -1285,6 +1814,103 @@ impl App {
             return;
         }
 
+        // Handle merge dialog
+        if let CommentMode::Merging {
+            ref info,
+            ref mut method,
+            ref mut title,
+            ref mut body,
+            ref mut editing,
+            ref mut delete_branch,
+            ref mut auto_merge,
+        } = self.comment_mode
+        {
+            let is_submit = match key.code {
+                KeyCode::Enter if key.modifiers.contains(KeyModifiers::CONTROL) => true,
+                KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) => true,
+                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => true,
+                _ => false,
+            };
+            if is_submit {
+                let Some(&merge_method) = info.methods.get(*method) else {
+                    return;
+                };
+                let ready = if *auto_merge {
+                    info.status.can_auto_merge() && !info.auto_merge_enabled
+                } else {
+                    info.status.can_merge()
+                };
+                if !ready {
+                    self.loading = LoadingState::Error(if info.auto_merge_enabled && *auto_merge {
+                        "Auto-merge is already enabled".to_string()
+                    } else {
+                        info.status.description().to_string()
+                    });
+                    return;
+                }
+                let request = MergeRequest {
+                    method: merge_method,
+                    title: title.clone(),
+                    body: body.clone(),
+                    delete_branch: *delete_branch && !*auto_merge,
+                    auto_merge: *auto_merge,
+                    head_sha: self.current_pr.as_ref().and_then(|pr| pr.head_sha.clone()),
+                };
+                self.merge_pr(request);
+                return;
+            }
+
+            match editing {
+                Some(field) => match key.code {
+                    KeyCode::Esc => *editing = None,
+                    KeyCode::Enter if *field == MergeField::Title => *editing = None,
+                    KeyCode::Enter => body.push('\n'),
+                    KeyCode::Backspace => {
+                        match field {
+                            MergeField::Title => title.pop(),
+                            MergeField::Body => body.pop(),
+                        };
+                    }
+                    KeyCode::Char(c) => match field {
+                        MergeField::Title => title.push(c),
+                        MergeField::Body => body.push(c),
+                    },
+                    _ => {}
+                },
+                None => {
+                    let count = info.methods.len();
+                    let previous = *method;
+                    match key.code {
+                        KeyCode::Esc => {
+                            self.comment_mode = CommentMode::None;
+                            return;
+                        }
+                        KeyCode::Char('j') | KeyCode::Down if count > 0 => *method = (*method + 1) % count,
+                        KeyCode::Char('k') | KeyCode::Up if count > 0 => *method = (*method + count - 1) % count,
+                        KeyCode::Char(c @ '1'..='3') if (c as usize - '1' as usize) < count => {
+                            *method = c as usize - '1' as usize;
+                        }
+                        KeyCode::Char('t') => *editing = Some(MergeField::Title),
+                        KeyCode::Char('c') | KeyCode::Enter => *editing = Some(MergeField::Body),
+                        KeyCode::Char('d') if info.can_delete_branch && !info.deletes_branch_on_merge => {
+                            *delete_branch = !*delete_branch;
+                        }
+                        KeyCode::Char('a') if info.auto_merge_allowed => *auto_merge = !*auto_merge,
+                        _ => {}
+                    }
+
+                    // Switch to the new method's default message, unless it was edited
+                    if *method != previous
+                        && let (Some(&old), Some(&new)) = (info.methods.get(previous), info.methods.get(*method))
+                        && (title.clone(), body.clone()) == info.message(old)
+                    {
+                        (*title, *body) = info.message(new);
+                    }
+                }
+            }
+            return;
+        }
+
         // Handle search mode input
         if self.search_mode {
             match key.code {
@@ -1384,44 +2010,72 @@ impl App {
             }
             KeyCode::Char('c') => {
                 // Start new comment
-                if self.current_pr.is_some() {
+                if self.current_pr.is_some() || self.local_review.is_some() {
                     let inline_context = if self.focus == Focus::Diff {
                         if self.visual_mode {
                             // Multi-line selection
-                            if let Some((path, start, end)) = self.get_selection_line_info() {
-                                if start == end {
-                                    Some((path, end, None)) // Single line
-                                } else {
-                                    Some((path, end, Some(start))) // Range
-                                }
-                            } else {
-                                None
-                            }
+                            self.get_selection_target()
                         } else {
                             // Single line
-                            self.get_cursor_line_info().map(|(p, l)| (p, l, None))
+                            self.get_cursor_line_info().map(|(path, line, side)| InlineTarget {
+                                path,
+                                line: Some((line, side)),
+                                start: None,
+                            })
                         }
                     } else {
                         None
                     };
                     self.visual_mode = false; // Exit visual mode when commenting
-                    self.comment_mode = CommentMode::Editing {
-                        text: String::new(),
-                        inline_context,
-                    };
+                    self.begin_comment(String::new(), inline_context);
                 }
             }
-            KeyCode::Char('C') => {
-                // View pending comments
-                if !self.pending_comments.is_empty() {
-                    self.comment_mode = CommentMode::ViewingPending;
-                    self.selected_pending_comment = 0;
+            KeyCode::Char('F') => {
+                // Comment on the whole file (the current one, or the one selected in the tree)
+                if (self.current_pr.is_some() || self.local_review.is_some())
+                    && self.selected_tree_item.is_none()
+                {
+                    if let Some(file) = self.files.get(self.selected_file) {
+                        self.visual_mode = false;
+                        let target = InlineTarget::file(file.path.clone());
+                        self.begin_comment(String::new(), Some(target));
+                    }
                 }
             }
-            KeyCode::Char('S') => {
-                // Submit all pending comments
-                if !self.pending_comments.is_empty() && self.current_pr.is_some() {
-                    self.submit_pending_comments();
+            KeyCode::Char('s') => {
+                // Suggest a change to the selected lines
+                if self.visual_mode
+                    && (self.current_pr.is_some() || self.local_review.is_some())
+                {
+                    self.visual_mode = false;
+                    match self.get_selection_new_lines() {
+                        Ok((path, start, end, lines)) => {
+                            let target = InlineTarget {
+                                path,
+                                line: Some((end, DiffSide::Right)),
+                                start: (start != end).then_some((start, DiffSide::Right)),
+                            };
+                            self.begin_comment(helpers::suggestion_body(&lines), Some(target));
+                        }
+                        Err(reason) => {
+                            self.loading = LoadingState::Error(reason.to_string());
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('C') => {
+                // View pending comments
+                if !self.pending_comments.is_empty() {
+                    self.comment_mode = CommentMode::ViewingPending;
+                    self.selected_pending_comment = 0;
+                }
+            }
+            KeyCode::Char('S') => {
+                // Submit all pending comments (export notes in local mode)
+                if !self.pending_comments.is_empty()
+                    && (self.current_pr.is_some() || self.local_review.is_some())
+                {
+                    self.submit_pending_comments();
                 }
             }
             KeyCode::Char('t') => {
@@ -1436,12 +2090,50 @@ impl App {
             KeyCode::Char('T') => {
                 // Refresh comment threads
                 if self.current_pr.is_some() {
-                    self.load_comment_threads();
+                    self.load_review_state();
+                }
+            }
+            KeyCode::Char('n') => self.jump_to_annotation(true),
+            KeyCode::Char('N') => self.jump_to_annotation(false),
+            KeyCode::Char('E') => {
+                // Expand/collapse the annotation messages of the cursor line
+                self.expand_annotations = !self.expand_annotations;
+            }
+            KeyCode::Char('I') => {
+                // Toggle the changes since the last review
+                if self.current_pr.is_some() && self.compare_receiver.is_none() {
+                    self.toggle_since_review();
+                }
+            }
+            KeyCode::Char('M') => {
+                // Merge the PR, once the host reported whether it can be merged
+                if self.current_pr.is_some() {
+                    self.load_merge_info();
+                }
+            }
+            KeyCode::Char('m') => {
+                // Edit requested reviewers, assignees and labels
+                if self.current_pr.is_some() {
+                    self.load_metadata();
+                }
+            }
+            KeyCode::Char('K') => {
+                // View CI checks
+                if self.current_pr.is_some() {
+                    self.show_checks = true;
+                    self.selected_check = 0;
+                    if self.checks.is_none() && self.checks_receiver.is_none() {
+                        self.load_checks();
+                    }
                 }
             }
             KeyCode::Char('A') => {
                 // Open review submission modal
                 if self.current_pr.is_some() {
+                    // Needed to warn about approving with failing checks
+                    if self.checks.is_none() && self.checks_receiver.is_none() {
+                        self.load_checks();
+                    }
                     self.comment_mode = CommentMode::SubmittingReview {
                         selected_action: 0,
                         body: String::new(),
@@ -1459,9 +2151,24 @@ impl App {
         }
     }
 
+    /// Open the comment editor, unless the target can't be placed on the PR's diff
+    /// Comments are anchored on the PR's head, so while the changes since the last review are shown
+    /// they must land on new-side lines that are also in the PR's whole diff
+    fn begin_comment(&mut self, text: String, inline_context: Option<InlineTarget>) {
+        if let (Some(full_files), Some(target)) = (&self.full_files, &inline_context)
+            && !helpers::head_target_in_diff(full_files, target)
+        {
+            self.loading = LoadingState::Error(
+                "Only lines of the PR's diff can be commented on; press I for the whole diff".to_string(),
+            );
+            return;
+        }
+        self.comment_mode = CommentMode::Editing { text, inline_context };
+    }
+
     fn open_pr_in_browser(&self) {
         if let Some(ref pr) = self.current_pr {
-            self.open_pr_url_in_browser(&pr.repo_full_name(), pr.number);
+            self.open_pr_url_in_browser(pr);
         }
     }
 
@@ -1473,13 +2180,20 @@ impl App {
         let pr_list = self.current_pr_list();
         let selected = self.current_selected_pr();
         let pr = &pr_list[selected];
-        self.open_pr_url_in_browser(&pr.repo_full_name(), pr.number);
+        self.open_pr_url_in_browser(pr);
     }
 
-    fn open_pr_url_in_browser(&self, repo: &str, number: u32) {
-        // Use gh CLI to open PR in browser
-        let _ = std::process::Command::new("gh")
-            .args(["pr", "view", &number.to_string(), "--repo", repo, "--web"])
+    fn open_pr_url_in_browser(&self, pr: &ReviewPr) {
+        let mut command = match crate::forge::backend_for(&pr.host).web_url(&pr.to_pr_info()) {
+            Some(url) => helpers::open_url_command(&url),
+            None => {
+                // Use gh CLI to open PR in browser
+                let mut command = std::process::Command::new("gh");
+                command.args(["pr", "view", &pr.number.to_string(), "--repo", &pr.repo_full_name(), "--web"]);
+                command
+            }
+        };
+        let _ = command
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
@@ -1491,6 +2205,17 @@ impl App {
         match self.pr_tab {
             PrListTab::ForReview => &self.review_prs,
             PrListTab::MyPrs => &self.my_prs,
+            PrListTab::Inbox => &self.inbox.prs,
+            PrListTab::Search(i) => &self.search_tabs[i].list.prs,
+        }
+    }
+
+    /// State of the active tab when it is the inbox or a saved search
+    fn extra_list_state(&self) -> Option<&PrListState> {
+        match self.pr_tab {
+            PrListTab::ForReview | PrListTab::MyPrs => None,
+            PrListTab::Inbox => Some(&self.inbox),
+            PrListTab::Search(i) => Some(&self.search_tabs[i].list),
         }
     }
 
@@ -1498,6 +2223,8 @@ impl App {
         match self.pr_tab {
             PrListTab::ForReview => &self.filtered_review_pr_indices,
             PrListTab::MyPrs => &self.filtered_my_pr_indices,
+            PrListTab::Inbox => &self.inbox.filtered_indices,
+            PrListTab::Search(i) => &self.search_tabs[i].list.filtered_indices,
         }
     }
 
@@ -1505,6 +2232,8 @@ impl App {
         match self.pr_tab {
             PrListTab::ForReview => self.selected_review_pr,
             PrListTab::MyPrs => self.selected_my_pr,
+            PrListTab::Inbox => self.inbox.selected,
+            PrListTab::Search(i) => self.search_tabs[i].list.selected,
         }
     }
 
@@ -1512,6 +2241,8 @@ impl App {
         match self.pr_tab {
             PrListTab::ForReview => self.selected_review_pr = idx,
             PrListTab::MyPrs => self.selected_my_pr = idx,
+            PrListTab::Inbox => self.inbox.selected = idx,
+            PrListTab::Search(i) => self.search_tabs[i].list.selected = idx,
         }
     }
 
@@ -1555,6 +2286,23 @@ impl App {
         self.update_filtered_pr_indices();
     }
 
+    /// Cycle the "For Review" list through each reason PRs are listed for, then back to all
+    fn cycle_reason_filter(&mut self) {
+        let mut reasons: Vec<ReviewReason> = self
+            .review_prs
+            .iter()
+            .flat_map(|pr| pr.review_reasons.iter().cloned())
+            .collect();
+        reasons.sort();
+        reasons.dedup();
+
+        self.reason_filter = match &self.reason_filter {
+            None => reasons.into_iter().next(),
+            Some(current) => reasons.into_iter().find(|reason| reason > current),
+        };
+        self.update_filtered_pr_indices();
+    }
+
     fn update_filtered_pr_indices(&mut self) {
         let query = self.pr_search_query.to_lowercase();
 
@@ -1564,6 +2312,11 @@ impl App {
             .iter()
             .enumerate()
             .filter(|(_, pr)| self.pr_matches_filter(pr, &query))
+            .filter(|(_, pr)| {
+                self.reason_filter
+                    .as_ref()
+                    .is_none_or(|reason| pr.review_reasons.contains(reason))
+            })
             .map(|(i, _)| i)
             .collect();
 
@@ -1576,6 +2329,34 @@ impl App {
             .map(|(i, _)| i)
             .collect();
 
+        // Update inbox filter
+        self.inbox.filtered_indices = self
+            .inbox
+            .prs
+            .iter()
+            .enumerate()
+            .filter(|(_, pr)| self.pr_matches_filter(pr, &query))
+            .map(|(i, _)| i)
+            .collect();
+
+        // Update saved search tabs
+        let search_indices: Vec<Vec<usize>> = self
+            .search_tabs
+            .iter()
+            .map(|tab| {
+                tab.list
+                    .prs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pr)| self.pr_matches_filter(pr, &query))
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+        for (tab, indices) in self.search_tabs.iter_mut().zip(search_indices) {
+            tab.list.filtered_indices = indices;
+        }
+
         // Ensure selected PR is in filtered list for current tab
         match self.pr_tab {
             PrListTab::ForReview => {
@@ -1594,6 +2375,17 @@ impl App {
                     }
                 self.my_pr_scroll = 0;
             }
+            PrListTab::Inbox | PrListTab::Search(_) => {
+                let tab = match self.pr_tab {
+                    PrListTab::Search(i) => &mut self.search_tabs[i].list,
+                    _ => &mut self.inbox,
+                };
+                if !tab.filtered_indices.contains(&tab.selected)
+                    && let Some(&first) = tab.filtered_indices.first() {
+                        tab.selected = first;
+                    }
+                tab.scroll = 0;
+            }
         }
     }
 
@@ -1640,21 +2432,19 @@ impl App {
 
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
             let result = rt.block_on(async {
-                // Fetch diff and PR details (including body) in parallel
-                let (diff_result, details_result) = tokio::join!(
-                    crate::github::fetch_pr_diff(&pr_info),
-                    crate::github::fetch_pr_details(&pr_info)
+                // Fetch diff and review state (details, threads, reviews) in parallel
+                let (diff_result, state_result) = tokio::join!(
+                    forge.fetch_pr_diff(&pr_info),
+                    forge.fetch_review_state(&pr_info)
                 );
 
-                // Diff is required, details are optional (for head_sha and body)
+                // Diff is required, the review state is optional (for head_sha, body and threads)
                 match diff_result {
                     Ok(diff_content) => {
                         let files = crate::parser::parse_diff(&diff_content);
-                        let (head_sha, body) = details_result
-                            .map(|d| (d.head_sha, d.body))
-                            .unwrap_or((None, None));
-                        Ok((files, head_sha, body))
+                        Ok((files, state_result.ok()))
                     }
                     Err(e) => Err(e.to_string()),
                 }
@@ -1669,57 +2459,103 @@ impl App {
         self.cache_age = Some(age);
     }
 
+    /// Mark lists that were cut short by the configured PR cap
+    pub fn set_truncated(&mut self, review_truncated: bool, my_truncated: bool) {
+        self.review_truncated = review_truncated;
+        self.my_truncated = my_truncated;
+    }
+
+    /// Hosts that failed while the others' PRs were listed
+    pub fn set_host_errors(&mut self, errors: Vec<String>) {
+        self.host_errors = errors;
+    }
+
+    /// Show the cached results of each saved search tab
+    pub fn set_cached_searches(&mut self, searches: &HashMap<String, Vec<ReviewPr>>) {
+        for tab in &mut self.search_tabs {
+            if let Some(prs) = searches.get(&tab.query) {
+                tab.list.set_results(PrList { prs: prs.clone(), ..Default::default() });
+            }
+        }
+        self.update_available_repos();
+    }
+
+    /// Show the fetched (or cached) notifications in the inbox tab
+    pub fn set_inbox(&mut self, result: Result<PrList, String>) {
+        self.inbox.apply(result);
+        self.update_available_repos();
+    }
+
+    /// Apply fetched saved search results, one per tab in order
+    /// A failed search keeps its previous results and records the error
+    pub fn set_search_results(&mut self, results: Vec<Result<PrList, String>>) {
+        for (tab, result) in self.search_tabs.iter_mut().zip(results) {
+            tab.list.apply(result);
+        }
+        self.update_available_repos();
+    }
+
+    /// Collect the repos of every listed PR for the repo filter
+    fn update_available_repos(&mut self) {
+        let mut repos: Vec<String> = self
+            .review_prs
+            .iter()
+            .chain(self.my_prs.iter())
+            .chain(self.inbox.prs.iter())
+            .chain(self.search_tabs.iter().flat_map(|tab| tab.list.prs.iter()))
+            .map(|pr| pr.repo_full_name())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        repos.sort();
+        self.available_repos = repos;
+    }
+
     /// Trigger a background refresh without showing loading state
     /// Used when starting with cached data
     pub fn trigger_background_refresh(&mut self) {
         self.background_refreshing = true;
-        let (tx, rx) = mpsc::channel();
-        self.pr_list_receiver = Some(rx);
-
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let result = rt.block_on(async {
-                let (review_result, my_result) = tokio::join!(
-                    crate::github::fetch_review_prs(),
-                    crate::github::fetch_my_prs()
-                );
-
-                match (review_result, my_result) {
-                    (Ok(review_prs), Ok(my_prs)) => {
-                        // Save to cache
-                        crate::cache::save_cache(&review_prs, &my_prs);
-                        Ok((review_prs, my_prs))
-                    }
-                    (Err(e), _) => Err(e.to_string()),
-                    (_, Err(e)) => Err(e.to_string()),
-                }
-            });
-
-            let _ = tx.send(result);
-        });
+        self.spawn_pr_list_fetch();
     }
 
     fn refresh_pr_list(&mut self) {
         // Non-blocking async refresh - results are processed in event_loop
         self.loading = LoadingState::Loading("Refreshing PRs...".to_string());
+        self.spawn_pr_list_fetch();
+    }
 
+    /// Fetch both lists and every saved search in a single background thread
+    fn spawn_pr_list_fetch(&mut self) {
         let (tx, rx) = mpsc::channel();
         self.pr_list_receiver = Some(rx);
+        let queries: Vec<String> = self.search_tabs.iter().map(|tab| tab.query.clone()).collect();
 
-        // Fetch both lists in parallel in a single background thread
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
+            let hosts = crate::forge::forge_hosts();
             let result = rt.block_on(async {
-                let (review_result, my_result) = tokio::join!(
-                    crate::github::fetch_review_prs(),
-                    crate::github::fetch_my_prs()
+                let (review_result, my_result, searches, inbox) = tokio::join!(
+                    crate::forge::fetch_review_prs_all(&hosts),
+                    crate::forge::fetch_my_prs_all(&hosts),
+                    crate::forge::fetch_search_prs_all(&hosts, &queries),
+                    crate::forge::fetch_notifications_all(&hosts)
                 );
 
                 match (review_result, my_result) {
                     (Ok(review_prs), Ok(my_prs)) => {
                         // Save to cache
-                        crate::cache::save_cache(&review_prs, &my_prs);
-                        Ok((review_prs, my_prs))
+                        crate::cache::save_cache(
+                            &review_prs.prs,
+                            &my_prs.prs,
+                            crate::cache::search_results(&queries, &searches),
+                            inbox.as_ref().map(|list| list.prs.as_slice()).unwrap_or_default(),
+                        );
+                        Ok(PrListRefresh {
+                            review: review_prs,
+                            my: my_prs,
+                            searches: searches.into_iter().map(|r| r.map_err(|e| e.to_string())).collect(),
+                            inbox: inbox.map_err(|e| e.to_string()),
+                        })
                     }
                     (Err(e), _) => Err(e.to_string()),
                     (_, Err(e)) => Err(e.to_string()),
@@ -1731,14 +2567,28 @@ impl App {
     }
 
     fn submit_pending_comments(&mut self) {
-        let Some(ref pr) = self.current_pr else {
+        if self.pending_comments.is_empty() {
             return;
-        };
+        }
 
-        if self.pending_comments.is_empty() {
+        // Local reviews have nowhere to submit to, so notes are exported instead
+        if let Some(ref review) = self.local_review {
+            self.loading = match crate::local::export_notes(review, &self.pending_comments) {
+                Ok(path) => LoadingState::Success(format!(
+                    "Exported {} note(s) to {}",
+                    self.pending_comments.len(),
+                    path.display()
+                )),
+                Err(e) => LoadingState::Error(e),
+            };
+            self.comment_mode = CommentMode::None;
             return;
         }
 
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+
         let pr_info = pr.to_pr_info();
         let comments = self.pending_comments.clone();
         let count = comments.len();
@@ -1753,9 +2603,15 @@ impl App {
         // Submit comments in a separate thread - results processed in event_loop
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
             let result = rt.block_on(async {
-                crate::github::submit_pr_comments(&pr_info, &comments, head_sha.as_deref()).await
+                forge.submit_comments(&pr_info, &comments, head_sha.as_deref()).await
             });
+            if result.is_ok()
+                && let Some(ref head_sha) = head_sha
+            {
+                let _ = crate::drafts::save_reviewed_head(&pr_info, head_sha);
+            }
 
             let _ = tx.send(result.map_err(|e| e.to_string()));
         });
@@ -1781,7 +2637,8 @@ impl App {
         // Submit in a separate thread - results processed in event_loop
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
-            let result = rt.block_on(crate::github::submit_thread_reply(
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.submit_thread_reply(
                 &pr_info,
                 &thread,
                 &body_clone,
@@ -1792,6 +2649,212 @@ impl App {
         });
     }
 
+    /// Reactions shown for a picker target
+    fn reactions(&self, target: ReactionTarget) -> Option<&[Reaction]> {
+        match target {
+            ReactionTarget::Pr => Some(&self.pr_reactions),
+            ReactionTarget::Comment { thread, comment } => self
+                .comment_threads
+                .get(thread)?
+                .comments
+                .get(comment)
+                .map(|c| c.reactions.as_slice()),
+        }
+    }
+
+    fn reactions_mut(&mut self, target: ReactionTarget) -> Option<&mut Vec<Reaction>> {
+        match target {
+            ReactionTarget::Pr => Some(&mut self.pr_reactions),
+            ReactionTarget::Comment { thread, comment } => self
+                .comment_threads
+                .get_mut(thread)?
+                .comments
+                .get_mut(comment)
+                .map(|c| &mut c.reactions),
+        }
+    }
+
+    /// Add the reaction selected in the picker, or remove it if the user already left it
+    fn toggle_reaction(&mut self, picker: ReactionPicker) {
+        self.reaction_picker = None;
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let subject_id = match picker.target {
+            ReactionTarget::Pr => self.pr_node_id.clone(),
+            ReactionTarget::Comment { thread, comment } => self
+                .comment_threads
+                .get(thread)
+                .and_then(|t| t.comments.get(comment))
+                .and_then(|c| c.node_id.clone()),
+        };
+        let Some(subject_id) = subject_id else {
+            return;
+        };
+        let Some(&kind) = ReactionKind::ALL.get(picker.selected) else {
+            return;
+        };
+        let add = !self
+            .reactions(picker.target)
+            .unwrap_or_default()
+            .iter()
+            .any(|r| r.kind == kind && r.by_viewer);
+
+        let host = pr.host.clone();
+        self.loading = LoadingState::Loading(if add {
+            "Adding reaction...".to_string()
+        } else {
+            "Removing reaction...".to_string()
+        });
+
+        let (tx, rx) = mpsc::channel();
+        self.reaction_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&host);
+            let result = rt.block_on(forge.set_reaction(&subject_id, kind, add));
+
+            let _ = tx.send(result.map(|_| (picker.target, kind, add)).map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Whether a comment is the user's own, and can be edited or deleted
+    fn is_own(comment: &ThreadComment) -> bool {
+        comment.by_viewer && comment.id != 0
+    }
+
+    /// Detail view of a thread, highlighting the user's latest comment in it (or the first one)
+    fn thread_view(&self, thread_index: usize) -> CommentMode {
+        let selected = self
+            .thread_visual_order()
+            .iter()
+            .position(|&i| i == thread_index)
+            .unwrap_or(0);
+        let comment = self.comment_threads.get(thread_index).and_then(|thread| {
+            thread
+                .comments
+                .iter()
+                .rposition(Self::is_own)
+                .or((!thread.comments.is_empty()).then_some(0))
+        });
+        CommentMode::ViewingThread {
+            index: thread_index,
+            selected,
+            scroll: 0,
+            comment,
+            deleting: false,
+        }
+    }
+
+    /// Replace the text of one of the user's comments on the host
+    fn edit_thread_comment(&mut self, thread_index: usize, comment_index: usize, body: String) {
+        self.change_thread_comment(thread_index, comment_index, Some(body));
+    }
+
+    /// Delete one of the user's comments on the host
+    fn delete_thread_comment(&mut self, thread_index: usize, comment_index: usize) {
+        self.change_thread_comment(thread_index, comment_index, None);
+    }
+
+    /// Edit (with a new body) or delete (without) a submitted comment
+    fn change_thread_comment(&mut self, thread_index: usize, comment_index: usize, body: Option<String>) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let Some(thread) = self.comment_threads.get(thread_index).cloned() else {
+            return;
+        };
+        let Some(comment_id) = thread.comments.get(comment_index).map(|c| c.id) else {
+            return;
+        };
+
+        let pr_info = pr.to_pr_info();
+        self.loading = LoadingState::Loading(if body.is_some() {
+            "Updating comment...".to_string()
+        } else {
+            "Deleting comment...".to_string()
+        });
+
+        let (tx, rx) = mpsc::channel();
+        self.comment_change_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(async {
+                match &body {
+                    Some(body) => forge.edit_comment(&pr_info, &thread, comment_id, body).await,
+                    None => forge.delete_comment(&pr_info, &thread, comment_id).await,
+                }
+            });
+
+            let _ = tx.send(
+                result
+                    .map(|_| (thread_index, comment_index, body))
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    }
+
+    /// Show an edited or deleted comment in the thread it belongs to
+    fn apply_comment_change(&mut self, thread_index: usize, comment_index: usize, body: Option<String>) {
+        let Some(thread) = self.comment_threads.get_mut(thread_index) else {
+            return;
+        };
+        if comment_index >= thread.comments.len() {
+            return;
+        }
+
+        match body {
+            Some(body) => {
+                thread.comments[comment_index].body = body;
+                self.loading = LoadingState::Success("Comment updated".to_string());
+            }
+            None => {
+                thread.comments.remove(comment_index);
+                self.loading = LoadingState::Success("Comment deleted".to_string());
+                if thread.comments.is_empty() {
+                    // The thread is gone on the host too; reload to drop it
+                    self.load_review_state();
+                    self.comment_mode = CommentMode::ViewingThreads { selected: 0, scroll: 0 };
+                    return;
+                }
+            }
+        }
+        self.comment_mode = self.thread_view(thread_index);
+    }
+
+    /// Resolve an unresolved thread, or unresolve a resolved one
+    fn toggle_thread_resolved(&mut self, thread_index: usize) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let Some(thread) = self.comment_threads.get(thread_index).cloned() else {
+            return;
+        };
+
+        let pr_info = pr.to_pr_info();
+        let resolved = !thread.resolved;
+
+        self.loading = LoadingState::Loading(if resolved {
+            "Resolving thread...".to_string()
+        } else {
+            "Unresolving thread...".to_string()
+        });
+
+        let (tx, rx) = mpsc::channel();
+        self.resolve_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.set_thread_resolved(&pr_info, &thread, resolved));
+
+            let _ = tx.send(result.map(|_| (thread_index, resolved)).map_err(|e| e.to_string()));
+        });
+    }
+
     /// Submit a PR review (approve/request changes/comment) with all pending comments
     fn submit_review(&mut self, action: usize, body: Option<String>) {
         let Some(ref pr) = self.current_pr else {
@@ -1818,88 +2881,531 @@ impl App {
         };
 
         let pr_info = pr.to_pr_info();
-        let head_sha = pr.head_sha.clone();
-        let pending_comments = self.pending_comments.clone();
-
-        self.loading = LoadingState::Loading(loading_msg);
-        self.comment_mode = CommentMode::None;
+        let head_sha = pr.head_sha.clone();
+        let pending_comments = self.pending_comments.clone();
+
+        self.loading = LoadingState::Loading(loading_msg);
+        self.comment_mode = CommentMode::None;
+
+        let (tx, rx) = mpsc::channel();
+        self.review_submit_receiver = Some(rx);
+
+        let event_str = event.to_string();
+        let body_clone = body;
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let comments_opt = if pending_comments.is_empty() {
+                None
+            } else {
+                Some(pending_comments.as_slice())
+            };
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.submit_review(
+                &pr_info,
+                &event_str,
+                body_clone.as_deref(),
+                comments_opt,
+                head_sha.as_deref(),
+            ));
+            if result.is_ok()
+                && let Some(ref head_sha) = head_sha
+            {
+                let _ = crate::drafts::save_reviewed_head(&pr_info, head_sha);
+            }
+
+            // Return (event, comments_submitted) on success
+            let _ = tx.send(
+                result
+                    .map(|count| (event_str, count))
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    }
+
+    /// Mark the selected inbox notification as read, or as done (gone from the inbox)
+    fn mark_selected_notification(&mut self, done: bool) {
+        if !self.inbox.filtered_indices.contains(&self.inbox.selected) {
+            return;
+        }
+        let Some(pr) = self.inbox.prs.get(self.inbox.selected) else {
+            return;
+        };
+        let Some(ref notification) = pr.notification else {
+            return;
+        };
+        self.mark_notification(pr.host.clone(), notification.thread_id.clone(), done);
+    }
+
+    /// A submitted review takes care of the PR's notification
+    fn mark_current_pr_notification_done(&mut self) {
+        let Some(ref current) = self.current_pr else {
+            return;
+        };
+        let listed = self.inbox.prs.iter().find(|pr| {
+            pr.host == current.host
+                && pr.repo_owner == current.repo_owner
+                && pr.repo_name == current.repo_name
+                && pr.number == current.number
+        });
+        if let Some(pr) = listed
+            && let Some(ref notification) = pr.notification
+        {
+            self.mark_notification(pr.host.clone(), notification.thread_id.clone(), true);
+        }
+    }
+
+    fn mark_notification(&mut self, host: String, thread_id: String, done: bool) {
+        let (tx, rx) = mpsc::channel();
+        self.notification_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&host);
+            let result = rt.block_on(forge.mark_notification(&thread_id, done));
+
+            let _ = tx.send(result.map(|()| (thread_id, done)).map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Reflect a notification marked on the host: read ones stay listed until the next refresh
+    fn apply_notification_update(&mut self, thread_id: &str, done: bool) {
+        let Some(pos) = self.inbox.prs.iter().position(|pr| {
+            pr.notification
+                .as_ref()
+                .is_some_and(|notification| notification.thread_id == thread_id)
+        }) else {
+            return;
+        };
+
+        if done {
+            self.inbox.prs.remove(pos);
+            if self.inbox.selected > pos || self.inbox.selected >= self.inbox.prs.len() {
+                self.inbox.selected = self.inbox.selected.saturating_sub(1);
+            }
+            self.update_filtered_pr_indices();
+        } else if let Some(ref mut notification) = self.inbox.prs[pos].notification {
+            notification.unread = false;
+        }
+    }
+
+    /// Ask the host whether and how the current PR can be merged, then open the merge dialog
+    fn load_merge_info(&mut self) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let pr_info = pr.to_pr_info();
+        self.loading = LoadingState::Loading("Checking mergeability...".to_string());
+
+        let (tx, rx) = mpsc::channel();
+        self.merge_info_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.fetch_merge_info(&pr_info));
+
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Load the PR's reviewers, assignees and labels, then open the editor
+    fn load_metadata(&mut self) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let pr_info = pr.to_pr_info();
+        self.loading = LoadingState::Loading("Loading reviewers, assignees and labels...".to_string());
+
+        let (tx, rx) = mpsc::channel();
+        self.metadata_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.fetch_metadata(&pr_info));
+
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    fn update_metadata(&mut self, original: PrMetadata, updated: PrMetadata) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let pr_info = pr.to_pr_info();
+        self.loading = LoadingState::Loading("Updating reviewers, assignees and labels...".to_string());
+
+        let (tx, rx) = mpsc::channel();
+        self.metadata_update_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.update_metadata(&pr_info, &original, &updated));
+
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    fn open_merge_dialog(&mut self, info: MergeInfo) {
+        let Some(&method) = info.methods.first() else {
+            self.loading = LoadingState::Error("The repository allows no merge methods".to_string());
+            return;
+        };
+        self.loading = LoadingState::Idle;
+
+        // Offer auto-merge when merging has to wait for requirements or running checks
+        let checks_pending = matches!(
+            self.checks,
+            Some(Ok(ref checks)) if checks.iter().any(|c| c.state == CheckState::Pending)
+        );
+        let auto_merge = info.auto_merge_allowed
+            && !info.auto_merge_enabled
+            && info.status.can_auto_merge()
+            && (!info.status.can_merge() || checks_pending);
+
+        let (title, body) = info.message(method);
+        self.comment_mode = CommentMode::Merging {
+            info,
+            method: 0,
+            title,
+            body,
+            editing: None,
+            delete_branch: false,
+            auto_merge,
+        };
+    }
+
+    fn merge_pr(&mut self, request: MergeRequest) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let pr_info = pr.to_pr_info();
+        self.loading = LoadingState::Loading(if request.auto_merge {
+            "Enabling auto-merge...".to_string()
+        } else {
+            format!("Merging #{}...", pr.number)
+        });
+        self.comment_mode = CommentMode::None;
+
+        let (tx, rx) = mpsc::channel();
+        self.merge_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.merge_pr(&pr_info, &request));
+
+            let _ = tx.send(result.map(|_| request.auto_merge).map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Save current drafts to disk (non-blocking)
+    /// Spawns a background thread to avoid blocking the UI during file I/O
+    fn save_current_drafts(&self) {
+        if let Some(ref review) = self.local_review {
+            let key = review.key.clone();
+            let comments = self.pending_comments.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = crate::drafts::save_local_notes(&key, &comments) {
+                    eprintln!("Warning: Failed to save notes: {}", e);
+                }
+            });
+        } else if let Some(ref pr) = self.current_pr {
+            let pr_info = pr.to_pr_info();
+            let comments = self.pending_comments.clone();
+            // Spawn background thread for file I/O to avoid blocking UI
+            std::thread::spawn(move || {
+                if let Err(e) = crate::drafts::save_drafts(&pr_info, &comments) {
+                    // Silently ignore save errors - drafts are best-effort
+                    eprintln!("Warning: Failed to save drafts: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Load drafts for the current PR from disk
+    fn load_current_drafts(&mut self) {
+        if let Some(ref review) = self.local_review {
+            self.pending_comments = crate::drafts::load_local_notes(&review.key);
+            self.selected_pending_comment = 0;
+        } else if let Some(ref pr) = self.current_pr {
+            let pr_info = pr.to_pr_info();
+            self.pending_comments = crate::drafts::load_drafts(&pr_info);
+            self.selected_pending_comment = 0;
+            self.synced_drafts.clear();
+        }
+    }
+
+    /// Persist the drafts after an edit and mirror it to the pending review
+    fn drafts_changed(&mut self) {
+        self.save_current_drafts();
+        self.sync_pending_review();
+    }
+
+    /// Bring the GitHub pending review in line with the drafts (non-blocking)
+    /// New inline drafts are only pushed with `github.sync_pending_review`
+    fn sync_pending_review(&mut self) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        if self.local_review.is_some() || self.config.host_kind(&pr.host) != ForgeKind::Github {
+            return;
+        }
+        // One sync at a time; the running one picks up the change when it finishes
+        if self.pending_sync_receiver.is_some() {
+            self.pending_sync_dirty = true;
+            return;
+        }
+
+        let changes = crate::drafts::pending_review_changes(
+            &self.pending_comments,
+            &self.synced_drafts,
+            self.config.github.sync_pending_review,
+        );
+        if changes.is_empty() {
+            return;
+        }
+        self.pending_sync_dirty = false;
+
+        let pr_info = pr.to_pr_info();
+        let (tx, rx) = mpsc::channel();
+        self.pending_sync_receiver = Some(rx);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let mut done = PendingReviewSync::default();
+
+            let result: Result<(), crate::forge::ForgeError> = rt.block_on(async {
+                for mut comment in changes.add {
+                    comment.remote_id = Some(forge.add_pending_comment(&pr_info, &comment).await?);
+                    done.add.push(comment);
+                }
+                for (id, body) in changes.update {
+                    forge.update_pending_comment(&pr_info, &id, &body).await?;
+                    done.update.push((id, body));
+                }
+                for id in changes.delete {
+                    forge.delete_pending_comment(&pr_info, &id).await?;
+                    done.delete.push(id);
+                }
+                Ok(())
+            });
+
+            let _ = tx.send((done, result.err().map(|e| e.to_string())));
+        });
+    }
+
+    /// Record pending review edits that reached GitHub
+    fn apply_pending_sync(&mut self, done: PendingReviewSync) {
+        let synced = !done.add.is_empty() || !done.update.is_empty();
+        for comment in done.add {
+            let Some(id) = comment.remote_id.clone() else {
+                continue;
+            };
+            // The draft may have been edited or deleted meanwhile; the next sync then
+            // deletes the orphaned remote copy
+            if let Some(draft) = self
+                .pending_comments
+                .iter_mut()
+                .find(|d| d.remote_id.is_none() && d.same_draft(&comment))
+            {
+                draft.remote_id = Some(id.clone());
+                draft.synced_body = Some(comment.body.clone());
+            }
+            self.synced_drafts.insert(id, comment.body);
+        }
+        for (id, body) in done.update {
+            if let Some(draft) = self
+                .pending_comments
+                .iter_mut()
+                .find(|d| d.remote_id.as_ref() == Some(&id))
+            {
+                draft.synced_body = Some(body.clone());
+            }
+            self.synced_drafts.insert(id, body);
+        }
+        for id in done.delete {
+            self.synced_drafts.remove(&id);
+        }
+
+        // The synced text is kept with the drafts to tell later local edits from remote ones
+        if synced {
+            self.save_current_drafts();
+        }
+    }
+
+    /// Load comment threads (with the rest of the review state) for the current PR (non-blocking)
+    fn load_review_state(&mut self) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+
+        let pr_info = pr.to_pr_info();
 
         let (tx, rx) = mpsc::channel();
-        self.review_submit_receiver = Some(rx);
+        self.review_state_receiver = Some(rx);
 
-        let event_str = event.to_string();
-        let body_clone = body;
+        // Spawn a thread to fetch comments - results processed in event_loop
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.fetch_review_state(&pr_info));
+
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Fetch the CI checks of the current PR's head commit in the background
+    fn load_checks(&mut self) {
+        let Some(ref pr) = self.current_pr else {
+            return;
+        };
+        let Some(head_sha) = pr.head_sha.clone() else {
+            self.checks = Some(Err("Head commit not loaded yet".to_string()));
+            return;
+        };
+
+        let pr_info = pr.to_pr_info();
+        let (tx, rx) = mpsc::channel();
+        self.checks_receiver = Some(rx);
 
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
-            let comments_opt = if pending_comments.is_empty() {
-                None
-            } else {
-                Some(pending_comments.as_slice())
-            };
-            let result = rt.block_on(crate::github::submit_pr_review(
-                &pr_info,
-                &event_str,
-                body_clone.as_deref(),
-                comments_opt,
-                head_sha.as_deref(),
-            ));
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt.block_on(forge.fetch_checks(&pr_info, &head_sha));
 
-            // Return (event, comments_submitted) on success
-            let _ = tx.send(
-                result
-                    .map(|count| (event_str, count))
-                    .map_err(|e| e.to_string()),
-            );
+            let _ = tx.send(result.map_err(|e| e.to_string()));
         });
     }
 
-    /// Save current drafts to disk (non-blocking)
-    /// Spawns a background thread to avoid blocking the UI during file I/O
-    fn save_current_drafts(&self) {
-        if let Some(ref pr) = self.current_pr {
-            let pr_info = pr.to_pr_info();
-            let comments = self.pending_comments.clone();
-            // Spawn background thread for file I/O to avoid blocking UI
-            std::thread::spawn(move || {
-                if let Err(e) = crate::drafts::save_drafts(&pr_info, &comments) {
-                    // Silently ignore save errors - drafts are best-effort
-                    eprintln!("Warning: Failed to save drafts: {}", e);
-                }
-            });
-        }
+    /// Show a freshly loaded diff from its first file
+    fn show_diff_files(&mut self, files: Vec<DiffFile>) {
+        let file_count = files.len();
+        self.files = files;
+        self.filtered_indices = (0..file_count).collect();
+        self.selected_file = 0;
+        self.scroll_offset = 0;
+        self.diff_cursor = 0;
+        self.collapsed.clear();
+        self.init_collapsed_folders();
+        self.invalidate_tree_cache(); // Cache invalidated when files change
     }
 
-    /// Load drafts for the current PR from disk
-    fn load_current_drafts(&mut self) {
-        if let Some(ref pr) = self.current_pr {
-            let pr_info = pr.to_pr_info();
-            self.pending_comments = crate::drafts::load_drafts(&pr_info);
-            self.selected_pending_comment = 0;
+    /// Show another diff of the current PR, staying on the selected file when it is in both
+    /// Returns the files shown before
+    fn replace_diff_files(&mut self, files: Vec<DiffFile>) -> Vec<DiffFile> {
+        let selected_path = self.files.get(self.selected_file).map(|file| file.path.clone());
+        let previous = std::mem::take(&mut self.files);
+        self.show_diff_files(files);
+        if let Some(index) = selected_path.and_then(|path| self.files.iter().position(|file| file.path == path)) {
+            self.select_file(index);
         }
+        previous
     }
 
-    /// Load comment threads for the current PR from GitHub (non-blocking)
-    fn load_comment_threads(&mut self) {
+    /// Switch between the PR's whole diff and the changes since the current user's last review
+    /// The last review is the host's latest review by the user, or else the last one submitted from kensa
+    fn toggle_since_review(&mut self) {
+        if let Some(files) = self.full_files.take() {
+            self.since_review = None;
+            self.replace_diff_files(files);
+            return;
+        }
         let Some(ref pr) = self.current_pr else {
             return;
         };
+        let Some(head) = pr.head_sha.clone() else {
+            self.loading = LoadingState::Error("Head commit not loaded yet".to_string());
+            return;
+        };
 
         let pr_info = pr.to_pr_info();
+        let Some(base) = helpers::last_reviewed_commit(&self.pr_reviews)
+            .map(str::to_string)
+            .or_else(|| crate::drafts::load_reviewed_head(&pr_info))
+        else {
+            self.loading = LoadingState::Error("You haven't reviewed this PR yet".to_string());
+            return;
+        };
+        if base == head {
+            self.loading = LoadingState::Success("No new commits since your last review".to_string());
+            return;
+        }
 
+        self.loading = LoadingState::Loading(format!(
+            "Loading changes since {}...",
+            base.chars().take(7).collect::<String>()
+        ));
         let (tx, rx) = mpsc::channel();
-        self.comment_threads_receiver = Some(rx);
+        self.compare_receiver = Some(rx);
 
-        // Spawn a thread to fetch comments - results processed in event_loop
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
-            let result = rt.block_on(crate::github::fetch_all_comment_threads(&pr_info));
+            let forge = crate::forge::backend_for(&pr_info.host);
+            let result = rt
+                .block_on(forge.fetch_compare_diff(&pr_info, &base, &head))
+                .map(|diff| (base, crate::parser::parse_diff(&diff)));
 
             let _ = tx.send(result.map_err(|e| e.to_string()));
         });
     }
 
-    /// Build lookup map from (file, line) to thread indices
+    /// Store a freshly loaded review state for the current PR
+    fn apply_review_state(&mut self, state: ReviewState) {
+        if let Some(ref mut pr) = self.current_pr {
+            // Checks belong to the head commit, which may have moved
+            if pr.head_sha != state.pr.head_sha {
+                self.checks = None;
+                self.checks_receiver = None;
+                self.line_to_annotations.clear();
+            }
+            pr.head_sha = state.pr.head_sha;
+            pr.body = state.pr.body;
+        }
+        // Annotations are shown in the diff, so checks load with the review state
+        if self.checks.is_none() && self.checks_receiver.is_none() {
+            self.load_checks();
+        }
+        self.build_line_to_threads_map(&state.threads);
+        self.comment_threads = state.threads;
+        self.pr_reviews = state.reviews;
+        self.pr_node_id = state.pr_node_id;
+        self.pr_reactions = state.reactions;
+        self.pr_base_sha = state.base_sha;
+        self.file_stats = state.file_stats;
+
+        // A running sync would race with the merge; the next load catches up
+        if self.pending_sync_receiver.is_none() {
+            let remote = state.pending_review.map(|review| review.comments).unwrap_or_default();
+            crate::drafts::merge_pending_review(&mut self.pending_comments, &remote);
+            self.synced_drafts = remote
+                .into_iter()
+                .filter_map(|comment| Some((comment.remote_id?, comment.body)))
+                .collect();
+            self.save_current_drafts();
+            self.sync_pending_review();
+        }
+    }
+
+    /// Forget the threads, reviews and stats of the previous PR
+    fn clear_review_state(&mut self) {
+        self.comment_threads = Vec::new();
+        self.line_to_threads.clear();
+        self.line_to_threads.shrink_to_fit();
+        self.pr_reviews = Vec::new();
+        self.pr_node_id = None;
+        self.pr_reactions = Vec::new();
+        self.checks = None;
+        self.checks_receiver = None;
+        self.line_to_annotations.clear();
+        self.pr_base_sha = None;
+        self.file_stats = Vec::new();
+    }
+
+    /// Build lookup map from (file, line, side) to thread indices
     fn build_line_to_threads_map(&mut self, threads: &[CommentThread]) {
         // Clear and shrink to prevent unbounded growth
         self.line_to_threads.clear();
@@ -1911,27 +3417,134 @@ impl App {
         for (idx, thread) in threads.iter().enumerate() {
             if let (Some(path), Some(line)) = (&thread.file_path, thread.line) {
                 self.line_to_threads
-                    .entry((path.clone(), line))
+                    .entry((path.clone(), line, thread.side))
                     .or_default()
                     .push(idx);
             }
         }
     }
 
-    /// Check if there are any threads at a given line
-    fn has_threads_at_line(&self, file_path: &str, line: u32) -> bool {
-        self.line_to_threads
-            .contains_key(&(file_path.to_string(), line))
+    /// Build lookup map from (file, line) to the check annotations covering it
+    fn build_line_to_annotations_map(&mut self) {
+        self.line_to_annotations.clear();
+        let Some(Ok(ref checks)) = self.checks else {
+            return;
+        };
+        for (check_idx, check) in checks.iter().enumerate() {
+            for (idx, annotation) in check.annotations.iter().enumerate() {
+                for line in annotation.start_line..=annotation.end_line {
+                    self.line_to_annotations
+                        .entry((annotation.path.clone(), line))
+                        .or_default()
+                        .push((check_idx, idx));
+                }
+            }
+        }
+    }
+
+    /// Check annotations covering a line of the new version, with the name of their check
+    fn annotations_at_line(&self, file_path: &str, line: u32) -> Vec<(&str, &CheckAnnotation)> {
+        let (Some(Ok(checks)), Some(entries)) = (
+            &self.checks,
+            self.line_to_annotations.get(&(file_path.to_string(), line)),
+        ) else {
+            return Vec::new();
+        };
+        entries
+            .iter()
+            .filter_map(|&(check_idx, idx)| {
+                let check = checks.get(check_idx)?;
+                Some((check.name.as_str(), check.annotations.get(idx)?))
+            })
+            .collect()
     }
 
-    /// Get count of threads at a line
-    fn thread_count_at_line(&self, file_path: &str, line: u32) -> usize {
+    /// Gutter marker for the most severe annotation on a line
+    fn annotation_marker(&self, file_path: &str, line: u32) -> Option<(&'static str, Color)> {
+        let level = self
+            .annotations_at_line(file_path, line)
+            .iter()
+            .map(|(_, a)| a.level)
+            .max_by_key(|level| match level {
+                AnnotationLevel::Notice => 0,
+                AnnotationLevel::Warning => 1,
+                AnnotationLevel::Failure => 2,
+            })?;
+        Some(match level {
+            AnnotationLevel::Failure => ("!", Color::Red),
+            AnnotationLevel::Warning => ("!", Color::Yellow),
+            AnnotationLevel::Notice => ("i", Color::Blue),
+        })
+    }
+
+    /// (file index, row) of every line where an annotation starts, in diff order
+    fn annotation_rows(&self) -> Vec<(usize, usize)> {
+        let mut rows = Vec::new();
+        for (file_idx, file) in self.files.iter().enumerate() {
+            // Rows as in render_unified_direct: a header, then the lines of each hunk
+            let mut row = 0;
+            for hunk in &file.hunks {
+                row += 1;
+                for line in &hunk.lines {
+                    if let Some(ln) = line.new_ln
+                        && self
+                            .annotations_at_line(&file.path, ln)
+                            .iter()
+                            .any(|(_, a)| a.start_line == ln)
+                    {
+                        rows.push((file_idx, row));
+                    }
+                    row += 1;
+                }
+            }
+        }
+        rows
+    }
+
+    /// Move the cursor to the next (or previous) annotated line, wrapping around the diff
+    fn jump_to_annotation(&mut self, forward: bool) {
+        let rows = self.annotation_rows();
+        let current = (self.selected_file, self.diff_cursor);
+        let target = if forward {
+            rows.iter().find(|&&row| row > current).or(rows.first())
+        } else {
+            rows.iter().rev().find(|&&row| row < current).or(rows.last())
+        };
+        let Some(&(file_idx, row)) = target else {
+            return;
+        };
+
+        if file_idx != self.selected_file {
+            self.select_file(file_idx);
+        }
+        self.focus = Focus::Diff;
+        self.visual_mode = false;
+        self.diff_cursor = row;
+        self.scroll_offset = row.saturating_sub(10);
+    }
+
+    /// Get count of threads at a line on one side of the diff
+    fn thread_count_at_line(&self, file_path: &str, line: u32, side: DiffSide) -> usize {
         self.line_to_threads
-            .get(&(file_path.to_string(), line))
+            .get(&(file_path.to_string(), line, side))
             .map(|v| v.len())
             .unwrap_or(0)
     }
 
+    /// Get count of threads at a diff line: new-side threads by `new_ln`, old-side ones by `old_ln`
+    fn thread_count_at_diff_line(
+        &self,
+        file_path: &str,
+        old_ln: Option<u32>,
+        new_ln: Option<u32>,
+    ) -> usize {
+        let count = |ln: Option<u32>, side| {
+            ln.map(|ln| self.thread_count_at_line(file_path, ln, side))
+                .unwrap_or(0)
+        };
+        count(new_ln, DiffSide::Right) + count(old_ln, DiffSide::Left)
+    }
+
     /// Get count of pending comments for a file
     fn pending_comment_count_for_file(&self, file_path: &str) -> usize {
         self.pending_comments
@@ -1940,19 +3553,24 @@ impl App {
             .count()
     }
 
-    /// Get the visual order of thread indices (current threads first, then outdated)
+    /// Whether a thread is shown in the threads list (resolved ones only on request)
+    fn thread_listed(&self, thread: &CommentThread) -> bool {
+        self.show_resolved_threads || !thread.resolved
+    }
+
+    /// Get the visual order of listed thread indices (current threads first, then outdated)
     /// Returns a Vec where each element is the original index in self.comment_threads
     fn thread_visual_order(&self) -> Vec<usize> {
         let mut order = Vec::new();
         // Current threads first
         for (idx, thread) in self.comment_threads.iter().enumerate() {
-            if !thread.outdated {
+            if !thread.outdated && self.thread_listed(thread) {
                 order.push(idx);
             }
         }
         // Then outdated threads
         for (idx, thread) in self.comment_threads.iter().enumerate() {
-            if thread.outdated {
+            if thread.outdated && self.thread_listed(thread) {
                 order.push(idx);
             }
         }
@@ -1989,8 +3607,8 @@ impl App {
     }
 
     /// Get line info at the current cursor position for inline comments
-    /// Returns (file_path, line_number) if cursor is on a commentable line
-    fn get_cursor_line_info(&self) -> Option<(String, u32)> {
+    /// Returns (file_path, line_number, side) if cursor is on a commentable line
+    fn get_cursor_line_info(&self) -> Option<(String, u32, DiffSide)> {
         let file = self.files.get(self.selected_file)?;
 
         // Build flattened line list (same as render_unified_direct)
@@ -2004,15 +3622,8 @@ impl App {
 
             for diff_line in &hunk.lines {
                 if line_idx == self.diff_cursor {
-                    // Found the line - return file path and new line number
-                    // For inline comments, we use the "new" line number (RIGHT side)
-                    if let Some(new_ln) = diff_line.new_ln {
-                        return Some((file.path.clone(), new_ln));
-                    } else if let Some(old_ln) = diff_line.old_ln {
-                        // Deleted line - use old line number
-                        return Some((file.path.clone(), old_ln));
-                    }
-                    return None;
+                    // Found the line - return file path, line number and side
+                    return line_anchor(diff_line).map(|(line, side)| (file.path.clone(), line, side));
                 }
                 line_idx += 1;
             }
@@ -2046,44 +3657,73 @@ impl App {
         (start, end)
     }
 
-    /// Get line info for a range of lines (for multi-line comments)
-    /// Returns (file_path, start_line, end_line) if valid
-    fn get_selection_line_info(&self) -> Option<(String, u32, u32)> {
+    /// Get the comment target for a range of lines (for multi-line comments)
+    /// A range may start on a deleted line and end on an added one
+    fn get_selection_target(&self) -> Option<InlineTarget> {
         let file = self.files.get(self.selected_file)?;
         let (sel_start, sel_end) = self.get_selection_range();
 
-        let mut start_line_num: Option<u32> = None;
-        let mut end_line_num: Option<u32> = None;
+        let mut start_line_num: Option<(u32, DiffSide)> = None;
+        let mut end_line_num: Option<(u32, DiffSide)> = None;
 
         let mut line_idx = 0;
         for hunk in &file.hunks {
             line_idx += 1; // Skip hunk header
 
             for diff_line in &hunk.lines {
-                if line_idx >= sel_start && line_idx <= sel_end {
-                    // This line is in selection
-                    if let Some(new_ln) = diff_line.new_ln {
-                        if start_line_num.is_none() {
-                            start_line_num = Some(new_ln);
-                        }
-                        end_line_num = Some(new_ln);
-                    } else if let Some(old_ln) = diff_line.old_ln {
-                        if start_line_num.is_none() {
-                            start_line_num = Some(old_ln);
-                        }
-                        end_line_num = Some(old_ln);
+                // This line is in selection
+                if line_idx >= sel_start
+                    && line_idx <= sel_end
+                    && let Some(anchor) = line_anchor(diff_line)
+                {
+                    if start_line_num.is_none() {
+                        start_line_num = Some(anchor);
                     }
+                    end_line_num = Some(anchor);
                 }
                 line_idx += 1;
             }
         }
 
         match (start_line_num, end_line_num) {
-            (Some(start), Some(end)) => Some((file.path.clone(), start, end)),
+            (Some(start), Some(end)) => Some(InlineTarget {
+                path: file.path.clone(),
+                line: Some(end),
+                start: (start != end).then_some(start), // None for a single line
+            }),
             _ => None,
         }
     }
 
+    /// Get the new-side lines of the selection, for suggested changes
+    /// Returns (file_path, start_line, end_line, contents), or why the selection can't be suggested on
+    fn get_selection_new_lines(&self) -> Result<(String, u32, u32, Vec<String>), &'static str> {
+        let file = self.files.get(self.selected_file).ok_or(helpers::NO_NEW_LINES)?;
+        let (sel_start, sel_end) = self.get_selection_range();
+        let (start, end, lines) = helpers::selection_new_lines(file, sel_start, sel_end)?;
+        Ok((file.path.clone(), start, end, lines))
+    }
+
+    /// Get the contents of new-side lines `start..=end` of a file, if the diff shows all of them
+    fn new_side_lines(&self, path: &str, start: u32, end: u32) -> Option<Vec<String>> {
+        let file = self.files.iter().find(|f| f.path == path)?;
+        let lines: Vec<String> = file
+            .hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| line.new_ln.is_some_and(|ln| ln >= start && ln <= end))
+            .map(|line| line.content.clone())
+            .collect();
+        (lines.len() == (end - start + 1) as usize).then_some(lines)
+    }
+
+    /// Mini-diff rows for a suggestion on new-side lines `start..=end` of a file
+    /// Without those lines in the diff, only the suggested lines are shown
+    fn suggestion_rows(&self, location: Option<(&str, u32, u32)>, suggested: &[String]) -> Vec<(char, String)> {
+        let original = location.and_then(|(path, start, end)| self.new_side_lines(path, start, end));
+        helpers::suggestion_diff(original.as_deref(), suggested)
+    }
+
     fn move_down(&mut self) {
         match self.focus {
             Focus::Tree => {
@@ -2377,6 +4017,21 @@ impl App {
             self.render_pr_description(frame);
         }
 
+        // Render CI checks panel if active
+        if self.show_checks {
+            self.render_checks(frame);
+        }
+
+        // Render reviewers/assignees/labels editor if active
+        if let Some(ref editor) = self.metadata_editor {
+            self.render_metadata_editor(frame, editor);
+        }
+
+        // Render reaction picker over the description or thread
+        if let Some(picker) = self.reaction_picker {
+            self.render_reaction_picker(frame, picker);
+        }
+
         // Render quit confirmation dialog if active
         if self.confirm_quit {
             self.render_confirm_quit(frame);
@@ -2388,8 +4043,8 @@ impl App {
         let bg = Color::Rgb(25, 28, 38);
 
         let popup_height = match self.help_mode {
-            HelpMode::PrList => 16,
-            HelpMode::DiffView => 28,
+            HelpMode::PrList => 19,
+            HelpMode::DiffView => 32,
             HelpMode::None => return,
         };
 
@@ -2418,11 +4073,14 @@ impl App {
                     ("j/k", "Move up/down"),
                     ("Enter", "Open PR"),
                     ("Tab", "Switch tabs"),
+                    ("1-9", "Go to tab"),
                 ]),
                 ("Actions", vec![
                     ("o", "Open in browser"),
                     ("R", "Refresh list"),
                     ("f", "Filter by repo"),
+                    ("t", "Filter by review reason"),
+                    ("m/d", "Mark notification read/done"),
                     ("/", "Search"),
                 ]),
                 ("General", vec![
@@ -2443,18 +4101,26 @@ impl App {
                     ("b", "Toggle file tree"),
                     ("d", "Toggle split view"),
                     ("i", "View PR description"),
+                    ("I", "Since last review"),
                     ("x", "Collapse folder"),
                     ("/", "Search files"),
+                    ("K", "View CI checks"),
+                    ("n/N", "Next/prev annotation"),
+                    ("E", "Expand annotations"),
                 ]),
                 ("Comments", vec![
                     ("c", "Add comment"),
                     ("v", "Visual select"),
+                    ("s", "Suggest change (visual)"),
+                    ("F", "Comment on file"),
                     ("C", "View drafts"),
                     ("t", "View threads"),
                     ("S", "Submit comments"),
                     ("A", "Submit review"),
+                    ("M", "Merge PR"),
                 ]),
                 ("General", vec![
+                    ("m", "Reviewers/labels"),
                     ("o", "Open in browser"),
                     ("q", "Back/quit"),
                 ]),
@@ -2563,7 +4229,14 @@ impl App {
         let buf = frame.buffer_mut();
 
         // PR metadata header
-        let meta_line = format!("@{} | {} | {}", pr.author, pr.repo_full_name(), pr.age());
+        let mut meta_line = format!("@{} | {} | {}", pr.author, pr.repo_full_name(), pr.age());
+        if let (Some(base), Some(head)) = (&self.pr_base_sha, &pr.head_sha) {
+            meta_line.push_str(&format!(
+                " | {}..{}",
+                base.chars().take(7).collect::<String>(),
+                head.chars().take(7).collect::<String>()
+            ));
+        }
         buf.set_string(
             inner.x + 1,
             inner.y,
@@ -2571,11 +4244,35 @@ impl App {
             Style::default().fg(Color::Rgb(150, 150, 180)).bg(bg),
         );
 
+        // Latest verdict per reviewer, when the host reported reviews
+        let mut header_rows = 1;
+        if let Some(summary) = helpers::review_summary(&self.pr_reviews) {
+            let summary = format!("Reviews: {}", summary);
+            let max_len = inner.width.saturating_sub(2) as usize;
+            buf.set_string(
+                inner.x + 1,
+                inner.y + 1,
+                summary.chars().take(max_len).collect::<String>(),
+                Style::default().fg(Color::Rgb(140, 180, 220)).bg(bg),
+            );
+            header_rows += 1;
+        }
+
+        if !self.pr_reactions.is_empty() {
+            buf.set_string(
+                inner.x + 1,
+                inner.y + header_rows,
+                helpers::reactions_label(&self.pr_reactions),
+                Style::default().fg(Color::Rgb(200, 180, 120)).bg(bg),
+            );
+            header_rows += 1;
+        }
+
         // Separator line
         let separator: String = "─".repeat((inner.width.saturating_sub(2)) as usize);
         buf.set_string(
             inner.x + 1,
-            inner.y + 1,
+            inner.y + header_rows,
             &separator,
             Style::default().fg(Color::Rgb(60, 60, 80)).bg(bg),
         );
@@ -2584,7 +4281,8 @@ impl App {
         let body = pr.body.as_deref().unwrap_or("(No description provided)");
         let max_width = (inner.width.saturating_sub(2)) as usize;
         let wrapped_lines = helpers::wrap_text_with_code(body, max_width);
-        let content_height = (inner.height.saturating_sub(4)) as usize; // Leave room for header, separator, footer
+        // Leave room for header, separator, footer
+        let content_height = (inner.height.saturating_sub(3 + header_rows)) as usize;
         let total_lines = wrapped_lines.len();
 
         // Cap scroll offset
@@ -2593,7 +4291,7 @@ impl App {
 
         // Render visible lines
         let visible_lines = wrapped_lines.iter().skip(scroll).take(content_height);
-        let content_start_y = inner.y + 2;
+        let content_start_y = inner.y + header_rows + 1;
         let code_bg = Color::Rgb(35, 38, 48);
 
         for (i, (line, is_code)) in visible_lines.enumerate() {
@@ -2630,12 +4328,224 @@ impl App {
         } else {
             String::new()
         };
-        let hint = format!("{}j/k scroll | q/Esc close", scroll_info);
+        let react_hint = if self.pr_node_id.is_some() { "+ react | " } else { "" };
+        let hint = format!("{}j/k scroll | {}q/Esc close", scroll_info, react_hint);
+        let hint_x = popup_area.x + (popup_area.width.saturating_sub(hint.len() as u16)) / 2;
+        buf.set_string(
+            hint_x,
+            popup_area.y + popup_area.height - 1,
+            &hint,
+            Style::default().fg(Color::Rgb(80, 80, 100)).bg(bg),
+        );
+    }
+
+    fn render_checks(&self, frame: &mut ratatui::Frame) {
+        let area = frame.area();
+        let bg = Color::Rgb(25, 28, 38);
+        let accent = self.accent_color();
+
+        let rows = match self.checks {
+            Some(Ok(ref checks)) => checks.len().max(1),
+            _ => 1,
+        };
+        let popup_width = (area.width as f32 * 0.8) as u16;
+        let popup_height = (rows as u16 + 4).min((area.height as f32 * 0.8) as u16);
+        let popup_area = Self::centered_popup(area, popup_width, popup_height);
+        Self::clear_popup_background(frame.buffer_mut(), popup_area, bg);
+
+        let head = self
+            .current_pr
+            .as_ref()
+            .and_then(|pr| pr.head_sha.as_deref())
+            .map(|sha| sha.chars().take(7).collect::<String>())
+            .unwrap_or_default();
+        let title = match self.checks {
+            Some(Ok(ref checks)) if self.checks_receiver.is_none() => {
+                format!(" Checks {} - {} ", head, helpers::checks_summary(checks))
+            }
+            _ => format!(" Checks {} ", head),
+        };
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(accent).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let buf = frame.buffer_mut();
+        let dim = Style::default().fg(Color::Rgb(120, 120, 140)).bg(bg);
+        let content_height = inner.height.saturating_sub(1) as usize;
+
+        let checks = match self.checks {
+            _ if self.checks_receiver.is_some() => {
+                buf.set_string(inner.x + 1, inner.y, "Loading checks...", dim);
+                &[][..]
+            }
+            Some(Ok(ref checks)) if checks.is_empty() => {
+                buf.set_string(inner.x + 1, inner.y, "No checks reported for this commit", dim);
+                &[][..]
+            }
+            Some(Ok(ref checks)) => checks.as_slice(),
+            Some(Err(ref e)) => {
+                buf.set_string(inner.x + 1, inner.y, e, Style::default().fg(Color::Red).bg(bg));
+                &[][..]
+            }
+            None => &[][..],
+        };
+
+        // Keep the selected check in view
+        let scroll = self.selected_check.saturating_sub(content_height.saturating_sub(1));
+        let name_width = inner.width.saturating_sub(24) as usize;
+        for (i, check) in checks.iter().enumerate().skip(scroll).take(content_height) {
+            let y = inner.y + (i - scroll) as u16;
+            let row_bg = if i == self.selected_check { Color::Rgb(45, 45, 65) } else { bg };
+            for x in inner.x..inner.x + inner.width {
+                buf.set_string(x, y, " ", Style::default().bg(row_bg));
+            }
+
+            let color = match check.state {
+                CheckState::Success => Color::Green,
+                CheckState::Failure => Color::Red,
+                CheckState::Pending => Color::Yellow,
+                CheckState::Neutral => Color::DarkGray,
+            };
+            buf.set_string(inner.x + 1, y, check.state.icon(), Style::default().fg(color).bg(row_bg));
+            let name: String = check.name.chars().take(name_width).collect();
+            buf.set_string(inner.x + 3, y, &name, Style::default().fg(Color::Rgb(220, 220, 230)).bg(row_bg));
+
+            let mut meta = Vec::new();
+            if check.required {
+                meta.push("required".to_string());
+            }
+            if let Some(duration) = check.duration() {
+                meta.push(helpers::format_duration(duration));
+            }
+            let meta = meta.join("  ");
+            let meta_x = (inner.x + inner.width).saturating_sub(meta.chars().count() as u16 + 1);
+            buf.set_string(meta_x, y, &meta, Style::default().fg(Color::Rgb(140, 140, 160)).bg(row_bg));
+        }
+
+        let hint = "j/k select | Enter/o open | r refresh | q/Esc close";
+        let hint_x = popup_area.x + (popup_area.width.saturating_sub(hint.len() as u16)) / 2;
+        buf.set_string(
+            hint_x,
+            popup_area.y + popup_area.height - 1,
+            hint,
+            Style::default().fg(Color::Rgb(80, 80, 100)).bg(bg),
+        );
+    }
+
+    fn render_metadata_editor(&self, frame: &mut ratatui::Frame, editor: &MetadataEditor) {
+        let area = frame.area();
+        let bg = Color::Rgb(25, 28, 38);
+        let accent = self.accent_color();
+        let author = self.current_pr.as_ref().map(|pr| pr.author.as_str()).unwrap_or_default();
+
+        // Completions are listed below the fields while adding
+        let max_completions = 8;
+        let completions = match editor.query {
+            Some(ref query) => editor.edited.completions(editor.field, query, author),
+            None => Vec::new(),
+        };
+        let list_rows: usize = MetadataField::ALL
+            .iter()
+            .map(|&field| editor.edited.entries(field).len().max(1) + 1)
+            .sum();
+        let adding_rows = if editor.query.is_some() { max_completions + 2 } else { 0 };
+        let popup_width = (area.width as f32 * 0.6) as u16;
+        let popup_height = ((list_rows + adding_rows) as u16 + 3).min((area.height as f32 * 0.8) as u16);
+        let popup_area = Self::centered_popup(area, popup_width, popup_height);
+        Self::clear_popup_background(frame.buffer_mut(), popup_area, bg);
+
+        let block = Block::default()
+            .title(" Reviewers, assignees and labels ")
+            .title_style(Style::default().fg(accent).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let buf = frame.buffer_mut();
+        let dim = Style::default().fg(Color::Rgb(120, 120, 140)).bg(bg);
+        let bottom = inner.y + inner.height.saturating_sub(1);
+        let width = inner.width.saturating_sub(4) as usize;
+        let display_name = |entry: &crate::types::MetadataEntry| {
+            if entry.is_team { format!("{} (team)", entry.name) } else { entry.name.clone() }
+        };
+        let mut y = inner.y;
+
+        for field in MetadataField::ALL {
+            if y >= bottom {
+                break;
+            }
+            let current = field == editor.field;
+            let header_style = if current {
+                Style::default().fg(accent).bg(bg).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Rgb(180, 140, 220)).bg(bg)
+            };
+            buf.set_string(inner.x + 1, y, field.label(), header_style);
+            y += 1;
+
+            let entries = editor.edited.entries(field);
+            if entries.is_empty() && y < bottom {
+                buf.set_string(inner.x + 3, y, "(none)", dim);
+                y += 1;
+            }
+            for (i, entry) in entries.iter().enumerate() {
+                if y >= bottom {
+                    break;
+                }
+                let selected = current && i == editor.selected && editor.query.is_none();
+                let row_bg = if selected { Color::Rgb(45, 45, 65) } else { bg };
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_string(x, y, " ", Style::default().bg(row_bg));
+                }
+                // Entries added in this edit are shown in green
+                let is_new = !editor.original.entries(field).iter().any(|e| e.id == entry.id);
+                let color = if is_new { Color::Green } else { Color::Rgb(220, 220, 230) };
+                let name = helpers::truncate_or_pad(&display_name(entry), width);
+                buf.set_string(inner.x + 3, y, name, Style::default().fg(color).bg(row_bg));
+                y += 1;
+            }
+        }
+
+        if let Some(ref query) = editor.query
+            && y + 1 < bottom
+        {
+            y += 1;
+            let prompt = format!("Add {}: {}_", editor.field.entry_name(), query);
+            buf.set_string(inner.x + 1, y, helpers::truncate_or_pad(&prompt, width + 2), Style::default().fg(Color::White).bg(bg));
+            y += 1;
+
+            if completions.is_empty() && y < bottom {
+                buf.set_string(inner.x + 3, y, "No matches", dim);
+            }
+            for (i, entry) in completions.iter().enumerate().take(max_completions) {
+                if y >= bottom {
+                    break;
+                }
+                let style = if i == editor.completion {
+                    Style::default().fg(Color::Green).bg(Color::Rgb(45, 45, 65))
+                } else {
+                    Style::default().fg(Color::Rgb(160, 160, 180)).bg(bg)
+                };
+                buf.set_string(inner.x + 3, y, helpers::truncate_or_pad(&display_name(entry), width), style);
+                y += 1;
+            }
+        }
+
+        let hint = if editor.query.is_some() {
+            "type to filter | Tab/Up/Down select | Enter add | Esc back"
+        } else {
+            "Tab/h/l section | j/k select | a add | d remove | Enter apply | Esc cancel"
+        };
         let hint_x = popup_area.x + (popup_area.width.saturating_sub(hint.len() as u16)) / 2;
         buf.set_string(
             hint_x,
             popup_area.y + popup_area.height - 1,
-            &hint,
+            hint,
             Style::default().fg(Color::Rgb(80, 80, 100)).bg(bg),
         );
     }
@@ -2856,7 +4766,11 @@ impl App {
                 .fg(Color::Rgb(100, 200, 255)) // Softer cyan
                 .bg(header_bg)
                 .add_modifier(Modifier::BOLD);
-            let header_text = format!("  @{}'s PRs ({}) ", author, self.filtered_my_pr_indices.len());
+            let header_text = format!(
+                "  @{}'s PRs ({}) ",
+                author,
+                helpers::list_count(self.filtered_my_pr_indices.len(), self.my_truncated)
+            );
             buf.set_string(tabs_start, tab_y, &header_text, header_style);
             // Underline indicator
             for x in tabs_start..(tabs_start + header_text.len() as u16) {
@@ -2878,7 +4792,7 @@ impl App {
             };
             let tab1_text = format!(
                 " For Review ({}) ",
-                self.filtered_review_pr_indices.len()
+                helpers::list_count(self.filtered_review_pr_indices.len(), self.review_truncated)
             );
             buf.set_string(tabs_start, tab_y, &tab1_text, tab1_style);
             // Underline for active tab
@@ -2900,7 +4814,10 @@ impl App {
                     .fg(Color::Rgb(100, 100, 110))
                     .bg(header_bg)
             };
-            let tab2_text = format!(" My PRs ({}) ", self.filtered_my_pr_indices.len());
+            let tab2_text = format!(
+                " My PRs ({}) ",
+                helpers::list_count(self.filtered_my_pr_indices.len(), self.my_truncated)
+            );
             let tab2_x = tabs_start + tab1_text.len() as u16;
             buf.set_string(tab2_x, tab_y, &tab2_text, tab2_style);
             // Underline for active tab
@@ -2910,6 +4827,45 @@ impl App {
                 }
             }
 
+            // Inbox and saved search tabs, as many as fit before the help hint
+            let mut tabs_end_x = tab2_x + tab2_text.len() as u16;
+            let tabs_limit = (area.x + area.width).saturating_sub(20);
+            let extra_tabs = std::iter::once((PrListTab::Inbox, "Inbox", &self.inbox)).chain(
+                self.search_tabs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, tab)| (PrListTab::Search(i), tab.name.as_str(), &tab.list)),
+            );
+            for (tab, name, list) in extra_tabs {
+                let active = self.pr_tab == tab;
+                let text = format!(
+                    " {} ({}) ",
+                    name,
+                    helpers::list_count(list.filtered_indices.len(), list.truncated)
+                );
+                let width = text.chars().count() as u16;
+                if tabs_end_x + width > tabs_limit {
+                    break;
+                }
+                let style = if active {
+                    Style::default()
+                        .fg(Color::Rgb(100, 200, 255))
+                        .bg(header_bg)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                        .fg(Color::Rgb(100, 100, 110))
+                        .bg(header_bg)
+                };
+                buf.set_string(tabs_end_x, tab_y, &text, style);
+                if active {
+                    for x in tabs_end_x..(tabs_end_x + width) {
+                        buf.set_string(x, tab_y + 1, "━", Style::default().fg(Color::Rgb(100, 200, 255)).bg(header_bg));
+                    }
+                }
+                tabs_end_x += width;
+            }
+
             // Help hint (right-aligned)
             let hint = "?:help  Tab:switch";
             let hint_x = area.x + area.width - hint.len() as u16 - 2;
@@ -2938,6 +4894,56 @@ impl App {
                     &text,
                     Style::default().fg(Color::Rgb(100, 100, 110)).bg(header_bg),
                 );
+                status_end_x = text_x - 1;
+            }
+
+            // A failed refresh of the inbox or a saved search keeps its previous results
+            if let Some(list) = self.extra_list_state()
+                && list.error.is_some()
+                && !list.prs.is_empty()
+            {
+                let text = "refresh failed, showing previous results";
+                let text_x = status_end_x.saturating_sub(text.len() as u16);
+                if text_x > tabs_end_x {
+                    buf.set_string(text_x, tab_y, text, Style::default().fg(Color::Red).bg(header_bg));
+                    status_end_x = text_x - 1;
+                }
+            }
+
+            // Name the hosts that failed, so their missing PRs don't go unnoticed
+            if let Some(first) = self.host_errors.first() {
+                let text = match self.host_errors.len() {
+                    1 => format!("failed: {}", first),
+                    n => format!("failed: {} (+{} more)", first, n - 1),
+                };
+                let available = status_end_x.saturating_sub(tabs_end_x + 1) as usize;
+                let text: String = text.chars().take(available).collect();
+                let text_x = status_end_x.saturating_sub(text.chars().count() as u16);
+                if !text.is_empty() && text_x > tabs_end_x {
+                    buf.set_string(text_x, tab_y, &text, Style::default().fg(Color::Red).bg(header_bg));
+                    status_end_x = text_x.saturating_sub(1);
+                }
+            }
+
+            // Explain the "+" on a capped list
+            let active_truncated = match self.pr_tab {
+                PrListTab::ForReview => self.review_truncated,
+                PrListTab::MyPrs => self.my_truncated,
+                PrListTab::Inbox => self.inbox.truncated,
+                PrListTab::Search(i) => self.search_tabs[i].list.truncated,
+            };
+            if active_truncated {
+                let text = format!("capped at {} (pr_list.max_prs)", self.config.pr_list.max_prs);
+                let text_x = status_end_x.saturating_sub(text.len() as u16);
+                // Skip on narrow terminals rather than drawing over the tabs
+                if text_x > tabs_end_x {
+                    buf.set_string(
+                        text_x,
+                        tab_y,
+                        &text,
+                        Style::default().fg(Color::Yellow).bg(header_bg),
+                    );
+                }
             }
 
             buf.set_string(
@@ -2958,6 +4964,11 @@ impl App {
             if let Some(ref repo) = self.repo_filter {
                 info.push_str(&format!(" repo:{} ", repo));
             }
+            if let Some(ref reason) = self.reason_filter
+                && self.pr_tab == PrListTab::ForReview
+            {
+                info.push_str(&format!(" reason:{} ", reason.label()));
+            }
             if !self.pr_search_query.is_empty() {
                 info.push_str(&format!(" filter:{} ", self.pr_search_query));
             }
@@ -2997,22 +5008,43 @@ impl App {
         let pr_scroll = match self.pr_tab {
             PrListTab::ForReview => self.review_pr_scroll,
             PrListTab::MyPrs => self.my_pr_scroll,
+            PrListTab::Inbox => self.inbox.scroll,
+            PrListTab::Search(i) => self.search_tabs[i].list.scroll,
         };
 
         if filtered_indices.is_empty() {
             let msg = match self.pr_tab {
                 PrListTab::ForReview => {
                     if self.review_prs.is_empty() {
-                        "No PRs awaiting your review"
+                        "No PRs awaiting your review".to_string()
                     } else {
-                        "No PRs match the current filter"
+                        "No PRs match the current filter".to_string()
                     }
                 }
                 PrListTab::MyPrs => {
                     if self.my_prs.is_empty() {
-                        "You have no open PRs"
+                        "You have no open PRs".to_string()
+                    } else {
+                        "No PRs match the current filter".to_string()
+                    }
+                }
+                PrListTab::Inbox => {
+                    if let Some(ref error) = self.inbox.error {
+                        format!("Couldn't load notifications: {}", error)
+                    } else if self.inbox.prs.is_empty() {
+                        "No unread PR notifications".to_string()
                     } else {
-                        "No PRs match the current filter"
+                        "No PRs match the current filter".to_string()
+                    }
+                }
+                PrListTab::Search(i) => {
+                    let tab = &self.search_tabs[i];
+                    if let Some(ref error) = tab.list.error {
+                        format!("Search failed: {}", error)
+                    } else if tab.list.prs.is_empty() {
+                        format!("No open PRs match \"{}\"", tab.query)
+                    } else {
+                        "No PRs match the current filter".to_string()
                     }
                 }
             };
@@ -3132,13 +5164,45 @@ impl App {
             buf.set_string(x, y, &num_str, num_style);
             x += 7;
 
-            // Calculate space for title
+            // Why the PR awaits review ("For Review" tab), or why it is in the inbox
+            let tag = match self.pr_tab {
+                PrListTab::ForReview => pr.review_reasons.first().map(|reason| {
+                    let color = match reason {
+                        ReviewReason::Direct => Color::Rgb(100, 200, 255),
+                        ReviewReason::Team(_) => Color::Rgb(180, 140, 255),
+                        ReviewReason::Reviewed => Color::Rgb(90, 90, 110),
+                    };
+                    (reason.label(), color)
+                }),
+                PrListTab::Inbox => pr.notification.as_ref().map(|notification| {
+                    if notification.unread {
+                        (format!("● {}", notification.label()), Color::Rgb(100, 200, 255))
+                    } else {
+                        (format!("  {}", notification.label()), Color::Rgb(90, 90, 110))
+                    }
+                }),
+                PrListTab::MyPrs | PrListTab::Search(_) => None,
+            };
+            if let Some((tag, color)) = tag {
+                let tag: String = tag.chars().take(16).collect();
+                buf.set_string(x, y, &tag, Style::default().fg(color).bg(row_bg));
+                x += tag.chars().count() as u16 + 1;
+            }
+
+            // Calculate space for title, dropping columns from the right on narrow terminals
             let author_age = format!("@{} · {}", pr.author, pr.age());
             let author_age_len = author_age.chars().count();
-            let title_max_width = (inner_area.x + inner_area.width)
+            let mut columns = self.pr_list_columns(pr);
+            let columns_width = |columns: &[(usize, Vec<(String, Color)>)]| -> usize {
+                columns.iter().map(|(width, _)| width + 2).sum()
+            };
+            let available = (inner_area.x + inner_area.width)
                 .saturating_sub(x)
-                .saturating_sub(author_age_len as u16 + 2)
-                as usize;
+                .saturating_sub(author_age_len as u16 + 2) as usize;
+            while !columns.is_empty() && available.saturating_sub(columns_width(&columns)) < 20 {
+                columns.pop();
+            }
+            let title_max_width = available.saturating_sub(columns_width(&columns));
 
             // Title (truncated) with better styling
             let title: String = pr.title.chars().take(title_max_width).collect();
@@ -3149,6 +5213,18 @@ impl App {
             };
             buf.set_string(x, y, &title_display, style);
 
+            // Columns, aligned across rows, between the title and the author
+            let mut column_x = inner_area.x + inner_area.width - author_age_len as u16 - 1
+                - columns_width(&columns) as u16;
+            for (width, segments) in &columns {
+                let mut segment_x = column_x;
+                for (text, color) in segments {
+                    buf.set_string(segment_x, y, text, Style::default().fg(*color).bg(row_bg));
+                    segment_x += text.chars().count() as u16;
+                }
+                column_x += *width as u16 + 2;
+            }
+
             // Author and age (right-aligned) with softer styling
             let right_x = inner_area.x + inner_area.width - author_age_len as u16 - 1;
             let author_style = if is_selected {
@@ -3181,6 +5257,61 @@ impl App {
         }
     }
 
+    /// Cells of the configured PR list columns for a PR, as (width, colored segments)
+    /// Columns keep their width when a PR has no value, so they line up across rows
+    fn pr_list_columns(&self, pr: &ReviewPr) -> Vec<(usize, Vec<(String, Color)>)> {
+        let dim = Color::Rgb(90, 90, 110);
+        self.config
+            .pr_list
+            .columns
+            .iter()
+            .map(|column| match column {
+                PrColumn::Draft => {
+                    let text = if pr.is_draft { "draft" } else { "" };
+                    (5, vec![(text.to_string(), Color::Rgb(140, 140, 160))])
+                }
+                PrColumn::Review => {
+                    let segment = pr.review_decision.map(|decision| {
+                        let color = match decision {
+                            ReviewDecision::Approved => Color::Green,
+                            ReviewDecision::ChangesRequested => Color::Red,
+                            ReviewDecision::ReviewRequired => Color::Yellow,
+                        };
+                        (decision.label().to_string(), color)
+                    });
+                    (8, segment.into_iter().collect())
+                }
+                PrColumn::Ci => {
+                    let segment = pr.ci_state.map(|state| {
+                        let color = match state {
+                            CheckState::Success => Color::Green,
+                            CheckState::Failure => Color::Red,
+                            CheckState::Pending => Color::Yellow,
+                            CheckState::Neutral => Color::DarkGray,
+                        };
+                        (state.icon().to_string(), color)
+                    });
+                    (1, segment.into_iter().collect())
+                }
+                PrColumn::Size => {
+                    let segments = match pr.size {
+                        Some(size) => vec![
+                            (format!("{:>5}", format!("+{}", helpers::compact_count(size.additions))), Color::Green),
+                            (format!(" {:<5}", format!("-{}", helpers::compact_count(size.deletions))), Color::Red),
+                            (format!(" {:>4}f", helpers::compact_count(size.changed_files)), dim),
+                        ],
+                        None => Vec::new(),
+                    };
+                    (17, segments)
+                }
+                PrColumn::Updated => {
+                    let segment = pr.updated_age().map(|age| (format!("↻ {}", age), dim));
+                    (6, segment.into_iter().collect())
+                }
+            })
+            .collect()
+    }
+
     fn render_pr_list_footer(&self, frame: &mut ratatui::Frame, area: Rect) {
         let buf = frame.buffer_mut();
         let help_y = area.y + area.height - 1;
@@ -3224,6 +5355,8 @@ impl App {
         let total = match self.pr_tab {
             PrListTab::ForReview => self.review_prs.len(),
             PrListTab::MyPrs => self.my_prs.len(),
+            PrListTab::Inbox => self.inbox.prs.len(),
+            PrListTab::Search(i) => self.search_tabs[i].list.prs.len(),
         };
         let count_str = if filtered.len() == total {
             format!("{} PRs ", total)
@@ -3363,6 +5496,20 @@ impl App {
                 Style::default().fg(Color::Rgb(100, 180, 140)).bg(status_bg),
             );
 
+            // Changes since the last review indicator
+            if let Some(ref base) = self.since_review {
+                let since = format!(" since {} ", base.chars().take(7).collect::<String>());
+                buf.set_string(
+                    view_x + view_mode.len() as u16 + 1,
+                    area.y + 1,
+                    &since,
+                    Style::default()
+                        .fg(Color::Rgb(25, 25, 35))
+                        .bg(Color::Rgb(140, 180, 220))
+                        .add_modifier(Modifier::BOLD),
+                );
+            }
+
             // Pending comments badge (right side)
             if !self.pending_comments.is_empty() {
                 let comment_badge = format!(" {} drafts ", self.pending_comments.len());
@@ -3384,7 +5531,7 @@ impl App {
             }
 
             let hints = if self.visual_mode {
-                vec![("Esc", "cancel"), ("c", "comment"), ("j/k", "extend")]
+                vec![("Esc", "cancel"), ("c", "comment"), ("s", "suggest"), ("j/k", "extend")]
             } else if self.pending_comments.is_empty() {
                 vec![("q", "back"), ("c", "comment"), ("v", "visual"), ("/", "search"), ("?", "help")]
             } else {
@@ -3517,15 +5664,17 @@ impl App {
             }
             CommentMode::ViewingThread {
                 index,
-                selected,
                 scroll,
+                comment,
+                deleting,
+                ..
             } => {
-                self.render_thread_detail(frame, area, *index, *selected, *scroll);
+                self.render_thread_detail(frame, area, *index, *scroll, *comment, *deleting);
             }
-            CommentMode::ReplyingToThread { index, text } => {
+            CommentMode::ReplyingToThread { index, text, editing } => {
                 // Show thread detail in background + reply input overlay
-                self.render_thread_detail(frame, area, *index, 0, 0);
-                self.render_reply_input(frame, area, text);
+                self.render_thread_detail(frame, area, *index, 0, *editing, false);
+                self.render_reply_input(frame, area, text, editing.is_some());
             }
             CommentMode::SubmittingReview {
                 selected_action,
@@ -3546,6 +5695,17 @@ impl App {
                     *editing_draft,
                 );
             }
+            CommentMode::Merging {
+                info,
+                method,
+                title,
+                body,
+                editing,
+                delete_branch,
+                auto_merge,
+            } => {
+                self.render_merge_modal(frame, area, info, *method, title, body, *editing, *delete_branch, *auto_merge);
+            }
         }
     }
 
@@ -3554,32 +5714,20 @@ impl App {
         frame: &mut ratatui::Frame,
         area: Rect,
         text: &str,
-        inline_context: Option<&(String, u32, Option<u32>)>,
+        inline_context: Option<&InlineTarget>,
     ) {
         // Create a centered popup for comment input
         let popup_width = (area.width * 2 / 3).min(80);
         let popup_area = Self::centered_popup(area, popup_width, 12);
 
         let title = match inline_context {
-            Some((path, end_line, Some(start_line))) => {
-                let suffix = " (Ctrl+S, Esc) ";
-                let line_info = format!(":{}-{}", start_line, end_line);
-                let prefix = " Comment on ";
-                let max_path_len = (popup_width as usize)
-                    .saturating_sub(prefix.len() + line_info.len() + suffix.len() + 2);
-                let display_path = if path.len() > max_path_len {
-                    format!("...{}", &path[path.len().saturating_sub(max_path_len.saturating_sub(3))..])
-                } else {
-                    path.clone()
-                };
-                format!("{}{}{}{}", prefix, display_path, line_info, suffix)
-            }
-            Some((path, line, None)) => {
+            Some(target) => {
                 let suffix = " (Ctrl+S, Esc) ";
-                let line_info = format!(":{}", line);
+                let line_info = target.line_suffix();
                 let prefix = " Comment on ";
                 let max_path_len = (popup_width as usize)
                     .saturating_sub(prefix.len() + line_info.len() + suffix.len() + 2);
+                let path = &target.path;
                 let display_path = if path.len() > max_path_len {
                     format!("...{}", &path[path.len().saturating_sub(max_path_len.saturating_sub(3))..])
                 } else {
@@ -3640,10 +5788,17 @@ impl App {
         let popup_height = (area.height * 2 / 3).min(20);
         let popup_area = Self::centered_popup(area, popup_width, popup_height);
 
-        let title = format!(
-            " Pending Comments ({}) - j/k:nav  e:edit  d:delete  S:submit  Esc:close ",
-            self.pending_comments.len()
-        );
+        let title = if self.local_review.is_some() {
+            format!(
+                " Local Notes ({}) - j/k:nav  e:edit  d:delete  S:export  Esc:close ",
+                self.pending_comments.len()
+            )
+        } else {
+            format!(
+                " Pending Comments ({}) - j/k:nav  e:edit  d:delete  S:submit  Esc:close ",
+                self.pending_comments.len()
+            )
+        };
         let block = Block::default()
             .title(title)
             .borders(Borders::ALL)
@@ -3667,10 +5822,11 @@ impl App {
             return;
         }
 
-        // Render each comment
+        // Render each comment, with the selected one's suggested change below it
+        let bottom = inner_area.y + inner_area.height;
         let mut y = inner_area.y;
         for (i, comment) in self.pending_comments.iter().enumerate() {
-            if y >= inner_area.y + inner_area.height {
+            if y >= bottom {
                 break;
             }
 
@@ -3687,38 +5843,36 @@ impl App {
             }
 
             // Comment number and type indicator
-            let type_indicator = if comment.is_inline() {
-                let path = comment.file_path.as_ref().unwrap();
-                let line = comment.line_number.unwrap();
+            let type_indicator = if let Some(target) = InlineTarget::of(comment) {
+                let path = &target.path;
                 // Shorten path if too long
                 let short_path: String = if path.len() > 20 {
                     format!("...{}", &path[path.len() - 17..])
                 } else {
                     path.clone()
                 };
-                if let Some(start_line) = comment.start_line {
-                    format!("{}. [{}:{}-{}] ", i + 1, short_path, start_line, line)
-                } else {
-                    format!("{}. [{}:{}] ", i + 1, short_path, line)
-                }
+                format!("{}. [{}{}] ", i + 1, short_path, target.line_suffix())
             } else {
                 format!("{}. [General] ", i + 1)
             };
             buf.set_string(inner_area.x, y, &type_indicator, style.fg(Color::Cyan));
 
-            // Comment preview (first line, truncated)
+            // Comment preview (first line, truncated); a suggestion block is summarized
+            let suggestion = helpers::split_suggestion(&comment.body);
+            let (first_line, has_more_lines) = match &suggestion {
+                Some((before, _, after)) => {
+                    let text = before.lines().chain(after.lines()).find(|l| !l.trim().is_empty());
+                    (text.unwrap_or("Suggested change"), text.is_some())
+                }
+                None => (
+                    comment.body.lines().next().unwrap_or(""),
+                    comment.body.lines().count() > 1,
+                ),
+            };
             let available_width =
                 (inner_area.width as usize).saturating_sub(type_indicator.len() + 2);
-            let preview: String = comment
-                .body
-                .lines()
-                .next()
-                .unwrap_or("")
-                .chars()
-                .take(available_width)
-                .collect();
+            let preview: String = first_line.chars().take(available_width).collect();
 
-            let has_more_lines = comment.body.lines().count() > 1;
             let display = if has_more_lines && preview.len() >= available_width.saturating_sub(3) {
                 format!("{}...", &preview[..preview.len().saturating_sub(3)])
             } else if has_more_lines {
@@ -3733,8 +5887,39 @@ impl App {
                 &display,
                 style,
             );
-
             y += 1;
+
+            if let (true, Some((_, suggested, _))) = (is_selected, &suggestion) {
+                // Suggestions replace lines of the new version only
+                let on_new_side = comment.side == DiffSide::Right
+                    && (comment.start_line.is_none() || comment.start_side == DiffSide::Right);
+                let location = comment
+                    .file_path
+                    .as_deref()
+                    .zip(comment.line_number)
+                    .filter(|_| on_new_side)
+                    .map(|(path, line)| (path, comment.start_line.unwrap_or(line), line));
+                for (marker, line) in self.suggestion_rows(location, suggested) {
+                    if y >= bottom {
+                        break;
+                    }
+                    let row: String = format!("   {} {}", marker, line)
+                        .chars()
+                        .take(inner_area.width as usize)
+                        .collect();
+                    buf.set_string(inner_area.x, y, &row, Self::suggestion_row_style(marker, Color::Rgb(40, 40, 50)));
+                    y += 1;
+                }
+            }
+        }
+    }
+
+    /// Style of a suggestion mini-diff row
+    fn suggestion_row_style(marker: char, bg: Color) -> Style {
+        match marker {
+            '+' => Style::default().fg(Color::Green).bg(bg),
+            '-' => Style::default().fg(Color::Red).bg(bg),
+            _ => Style::default().fg(Color::DarkGray).bg(bg),
         }
     }
 
@@ -3750,19 +5935,24 @@ impl App {
         let popup_height = (area.height * 3 / 4).min(30);
         let popup_area = Self::centered_popup(area, popup_width, popup_height);
 
-        let current_count = self.comment_threads.iter().filter(|t| !t.outdated).count();
-        let outdated_count = self.comment_threads.iter().filter(|t| t.outdated).count();
-        let title = if outdated_count > 0 {
-            format!(
-                " Comment Threads ({} current, {} outdated) - j/k:nav  Enter:view  r:reply  g:goto  q/Esc:close ",
-                current_count, outdated_count
-            )
+        let listed = |t: &&CommentThread| self.thread_listed(t);
+        let current_count = self.comment_threads.iter().filter(listed).filter(|t| !t.outdated).count();
+        let outdated_count = self.comment_threads.iter().filter(listed).filter(|t| t.outdated).count();
+        let resolved_count = self.comment_threads.iter().filter(|t| t.resolved).count();
+
+        let mut counts = if outdated_count > 0 {
+            format!("{} current, {} outdated", current_count, outdated_count)
         } else {
-            format!(
-                " Comment Threads ({}) - j/k:nav  Enter:view  r:reply  g:goto  q/Esc:close ",
-                current_count
-            )
+            current_count.to_string()
         };
+        if resolved_count > 0 && !self.show_resolved_threads {
+            counts.push_str(&format!(", {} resolved hidden", resolved_count));
+        }
+        let toggle_hint = if self.show_resolved_threads { "a:unresolved" } else { "a:all" };
+        let title = format!(
+            " Comment Threads ({}) - j/k:nav  Enter:view  r:reply  g:goto  {}  q/Esc:close ",
+            counts, toggle_hint
+        );
         let block = Block::default()
             .title(title)
             .borders(Borders::ALL)
@@ -3772,12 +5962,17 @@ impl App {
         Self::clear_popup_background(frame.buffer_mut(), popup_area, Color::Rgb(35, 35, 45));
         frame.render_widget(block, popup_area);
 
-        if self.comment_threads.is_empty() {
+        if current_count + outdated_count == 0 {
+            let message = if self.comment_threads.is_empty() {
+                "No comment threads on this PR"
+            } else {
+                "No unresolved threads (a: show all)"
+            };
             let buf = frame.buffer_mut();
             buf.set_string(
                 inner_area.x,
                 inner_area.y,
-                "No comment threads on this PR",
+                message,
                 Style::default()
                     .fg(Color::DarkGray)
                     .bg(Color::Rgb(35, 35, 45)),
@@ -3816,13 +6011,13 @@ impl App {
             .comment_threads
             .iter()
             .enumerate()
-            .filter(|(_, t)| !t.outdated)
+            .filter(|(_, t)| !t.outdated && self.thread_listed(t))
             .collect();
         let outdated_threads: Vec<(usize, &crate::types::CommentThread)> = self
             .comment_threads
             .iter()
             .enumerate()
-            .filter(|(_, t)| t.outdated)
+            .filter(|(_, t)| t.outdated && self.thread_listed(t))
             .collect();
 
         // Build display list: current section header, current threads, outdated section header, outdated threads
@@ -3908,6 +6103,8 @@ impl App {
                             .unwrap_or(path);
                         if let Some(line) = thread.line {
                             format!("{}:{}", filename, line)
+                        } else if thread.is_file_level() {
+                            format!("{} (file)", filename)
                         } else {
                             filename.to_string()
                         }
@@ -3918,11 +6115,21 @@ impl App {
                     let comment_count = format!(" ({})", thread.comment_count());
                     let author = format!(" @{}", thread.author());
 
+                    // Resolution badge for review threads: ✓ resolved, ○ unresolved
+                    let mut next_x = list_area.x + 1;
+                    if thread.resolved {
+                        buf.set_string(next_x, y, "✓ ", style.fg(Color::Green));
+                        next_x += 2;
+                    } else if thread.is_inline() {
+                        buf.set_string(next_x, y, "○ ", style.fg(Color::Yellow));
+                        next_x += 2;
+                    }
+
                     // Render location
-                    buf.set_string(list_area.x + 1, y, &location, style.fg(Color::Cyan));
+                    buf.set_string(next_x, y, &location, style.fg(Color::Cyan));
 
                     // Render count
-                    let next_x = list_area.x + 1 + location.len() as u16;
+                    let next_x = next_x + location.chars().count() as u16;
                     if next_x < list_area.x + list_area.width {
                         buf.set_string(next_x, y, &comment_count, style.fg(Color::Yellow));
                     }
@@ -3952,7 +6159,11 @@ impl App {
             }
 
             // Preview header
-            let header = format!(" Preview - {} comment(s) ", thread.comment_count());
+            let header = if thread.resolved {
+                format!(" Preview - {} comment(s), resolved ", thread.comment_count())
+            } else {
+                format!(" Preview - {} comment(s) ", thread.comment_count())
+            };
             buf.set_string(
                 preview_area.x,
                 preview_area.y,
@@ -4014,8 +6225,9 @@ impl App {
         frame: &mut ratatui::Frame,
         area: Rect,
         thread_idx: usize,
-        _selected: usize,
         scroll: usize,
+        own_comment: Option<usize>,
+        deleting: bool,
     ) {
         let Some(thread) = self.comment_threads.get(thread_idx) else {
             return;
@@ -4032,6 +6244,8 @@ impl App {
                 .unwrap_or(path);
             if let Some(line) = thread.line {
                 format!("{}:{}", filename, line)
+            } else if thread.is_file_level() {
+                format!("{} (file)", path)
             } else {
                 filename.to_string()
             }
@@ -4039,7 +6253,25 @@ impl App {
             "General Comment".to_string()
         };
 
-        let title = format!(" {} - j/k:scroll  r:reply  q/Esc:back ", location);
+        // Keys for the highlighted comment: reacting needs its node ID, editing needs it to be the user's own
+        let highlighted = own_comment.and_then(|i| thread.comments.get(i));
+        let mut own_keys = String::new();
+        if thread.comments.len() > 1 {
+            own_keys.push_str("  Tab:next");
+        }
+        if highlighted.is_some_and(|c| c.node_id.is_some()) {
+            own_keys.push_str("  +:react");
+        }
+        if highlighted.is_some_and(Self::is_own) {
+            own_keys.push_str("  e:edit  D:delete");
+        }
+        let title = if deleting {
+            " Delete the highlighted comment? y:delete  any other key:cancel ".to_string()
+        } else if thread.resolved {
+            format!(" {} [resolved] - j/k:scroll  r:reply  x:unresolve{}  q/Esc:back ", location, own_keys)
+        } else {
+            format!(" {} - j/k:scroll  r:reply  x:resolve{}  q/Esc:back ", location, own_keys)
+        };
 
         let block = Block::default()
             .title(title)
@@ -4060,21 +6292,58 @@ impl App {
         let body_style = Style::default().fg(Color::White).bg(Color::Rgb(30, 30, 40));
         let code_style = Style::default().fg(Color::Yellow).bg(Color::Rgb(20, 20, 30));
         let separator_style = Style::default().bg(Color::Rgb(30, 30, 40));
+        let reaction_style = Style::default().fg(Color::Rgb(200, 180, 120)).bg(Color::Rgb(30, 30, 40));
+
+        // Lines a suggestion replaces; unknown for outdated threads
+        let location_range = match (&thread.file_path, thread.line) {
+            (Some(path), Some(line)) if !thread.outdated => {
+                Some((path.as_str(), thread.start_line.unwrap_or(line), line))
+            }
+            _ => None,
+        };
 
-        for comment in &thread.comments {
-            // Author and timestamp
+        for (i, comment) in thread.comments.iter().enumerate() {
+            // Author and timestamp; the highlighted comment of the user's own is marked
             let time_ago = helpers::format_relative_time(&comment.created_at);
-            let header = format!("@{} - {}", comment.author, time_ago);
-            all_lines.push((header, header_style));
+            let marker = if own_comment == Some(i) { "▶ " } else { "" };
+            let header = format!("{}@{} - {}", marker, comment.author, time_ago);
+            let style = if own_comment == Some(i) && deleting {
+                header_style.fg(Color::Red)
+            } else {
+                header_style
+            };
+            all_lines.push((header, style));
 
             // Comment body (word-wrapped, with code block detection)
-            let wrapped = helpers::wrap_text_with_code(&comment.body, wrap_width);
-            for (line, is_code) in wrapped {
-                if is_code {
-                    all_lines.push((format!("  │ {}", line), code_style));
-                } else {
-                    all_lines.push((format!(" {}", line), body_style));
+            // A suggestion block is shown as a diff against the lines it replaces
+            let (before, suggestion, after) = match helpers::split_suggestion(&comment.body) {
+                Some((before, suggested, after)) => (before, Some(suggested), after),
+                None => (comment.body.clone(), None, String::new()),
+            };
+            let push_text = |text: &str, all_lines: &mut Vec<(String, Style)>| {
+                if text.trim().is_empty() {
+                    return;
+                }
+                for (line, is_code) in helpers::wrap_text_with_code(text, wrap_width) {
+                    if is_code {
+                        all_lines.push((format!("  │ {}", line), code_style));
+                    } else {
+                        all_lines.push((format!(" {}", line), body_style));
+                    }
                 }
+            };
+            push_text(&before, &mut all_lines);
+            if let Some(suggested) = suggestion {
+                all_lines.push((" Suggested change:".to_string(), body_style));
+                for (marker, line) in self.suggestion_rows(location_range, &suggested) {
+                    let row: String = format!("  {} {}", marker, line).chars().take(wrap_width + 2).collect();
+                    all_lines.push((row, Self::suggestion_row_style(marker, Color::Rgb(20, 20, 30))));
+                }
+            }
+            push_text(&after, &mut all_lines);
+
+            if !comment.reactions.is_empty() {
+                all_lines.push((format!(" {}", helpers::reactions_label(&comment.reactions)), reaction_style));
             }
 
             // Separator (empty line)
@@ -4106,11 +6375,53 @@ impl App {
         }
     }
 
-    fn render_reply_input(&self, frame: &mut ratatui::Frame, area: Rect, text: &str) {
+    /// Reaction picker: GitHub's reactions in a row, the user's own ones highlighted
+    fn render_reaction_picker(&self, frame: &mut ratatui::Frame, picker: ReactionPicker) {
+        let bg = Color::Rgb(35, 35, 50);
+        let own: Vec<ReactionKind> = self
+            .reactions(picker.target)
+            .unwrap_or_default()
+            .iter()
+            .filter(|r| r.by_viewer)
+            .map(|r| r.kind)
+            .collect();
+
+        // Each entry is " n:emoji " (emoji are two columns wide)
+        let entry_width = 7;
+        let popup_width = (ReactionKind::ALL.len() * entry_width) as u16 + 2;
+        let popup_area = Self::centered_popup(frame.area(), popup_width, 3);
+
+        let block = Block::default()
+            .title(" React (h/l, Enter, Esc) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.accent_color()));
+        let inner = block.inner(popup_area);
+        Self::clear_popup_background(frame.buffer_mut(), popup_area, bg);
+        frame.render_widget(block, popup_area);
+
+        let buf = frame.buffer_mut();
+        for (i, kind) in ReactionKind::ALL.iter().enumerate() {
+            let mut style = Style::default().fg(Color::Rgb(160, 160, 180)).bg(bg);
+            if own.contains(kind) {
+                style = style.fg(Color::Green).add_modifier(Modifier::BOLD);
+            }
+            if i == picker.selected {
+                style = style.bg(Color::Rgb(70, 70, 100));
+            }
+            let x = inner.x + (i * entry_width) as u16;
+            buf.set_string(x, inner.y, format!(" {}:{}  ", i + 1, kind.emoji()), style);
+        }
+    }
+
+    fn render_reply_input(&self, frame: &mut ratatui::Frame, area: Rect, text: &str, editing: bool) {
         let popup_width = (area.width * 2 / 3).min(80);
         let popup_area = Self::centered_popup(area, popup_width, 10);
 
-        let title = " Reply (Ctrl+S to send, Esc to cancel) ";
+        let title = if editing {
+            " Edit comment (Ctrl+S to save, Esc to cancel) "
+        } else {
+            " Reply (Ctrl+S to send, Esc to cancel) "
+        };
         let block = Block::default()
             .title(title)
             .borders(Borders::ALL)
@@ -4210,6 +6521,141 @@ impl App {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn render_merge_modal(
+        &self,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        info: &MergeInfo,
+        method: usize,
+        title: &str,
+        body: &str,
+        editing: Option<MergeField>,
+        delete_branch: bool,
+        auto_merge: bool,
+    ) {
+        let bg = Color::Rgb(35, 35, 50);
+        let popup_width = (area.width * 2 / 3).min(80);
+        let popup_area = Self::centered_popup(area, popup_width, 21);
+
+        let border_color = if editing.is_some() { Color::Green } else { Color::Cyan };
+        let block = Block::default()
+            .title(" Merge PR (Ctrl+Enter or Ctrl+S to merge, Esc to cancel) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+        let inner = block.inner(popup_area);
+        Self::clear_popup_background(frame.buffer_mut(), popup_area, bg);
+        frame.render_widget(block, popup_area);
+
+        let buf = frame.buffer_mut();
+        let width = inner.width as usize;
+        let dim = Style::default().fg(Color::DarkGray).bg(bg);
+        let mut y = inner.y;
+
+        // Mergeability first: it decides what the rest of the dialog can do
+        let status_color = match info.status {
+            crate::types::MergeStatus::Clean => Color::Green,
+            crate::types::MergeStatus::Unstable
+            | crate::types::MergeStatus::Blocked
+            | crate::types::MergeStatus::Behind
+            | crate::types::MergeStatus::Unknown => Color::Yellow,
+            crate::types::MergeStatus::Conflicting | crate::types::MergeStatus::Draft => Color::Red,
+        };
+        let mut status = info.status.description().to_string();
+        if info.auto_merge_enabled {
+            status.push_str(" (auto-merge enabled)");
+        }
+        buf.set_string(inner.x, y, helpers::truncate_or_pad(&status, width), Style::default().fg(status_color).bg(bg));
+        y += 2;
+
+        // Merge methods the repository allows
+        for (i, &merge_method) in info.methods.iter().enumerate() {
+            let selected = i == method;
+            let style = if selected && editing.is_none() {
+                Style::default().fg(Color::Green).bg(bg).add_modifier(Modifier::BOLD)
+            } else {
+                dim
+            };
+            let prefix = if selected { "> " } else { "  " };
+            buf.set_string(inner.x, y, format!("{}[{}] {}", prefix, i + 1, merge_method.label()), style);
+            y += 1;
+        }
+        y += 1;
+
+        // Commit title and message
+        let has_message = info.methods.get(method).is_some_and(|m| m.has_commit_message());
+        let field_style = |field: MergeField| {
+            if editing == Some(field) {
+                Style::default().fg(Color::White).bg(Color::Rgb(30, 40, 30))
+            } else {
+                Style::default().fg(Color::Gray).bg(Color::Rgb(30, 30, 40))
+            }
+        };
+        if has_message {
+            buf.set_string(inner.x, y, "Title (t to edit):", dim);
+            y += 1;
+            let mut title = title.to_string();
+            if editing == Some(MergeField::Title) {
+                title.push('_');
+            }
+            buf.set_string(inner.x, y, helpers::truncate_or_pad(&title, width), field_style(MergeField::Title));
+            y += 1;
+
+            buf.set_string(inner.x, y, "Message (c to edit):", dim);
+            y += 1;
+            let mut lines: Vec<String> = body
+                .split('\n')
+                .flat_map(|line| {
+                    if line.is_empty() {
+                        vec![String::new()]
+                    } else {
+                        helpers::wrap_text(line, width.saturating_sub(1))
+                    }
+                })
+                .collect();
+            if editing == Some(MergeField::Body)
+                && let Some(last) = lines.last_mut()
+            {
+                last.push('_');
+            }
+            // Show the end of long messages while typing
+            let body_height = 5;
+            let skip = if editing == Some(MergeField::Body) { lines.len().saturating_sub(body_height) } else { 0 };
+            for row in 0..body_height {
+                let line = lines.get(skip + row).map(String::as_str).unwrap_or_default();
+                buf.set_string(inner.x, y, helpers::truncate_or_pad(line, width), field_style(MergeField::Body));
+                y += 1;
+            }
+        } else {
+            buf.set_string(inner.x, y, "Commits are rebased onto the base branch as they are", dim);
+            y += 7;
+        }
+        y += 1;
+
+        // Options
+        let checkbox = |on: bool| if on { "[x]" } else { "[ ]" };
+        if info.deletes_branch_on_merge {
+            let label = format!("Branch {} is deleted after merging (repository setting)", info.head_ref);
+            buf.set_string(inner.x, y, helpers::truncate_or_pad(&label, width), dim);
+            y += 1;
+        } else if info.can_delete_branch && !auto_merge {
+            let label = format!("{} Delete branch {} (d)", checkbox(delete_branch), info.head_ref);
+            buf.set_string(inner.x, y, helpers::truncate_or_pad(&label, width), Style::default().fg(Color::Gray).bg(bg));
+            y += 1;
+        }
+        if info.auto_merge_allowed {
+            let label = format!("{} Merge automatically once requirements are met (a)", checkbox(auto_merge));
+            buf.set_string(inner.x, y, helpers::truncate_or_pad(&label, width), Style::default().fg(Color::Gray).bg(bg));
+        }
+
+        let hint = if editing.is_some() {
+            "Type text | Esc: done"
+        } else {
+            "j/k or 1-3: method | t: title | c: message | Ctrl+Enter: merge"
+        };
+        buf.set_string(inner.x, popup_area.y + popup_area.height - 2, hint, dim);
+    }
+
     fn render_review_main(
         &self,
         frame: &mut ratatui::Frame,
@@ -4345,6 +6791,23 @@ impl App {
             );
         }
 
+        // Approving while required checks fail or are still running
+        if selected_action == 0
+            && let Some(Ok(ref checks)) = self.checks
+        {
+            let failing = checks.iter().filter(|c| c.is_blocking() && c.state == CheckState::Failure).count();
+            let pending = checks.iter().filter(|c| c.is_blocking() && c.state == CheckState::Pending).count();
+            let warning = match (failing, pending) {
+                (0, 0) => None,
+                (f, 0) => Some(format!("⚠ {} required check(s) failing", f)),
+                (0, p) => Some(format!("⚠ {} required check(s) pending", p)),
+                (f, p) => Some(format!("⚠ {} required check(s) failing, {} pending", f, p)),
+            };
+            if let Some(warning) = warning {
+                buf.set_string(inner_area.x, drafts_y + 1, &warning, Style::default().fg(Color::Red));
+            }
+        }
+
         // Instructions at bottom
         let instructions = if editing_body {
             "Type comment | Esc: back | Ctrl+Enter: submit"
@@ -4427,13 +6890,9 @@ impl App {
             let prefix = if is_selected { "> " } else { "  " };
 
             // Show location for inline comments
-            let location = if let (Some(path), Some(line)) = (&draft.file_path, draft.line_number) {
-                let filename = path.rsplit('/').next().unwrap_or(path);
-                if let Some(start) = draft.start_line {
-                    format!("{}:{}-{}", filename, start, line)
-                } else {
-                    format!("{}:{}", filename, line)
-                }
+            let location = if let Some(target) = InlineTarget::of(draft) {
+                let filename = target.path.rsplit('/').next().unwrap_or(&target.path);
+                format!("{}{}", filename, target.line_suffix())
             } else {
                 "general".to_string()
             };
@@ -4793,12 +7252,37 @@ impl App {
         };
 
         let focus_indicator = if is_focused { "▶ " } else { "" };
+        // Line counts reported by the host, when it sent them with the review state
+        // They cover the whole PR, so they're left out while the changes since the last review are shown
+        let stats = self
+            .file_stats
+            .iter()
+            .filter(|_| self.since_review.is_none())
+            .find(|s| s.path == file.path)
+            .map(|s| format!(" +{} -{}", s.additions, s.deletions))
+            .unwrap_or_default();
+        // Comments on the whole file have no line to show them at
+        let file_comments = self
+            .comment_threads
+            .iter()
+            .filter(|t| t.is_file_level() && t.file_path.as_deref() == Some(&file.path))
+            .count()
+            + self
+                .pending_comments
+                .iter()
+                .filter(|c| c.is_file_level() && c.file_path.as_deref() == Some(&file.path))
+                .count();
+        let stats = if file_comments > 0 {
+            format!("{} [{} on file]", stats, file_comments)
+        } else {
+            stats
+        };
         let title = if self.collapsed.contains(&self.selected_file) {
-            format!(" {}{} [collapsed] ", focus_indicator, file.path)
+            format!(" {}{}{} [collapsed] ", focus_indicator, file.path, stats)
         } else if self.view_mode == ViewMode::Split {
-            format!(" {}{} [split] ", focus_indicator, file.path)
+            format!(" {}{}{} [split] ", focus_indicator, file.path, stats)
         } else {
-            format!(" {}{} ", focus_indicator, file.path)
+            format!(" {}{}{} ", focus_indicator, file.path, stats)
         };
 
         let block = Block::default()
@@ -4821,6 +7305,13 @@ impl App {
             ViewMode::Split => self.render_split_direct(frame.buffer_mut(), inner_area, file),
         }
 
+        if let Some((path, line, DiffSide::Right)) = self.get_cursor_line_info() {
+            let annotations = self.annotations_at_line(&path, line);
+            if !annotations.is_empty() {
+                self.render_annotations(frame.buffer_mut(), inner_area, &annotations);
+            }
+        }
+
         // Scrollbar
         let total_lines = file.line_count() + file.hunks.len();
         if total_lines > inner_area.height as usize {
@@ -4837,6 +7328,59 @@ impl App {
         }
     }
 
+    /// Annotations of the cursor line at the bottom of the diff: one line each, or their full
+    /// messages once expanded
+    fn render_annotations(&self, buf: &mut Buffer, area: Rect, annotations: &[(&str, &CheckAnnotation)]) {
+        let bg = Color::Rgb(40, 35, 30);
+        let width = area.width.saturating_sub(2) as usize;
+
+        let mut rows: Vec<(String, Style)> = Vec::new();
+        for (check, annotation) in annotations {
+            let color = match annotation.level {
+                AnnotationLevel::Failure => Color::Red,
+                AnnotationLevel::Warning => Color::Yellow,
+                AnnotationLevel::Notice => Color::Rgb(120, 160, 230),
+            };
+            let headline = annotation
+                .title
+                .as_deref()
+                .unwrap_or_else(|| annotation.message.lines().next().unwrap_or_default());
+            let range = if annotation.end_line > annotation.start_line {
+                format!(" (lines {}-{})", annotation.start_line, annotation.end_line)
+            } else {
+                String::new()
+            };
+            rows.push((
+                format!("{}: {}{}", check, headline, range),
+                Style::default().fg(color).bg(bg).add_modifier(Modifier::BOLD),
+            ));
+            if self.expand_annotations {
+                for line in annotation.message.lines() {
+                    for wrapped in helpers::wrap_text(line, width.saturating_sub(2)) {
+                        rows.push((format!("  {}", wrapped), Style::default().fg(Color::Rgb(220, 210, 200)).bg(bg)));
+                    }
+                }
+            }
+        }
+        if !self.expand_annotations
+            && let Some(last) = rows.last_mut()
+        {
+            last.0.push_str("  (E: expand)");
+        }
+
+        // At most half the diff
+        let max_rows = (area.height / 2).max(1) as usize;
+        rows.truncate(max_rows);
+        let top = area.y + area.height - rows.len() as u16;
+        for (i, (text, style)) in rows.iter().enumerate() {
+            let y = top + i as u16;
+            for x in area.x..area.x + area.width {
+                buf.set_string(x, y, " ", Style::default().bg(bg));
+            }
+            buf.set_string(area.x + 1, y, helpers::truncate_or_pad(text, width), *style);
+        }
+    }
+
     fn render_unified_direct(&self, buf: &mut Buffer, area: Rect, file: &DiffFile) {
         let mut lines: Vec<DiffDisplayLine> = Vec::new();
 
@@ -4996,13 +7540,8 @@ impl App {
 
                     // Comment thread indicator
                     let indicator_x = area.x + gutter_width as u16;
-                    let has_comment = new_ln
-                        .map(|ln| self.has_threads_at_line(&file.path, ln))
-                        .unwrap_or(false);
-                    if has_comment {
-                        let count = new_ln
-                            .map(|ln| self.thread_count_at_line(&file.path, ln))
-                            .unwrap_or(0);
+                    let count = self.thread_count_at_diff_line(&file.path, *old_ln, *new_ln);
+                    if count > 0 {
                         let indicator = if count > 1 {
                             format!("{}", count)
                         } else {
@@ -5015,6 +7554,12 @@ impl App {
                         buf.set_string(indicator_x, y, &indicator, indicator_style);
                     }
 
+                    // Check annotation indicator
+                    if let Some((marker, color)) = new_ln.and_then(|ln| self.annotation_marker(&file.path, ln)) {
+                        let marker_style = Style::default().fg(color).bg(bg).add_modifier(Modifier::BOLD);
+                        buf.set_string(indicator_x + 1, y, marker, marker_style);
+                    }
+
                     let content_start_x = area.x + gutter_width as u16 + 2; // +2 for indicator space
                     let max_x = area.x + area.width;
 
@@ -5155,6 +7700,7 @@ impl App {
                 left_area.width,
                 left.as_ref(),
                 &file.path,
+                DiffSide::Left,
             );
             self.render_split_line(
                 buf,
@@ -5163,10 +7709,27 @@ impl App {
                 right_area.width,
                 right.as_ref(),
                 &file.path,
+                DiffSide::Right,
             );
+
+            // Check annotations are on lines of the new version
+            let new_line = match right {
+                Some(SplitLine::Add { ln, .. }) => Some((*ln, self.add_bg())),
+                Some(SplitLine::Context { ln, .. }) => Some((*ln, self.bg_color())),
+                _ => None,
+            };
+            if let Some((ln, bg)) = new_line
+                && let Some((marker, color)) = self.annotation_marker(&file.path, ln)
+            {
+                let gutter_len = if self.config.display.show_line_numbers { 5 } else { 0 };
+                let marker_style = Style::default().fg(color).bg(bg).add_modifier(Modifier::BOLD);
+                buf.set_string(right_area.x + gutter_len + 1, y, marker, marker_style);
+            }
         }
     }
 
+    /// Render one pane of a split row; `side` is the diff side the pane shows
+    #[allow(clippy::too_many_arguments)]
     fn render_split_line(
         &self,
         buf: &mut Buffer,
@@ -5175,6 +7738,7 @@ impl App {
         width: u16,
         line: Option<&SplitLine>,
         path: &str,
+        side: DiffSide,
     ) {
         let max_x = x + width;
 
@@ -5198,13 +7762,13 @@ impl App {
                 );
             }
             Some(SplitLine::Del { ln, content }) => {
-                self.render_split_content_line(buf, x, y, width, *ln, content, self.del_bg(), path);
+                self.render_split_content_line(buf, x, y, width, *ln, content, self.del_bg(), path, side);
             }
             Some(SplitLine::Add { ln, content }) => {
-                self.render_split_content_line(buf, x, y, width, *ln, content, self.add_bg(), path);
+                self.render_split_content_line(buf, x, y, width, *ln, content, self.add_bg(), path, side);
             }
             Some(SplitLine::Context { ln, content }) => {
-                self.render_split_content_line(buf, x, y, width, *ln, content, self.bg_color(), path);
+                self.render_split_content_line(buf, x, y, width, *ln, content, self.bg_color(), path, side);
             }
         }
     }
@@ -5220,6 +7784,7 @@ impl App {
         content: &str,
         bg: Color,
         path: &str,
+        side: DiffSide,
     ) {
         let max_x = x + width;
 
@@ -5238,8 +7803,8 @@ impl App {
 
         // Comment thread indicator
         let indicator_x = x + gutter_len;
-        if self.has_threads_at_line(path, ln) {
-            let count = self.thread_count_at_line(path, ln);
+        let count = self.thread_count_at_line(path, ln, side);
+        if count > 0 {
             let indicator = if count > 1 {
                 format!("{}", count)
             } else {
@@ -1,5 +1,8 @@
 //! UI type definitions for the application state machine.
 
+use crate::config::TabConfig;
+use crate::types::{DiffSide, MergeInfo, MetadataField, PendingComment, PrList, PrMetadata, ReviewPr};
+
 /// A node in the file tree (either a folder or a file)
 #[derive(Debug, Clone)]
 pub enum TreeNode {
@@ -68,16 +71,204 @@ pub enum LoadingState {
 pub enum PrListTab {
     ForReview,
     MyPrs,
+    /// Notifications about PRs (GitHub)
+    Inbox,
+    /// A saved search, by index into `App::search_tabs`
+    Search(usize),
+}
+
+/// Lists fetched by a PR list refresh
+pub struct PrListRefresh {
+    pub review: PrList,
+    pub my: PrList,
+    /// One result per saved search tab
+    pub searches: Vec<Result<PrList, String>>,
+    pub inbox: Result<PrList, String>,
+}
+
+/// A PR list beyond the two built-in ones (inbox, saved searches) and its selection
+#[derive(Debug, Clone, Default)]
+pub struct PrListState {
+    pub prs: Vec<ReviewPr>,
+    pub selected: usize,
+    pub scroll: usize,
+    pub filtered_indices: Vec<usize>,
+    /// Cut short by the pr_list.max_prs cap
+    pub truncated: bool,
+    /// Why the last fetch failed, shown when there are no results
+    pub error: Option<String>,
+}
+
+impl PrListState {
+    /// Replace the results, keeping the selected PR when it is still listed
+    pub fn set_results(&mut self, list: PrList) {
+        let selected = self
+            .prs
+            .get(self.selected)
+            .map(|pr| (pr.repo_full_name(), pr.number));
+        self.prs = list.prs;
+        self.prs.sort_by(|a, b| {
+            a.repo_full_name()
+                .cmp(&b.repo_full_name())
+                .then_with(|| b.number.cmp(&a.number))
+        });
+        self.truncated = list.truncated;
+        self.error = None;
+        self.filtered_indices = (0..self.prs.len()).collect();
+        self.selected = selected
+            .and_then(|(repo, number)| {
+                self.prs
+                    .iter()
+                    .position(|pr| pr.repo_full_name() == repo && pr.number == number)
+            })
+            .unwrap_or(0);
+        self.scroll = self.scroll.min(self.selected);
+    }
+
+    /// Record the result of a fetch; a failed one keeps the previous results
+    pub fn apply(&mut self, result: Result<PrList, String>) {
+        match result {
+            Ok(list) => self.set_results(list),
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+/// A saved search from `[[tabs]]` and its results
+#[derive(Debug, Clone)]
+pub struct SearchTab {
+    pub name: String,
+    pub query: String,
+    pub list: PrListState,
+}
+
+impl SearchTab {
+    pub fn new(config: &TabConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            query: config.query.clone(),
+            list: PrListState::default(),
+        }
+    }
+}
+
+/// Lines of a file an inline comment is anchored to, or the whole file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineTarget {
+    pub path: String,
+    /// Last (or only) line and its side; None for a file-level comment
+    pub line: Option<(u32, DiffSide)>,
+    /// First line and its side, for multi-line comments
+    pub start: Option<(u32, DiffSide)>,
+}
+
+impl InlineTarget {
+    /// The whole file
+    pub fn file(path: String) -> Self {
+        Self { path, line: None, start: None }
+    }
+
+    /// Target of an existing draft (None for general comments)
+    pub fn of(comment: &PendingComment) -> Option<Self> {
+        Some(Self {
+            path: comment.file_path.clone()?,
+            line: comment.line_number.map(|line| (line, comment.side)),
+            start: comment.start_line.map(|start| (start, comment.start_side)),
+        })
+    }
+
+    /// A draft anchored here
+    pub fn into_comment(self, body: String) -> PendingComment {
+        let Some((line, side)) = self.line else {
+            return PendingComment::new_file(body, self.path);
+        };
+        match self.start {
+            Some((start, start_side)) => {
+                PendingComment::new_multiline(body, self.path, start, line).with_start_side(start_side)
+            }
+            None => PendingComment::new_inline(body, self.path, line),
+        }
+        .with_side(side)
+    }
+
+    /// Line numbers as shown after the path, e.g. ":12", ":5-7", or ":L5-R7" once old lines are
+    /// involved (the L/R notation GitHub uses in diff links); empty for file-level comments
+    pub fn line_suffix(&self) -> String {
+        let Some((line, side)) = self.line else {
+            return String::new();
+        };
+        let sides = [Some(side), self.start.map(|(_, side)| side)];
+        let mark = |side: DiffSide| match side {
+            _ if !sides.contains(&Some(DiffSide::Left)) => "",
+            DiffSide::Left => "L",
+            DiffSide::Right => "R",
+        };
+        match self.start {
+            Some((start, start_side)) => {
+                format!(":{}{}-{}{}", mark(start_side), start, mark(side), line)
+            }
+            None => format!(":{}{}", mark(side), line),
+        }
+    }
+}
+
+/// What a reaction chosen in the picker goes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionTarget {
+    Pr,
+    Comment { thread: usize, comment: usize },
+}
+
+/// The reaction picker: its target and the highlighted entry of `ReactionKind::ALL`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReactionPicker {
+    pub target: ReactionTarget,
+    pub selected: usize,
+}
+
+/// The reviewers, assignees and labels editor
+#[derive(Debug, Clone)]
+pub struct MetadataEditor {
+    /// As loaded from the host, to apply only what changed
+    pub original: PrMetadata,
+    pub edited: PrMetadata,
+    pub field: MetadataField,
+    /// Highlighted entry of the current field
+    pub selected: usize,
+    /// Text typed to find an entry to add, while adding
+    pub query: Option<String>,
+    /// Highlighted completion for the query
+    pub completion: usize,
+}
+
+impl MetadataEditor {
+    pub fn new(metadata: PrMetadata) -> Self {
+        Self {
+            original: metadata.clone(),
+            edited: metadata,
+            field: MetadataField::Reviewers,
+            selected: 0,
+            query: None,
+            completion: 0,
+        }
+    }
+}
+
+/// Text field being edited in the merge dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeField {
+    Title,
+    Body,
 }
 
 /// Comment input mode
 #[derive(Clone, PartialEq, Eq)]
 pub enum CommentMode {
     None,
-    /// Editing a comment: (text, optional (file_path, start_line, end_line) for inline)
+    /// Editing a comment: text and, for inline and file-level comments, where it is anchored
     Editing {
         text: String,
-        inline_context: Option<(String, u32, Option<u32>)>, // (path, end_line, optional start_line)
+        inline_context: Option<InlineTarget>,
     },
     ViewingPending, // Viewing list of pending comments
     /// Viewing list of existing comment threads
@@ -90,11 +281,14 @@ pub enum CommentMode {
         index: usize,
         selected: usize,
         scroll: usize,
+        comment: Option<usize>, // Highlighted comment, to react to (or edit/delete if the user's own)
+        deleting: bool,         // Asking to confirm deletion of the highlighted comment
     },
-    /// Composing a reply to a thread
+    /// Composing a reply to a thread, or editing one of its comments
     ReplyingToThread {
         index: usize,
         text: String,
+        editing: Option<usize>, // Index of the comment being edited
     },
     /// Submitting a PR review (approve/request changes/comment)
     SubmittingReview {
@@ -105,6 +299,16 @@ pub enum CommentMode {
         selected_draft: usize,  // Which draft is selected (when reviewing_drafts)
         editing_draft: bool,    // True when editing selected draft text
     },
+    /// Merging the PR (or enabling auto-merge)
+    Merging {
+        info: MergeInfo,
+        method: usize,               // Index into info.methods
+        title: String,               // Commit title
+        body: String,                // Commit message
+        editing: Option<MergeField>, // Text field being typed into
+        delete_branch: bool,
+        auto_merge: bool,
+    },
 }
 
 /// Help display state